@@ -0,0 +1,77 @@
+//! Compares counting from concurrent threads into a single shared atomic
+//! with counting into a `ShardedCounter`, as the synchronizer workers and
+//! request handlers do on the Prometheus backend.
+//!
+//! Run with `cargo bench --bench metrics_contention`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use solana_block_monitor::metrics::{Metrics, PrometheusMetrics, ShardedCounter, names};
+use solana_block_monitor::synchronizer::WORKERS_COUNT;
+
+const ADDS_PER_THREAD: u64 = 5_000_000;
+
+/// Runs `add` `ADDS_PER_THREAD` times on each of `threads` threads at once.
+fn run(threads: usize, add: impl Fn() + Send + Sync + 'static) -> Duration {
+    let add = Arc::new(add);
+    let started = Instant::now();
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let add = Arc::clone(&add);
+            thread::spawn(move || {
+                for _ in 0..ADDS_PER_THREAD {
+                    add();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    started.elapsed()
+}
+
+fn report(name: &str, threads: usize, elapsed: Duration) {
+    let adds = threads as u64 * ADDS_PER_THREAD;
+    println!(
+        "{:<28} {:>2} threads  {:>8.2?}  {:>6.2} ns/add",
+        name,
+        threads,
+        elapsed,
+        elapsed.as_nanos() as f64 / adds as f64
+    );
+}
+
+fn main() {
+    for threads in [1, WORKERS_COUNT, 2 * WORKERS_COUNT] {
+        let shared = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&shared);
+        let elapsed = run(threads, move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(
+            shared.load(Ordering::Relaxed),
+            threads as u64 * ADDS_PER_THREAD
+        );
+        report("shared AtomicU64", threads, elapsed);
+
+        let sharded = Arc::new(ShardedCounter::new());
+        let counter = Arc::clone(&sharded);
+        let elapsed = run(threads, move || counter.add(1));
+        assert_eq!(sharded.sum(), threads as u64 * ADDS_PER_THREAD);
+        report("ShardedCounter", threads, elapsed);
+
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let recorder = Arc::clone(&metrics);
+        let elapsed = run(threads, move || {
+            recorder.increment(names::INTERVAL_SLOTS, &[], 1)
+        });
+        let expected = format!("interval_slots_total {}", threads as u64 * ADDS_PER_THREAD);
+        assert!(metrics.render().contains(&expected));
+        report("PrometheusMetrics interval", threads, elapsed);
+        println!();
+    }
+}
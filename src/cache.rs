@@ -1,64 +1,497 @@
-use scc::HashCache;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::SystemTime;
 use tracing::{debug, info, warn};
 
-/// A simple in-memory cache for storing confirmed block numbers.
+/// Commitment level at which a cached block was observed.
 ///
-/// This implementation uses a concurrent hash map (scc::HashCache) for:
-/// - Fast implementation: Uses existing concurrent hash map implementation
-/// - Good performance: O(1) lookups and inserts
-/// - Thread safety: Built-in concurrent access support
-/// - Memory efficiency: Only stores block numbers as keys
+/// Levels are ordered, so a `Finalized` entry also satisfies a `Confirmed`
+/// lookup but not the other way around.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommitmentLevel {
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+/// Metadata stored alongside a confirmed block.
+///
+/// Fields are optional because most confirmations come from `getBlocks`,
+/// which only reports slot numbers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockMetadata {
+    pub block_time: Option<i64>,
+    pub block_height: Option<u64>,
+    pub commitment: CommitmentLevel,
+}
+
+impl BlockMetadata {
+    pub fn with_commitment(commitment: CommitmentLevel) -> Self {
+        Self {
+            commitment,
+            ..Self::default()
+        }
+    }
+
+    fn merge(&mut self, update: BlockMetadata) {
+        // Commitment only ever moves forward; a late confirmed-level update
+        // must not downgrade a finalized entry.
+        self.commitment = self.commitment.max(update.commitment);
+        if update.block_time.is_some() {
+            self.block_time = update.block_time;
+        }
+        if update.block_height.is_some() {
+            self.block_height = update.block_height;
+        }
+    }
+}
+
+struct LruEntry {
+    metadata: BlockMetadata,
+    /// Tick the entry is filed under in the recency index.
+    indexed_tick: u64,
+    /// Tick of the latest access. Lookups only raise this, under the read
+    /// lock; the recency index catches up when eviction reaches the entry.
+    last_access: AtomicU64,
+    inserted_at: SystemTime,
+}
+
+/// Called with the block number of every entry evicted under capacity
+/// pressure.
+pub type EvictionCallback = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Approximate bytes used per cached block: the hash map slot (key, entry
+/// and control byte) plus the recency index entry (tick and key). Allocator
+/// and load-factor overhead is not included, so treat it as a lower bound.
+pub const ENTRY_SIZE_BYTES: usize =
+    size_of::<u64>() + size_of::<LruEntry>() + 1 + 2 * size_of::<u64>();
+
+/// Entries plus a recency index ordered by the tick each entry was filed
+/// under, which may be older than its latest access.
+#[derive(Default)]
+struct LruState {
+    entries: HashMap<u64, LruEntry>,
+    recency: BTreeMap<u64, u64>,
+    next_tick: AtomicU64,
+}
+
+impl LruState {
+    fn next_tick(&self) -> u64 {
+        self.next_tick.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Returns the entry for `block_number`, marking it most recently used.
+    /// Needs only shared access, so lookups can run under the read lock.
+    fn touch(&self, block_number: u64) -> Option<&LruEntry> {
+        let entry = self.entries.get(&block_number)?;
+        entry
+            .last_access
+            .fetch_max(self.next_tick(), Ordering::Relaxed);
+        Some(entry)
+    }
+
+    /// Like `touch`, for callers holding the write lock that update the entry.
+    fn touch_mut(&mut self, block_number: u64) -> Option<&mut LruEntry> {
+        let tick = self.next_tick();
+        let entry = self.entries.get_mut(&block_number)?;
+        *entry.last_access.get_mut() = tick;
+        Some(entry)
+    }
+
+    /// Inserts a new entry, evicting the least recently used one if the
+    /// cache is full. Returns the evicted block number, if any.
+    fn insert_new(
+        &mut self,
+        block_number: u64,
+        metadata: BlockMetadata,
+        capacity: usize,
+    ) -> Option<u64> {
+        let evicted = if self.entries.len() >= capacity {
+            self.pop_oldest()
+        } else {
+            None
+        };
+
+        let tick = self.next_tick();
+        self.recency.insert(tick, block_number);
+        let entry = LruEntry {
+            metadata,
+            indexed_tick: tick,
+            last_access: AtomicU64::new(tick),
+            inserted_at: SystemTime::now(),
+        };
+        self.entries.insert(block_number, entry);
+        evicted
+    }
+
+    /// Evicts the least recently used entry. Entries accessed since they
+    /// were filed are re-filed under their latest access on the way, so the
+    /// first one found still filed under its latest access is the oldest.
+    fn pop_oldest(&mut self) -> Option<u64> {
+        while let Some((tick, block_number)) = self.recency.pop_first() {
+            let Some(entry) = self.entries.get_mut(&block_number) else {
+                continue;
+            };
+            let last_access = *entry.last_access.get_mut();
+            if last_access != tick {
+                entry.indexed_tick = last_access;
+                self.recency.insert(last_access, block_number);
+                continue;
+            }
+            self.entries.remove(&block_number);
+            return Some(block_number);
+        }
+        None
+    }
+}
+
+/// A simple in-memory LRU cache for storing confirmed block numbers.
+///
+/// This implementation uses a hash map guarded by a read-write lock, plus a
+/// recency index keyed by a monotonically increasing access tick:
+/// - Shared reads: Lookups take the read lock and promote an entry by
+///   raising its atomic access tick, so HTTP lookups run in parallel and
+///   only wait for inserts and evictions
+/// - LRU eviction: The recency index is brought up to date lazily, when
+///   eviction reaches an entry accessed since it was filed, and eviction
+///   still removes the least recently used block
+/// - Exact capacity: The cache never holds more than `capacity` blocks
+/// - Good performance: O(1) lookups, amortized O(log n) eviction, with
+///   very short critical sections
+/// - Memory efficiency: Stores block numbers as keys with small fixed-size metadata
 ///
-/// Alternative approach:
+/// Alternative approaches:
+/// - scc::HashCache: Lock-free, but evicts per bucket, so hot slots can be
+///   evicted while cold ones in another bucket survive
 /// - Sparse set: Could be more memory efficient but requires more complex implementation
 ///
-/// The current implementation prioritizes simplicity and correctness over
-/// memory optimization, which is appropriate for testing purposes.
+/// Use `peek` for internal bookkeeping that must not distort the LRU order.
 pub struct BlockCache {
-    cache: HashCache<u64, ()>,
+    state: RwLock<LruState>,
+    capacity: AtomicUsize,
+    max_memory_bytes: Option<usize>,
+    on_evict: Option<EvictionCallback>,
 }
 
 impl BlockCache {
     pub fn new(capacity: usize) -> Self {
-        let cache = HashCache::with_capacity(capacity, capacity * 2);
         info!(capacity, "Created block cache");
 
-        Self { cache }
+        Self {
+            state: RwLock::new(LruState::default()),
+            capacity: AtomicUsize::new(capacity.max(1)),
+            max_memory_bytes: None,
+            on_evict: None,
+        }
+    }
+
+    /// Registers a callback invoked after an insert evicts the least
+    /// recently used block. It runs outside the cache lock, so it may use
+    /// the cache itself. Entries removed by `clear` or by shrinking the
+    /// capacity in `with_max_memory_bytes` are not reported.
+    pub fn with_eviction_callback(
+        mut self,
+        on_evict: impl Fn(u64) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_evict = Some(Arc::new(on_evict));
+        self
+    }
+
+    /// Bounds the cache by its estimated memory usage.
+    ///
+    /// When the bound allows fewer entries than the requested capacity, the
+    /// effective capacity shrinks (evicting the oldest blocks if needed) and
+    /// a warning is logged, since the memory cap rather than the monitoring
+    /// depth now limits how much of the window can be cached.
+    pub fn with_max_memory_bytes(mut self, max_memory_bytes: usize) -> Self {
+        let memory_capacity = (max_memory_bytes / ENTRY_SIZE_BYTES).max(1);
+        let capacity = self.capacity.get_mut();
+        if memory_capacity < *capacity {
+            warn!(
+                requested_capacity = *capacity,
+                memory_capacity,
+                max_memory_bytes,
+                "Cache memory cap is smaller than the requested capacity"
+            );
+            *capacity = memory_capacity;
+
+            let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+            while state.entries.len() > memory_capacity {
+                state.pop_oldest();
+            }
+        }
+
+        self.max_memory_bytes = Some(max_memory_bytes);
+        self
+    }
+
+    fn state(&self) -> RwLockReadGuard<'_, LruState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn state_mut(&self) -> RwLockWriteGuard<'_, LruState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn notify_evicted(&self, evicted: Option<u64>) {
+        if let (Some(block_number), Some(on_evict)) = (evicted, &self.on_evict) {
+            on_evict(block_number);
+        }
     }
 
     pub fn contains(&self, block_number: u64) -> bool {
-        let exists = self.cache.get(&block_number).is_some();
+        let exists = self.state().touch(block_number).is_some();
         debug!(block_number, exists, "Checked block in cache");
         exists
     }
 
+    /// Checks presence without promoting the entry in the LRU order.
+    pub fn peek(&self, block_number: u64) -> bool {
+        self.state().entries.contains_key(&block_number)
+    }
+
+    /// Cached block numbers in `start..=end`, sorted ascending. Like `peek`,
+    /// this does not promote the entries it finds.
+    pub fn cached_in_range(&self, start: u64, end: u64) -> Vec<u64> {
+        let state = self.state();
+        let mut block_numbers: Vec<u64> = if end.saturating_sub(start) < state.entries.len() as u64
+        {
+            (start..=end)
+                .filter(|block_number| state.entries.contains_key(block_number))
+                .collect()
+        } else {
+            state
+                .entries
+                .keys()
+                .copied()
+                .filter(|block_number| (start..=end).contains(block_number))
+                .collect()
+        };
+        block_numbers.sort_unstable();
+        block_numbers
+    }
+
+    /// Checks whether a block is cached at `min_commitment` or higher.
+    pub fn contains_at(&self, block_number: u64, min_commitment: CommitmentLevel) -> bool {
+        let exists = self
+            .state()
+            .touch(block_number)
+            .is_some_and(|entry| entry.metadata.commitment >= min_commitment);
+        debug!(
+            block_number,
+            ?min_commitment,
+            exists,
+            "Checked block commitment in cache"
+        );
+        exists
+    }
+
+    /// Checks many blocks at once, preserving the input order.
+    ///
+    /// The whole batch is answered under a single lock acquisition. Found
+    /// entries are promoted just like with `contains`.
+    pub fn contains_many(&self, block_numbers: &[u64]) -> Vec<bool> {
+        let state = self.state();
+        let present: Vec<bool> = block_numbers
+            .iter()
+            .map(|block_number| state.touch(*block_number).is_some())
+            .collect();
+
+        debug!(
+            requested = block_numbers.len(),
+            present = present.iter().filter(|present| **present).count(),
+            "Checked block batch in cache"
+        );
+        present
+    }
+
     pub fn insert(&self, block_number: u64) -> bool {
-        match self.cache.put(block_number, ()) {
-            Ok(_) => {
-                debug!(block_number, "Inserted block into cache");
-                true
+        self.insert_at(block_number, CommitmentLevel::Confirmed)
+    }
+
+    pub fn insert_at(&self, block_number: u64, commitment: CommitmentLevel) -> bool {
+        let mut state = self.state_mut();
+        if state.entries.contains_key(&block_number) {
+            warn!(block_number, "Failed to insert block into cache");
+            return false;
+        }
+
+        let evicted = state.insert_new(
+            block_number,
+            BlockMetadata::with_commitment(commitment),
+            self.capacity(),
+        );
+        drop(state);
+        debug!(
+            block_number,
+            ?commitment,
+            ?evicted,
+            "Inserted block into cache"
+        );
+        self.notify_evicted(evicted);
+        true
+    }
+
+    /// Inserts a block or updates the metadata of an existing one.
+    ///
+    /// Returns `true` only when the block was newly added. Metadata fields
+    /// left as `None` keep their stored value.
+    pub fn upsert(&self, block_number: u64, metadata: BlockMetadata) -> bool {
+        let mut state = self.state_mut();
+        if let Some(entry) = state.touch_mut(block_number) {
+            entry.metadata.merge(metadata);
+            debug!(block_number, "Updated block metadata in cache");
+            return false;
+        }
+
+        let evicted = state.insert_new(block_number, metadata, self.capacity());
+        drop(state);
+        debug!(block_number, ?evicted, "Inserted block into cache");
+        self.notify_evicted(evicted);
+        true
+    }
+
+    /// Removes a block, for example one a fresh RPC check no longer
+    /// confirms. Returns `true` if it was cached. Removals are not reported
+    /// to the eviction callback.
+    pub fn remove(&self, block_number: u64) -> bool {
+        let mut state = self.state_mut();
+        let Some(entry) = state.entries.remove(&block_number) else {
+            return false;
+        };
+        state.recency.remove(&entry.indexed_tick);
+        debug!(block_number, "Removed block from cache");
+        true
+    }
+
+    /// Removes every block below `block_number` and returns how many were
+    /// cached. Like `remove`, removals are not reported to the eviction
+    /// callback.
+    pub fn remove_below(&self, block_number: u64) -> usize {
+        let mut state = self.state_mut();
+        let LruState {
+            entries, recency, ..
+        } = &mut *state;
+        let before = entries.len();
+        entries.retain(|cached, entry| {
+            let keep = *cached >= block_number;
+            if !keep {
+                recency.remove(&entry.indexed_tick);
             }
-            Err(_) => {
-                warn!(block_number, "Failed to insert block into cache");
-                false
+            keep
+        });
+        let removed = before - entries.len();
+        debug!(
+            block_number,
+            removed, "Removed blocks below slot from cache"
+        );
+        removed
+    }
+
+    /// Raises the commitment level of an already cached block in place.
+    ///
+    /// Returns `true` if the entry was upgraded; missing blocks and entries
+    /// already at or above `commitment` are left untouched.
+    pub fn upgrade_commitment(&self, block_number: u64, commitment: CommitmentLevel) -> bool {
+        match self.state_mut().entries.get_mut(&block_number) {
+            Some(entry) if entry.metadata.commitment < commitment => {
+                entry.metadata.commitment = commitment;
+                debug!(block_number, ?commitment, "Upgraded block commitment");
+                true
             }
+            _ => false,
         }
     }
 
+    pub fn metadata(&self, block_number: u64) -> Option<BlockMetadata> {
+        self.state()
+            .entries
+            .get(&block_number)
+            .map(|entry| entry.metadata)
+    }
+
+    /// When `block_number` was first cached. Metadata updates and lookups
+    /// leave it unchanged.
+    pub fn inserted_at(&self, block_number: u64) -> Option<SystemTime> {
+        self.state()
+            .entries
+            .get(&block_number)
+            .map(|entry| entry.inserted_at)
+    }
+
+    /// Smallest cached block number, found in a single pass over the entries.
+    pub fn min_slot(&self) -> Option<u64> {
+        self.state().entries.keys().min().copied()
+    }
+
+    /// Largest cached block number, found in a single pass over the entries.
+    pub fn max_slot(&self) -> Option<u64> {
+        self.state().entries.keys().max().copied()
+    }
+
     pub fn len(&self) -> usize {
-        self.cache.len()
+        self.state().entries.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.cache.is_empty()
+        self.state().entries.is_empty()
     }
 
     pub fn capacity(&self) -> usize {
-        self.cache.capacity()
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Changes the capacity at runtime, for example when the monitoring
+    /// depth changes, and returns the capacity now in effect. A memory cap
+    /// set with `with_max_memory_bytes` still bounds it. Shrinking evicts
+    /// the least recently used blocks, which are reported to the eviction
+    /// callback like any other eviction.
+    pub fn resize(&self, capacity: usize) -> usize {
+        let mut capacity = capacity.max(1);
+        if let Some(max_memory_bytes) = self.max_memory_bytes {
+            capacity = capacity.min((max_memory_bytes / ENTRY_SIZE_BYTES).max(1));
+        }
+
+        let mut state = self.state_mut();
+        let previous = self.capacity.swap(capacity, Ordering::Relaxed);
+        let mut evicted = Vec::new();
+        while state.entries.len() > capacity {
+            evicted.extend(state.pop_oldest());
+        }
+        drop(state);
+
+        info!(
+            previous,
+            capacity,
+            evicted = evicted.len(),
+            "Resized block cache"
+        );
+        for block_number in evicted {
+            self.notify_evicted(Some(block_number));
+        }
+        capacity
+    }
+
+    pub fn max_memory_bytes(&self) -> Option<usize> {
+        self.max_memory_bytes
+    }
+
+    /// Estimated memory the cache grows to once it holds `capacity` blocks,
+    /// see `ENTRY_SIZE_BYTES`.
+    pub fn capacity_memory_bytes(&self) -> usize {
+        self.capacity() * ENTRY_SIZE_BYTES
+    }
+
+    /// Estimated memory held by the cached entries, see `ENTRY_SIZE_BYTES`.
+    pub fn estimated_memory_bytes(&self) -> usize {
+        self.len() * ENTRY_SIZE_BYTES
     }
 
     pub fn clear(&self) {
-        self.cache.clear();
+        let mut state = self.state_mut();
+        state.entries.clear();
+        state.recency.clear();
         info!("Cleared block cache");
     }
 }
@@ -72,6 +505,7 @@ impl Default for BlockCache {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Mutex;
 
     #[test]
     fn test_cache_basic_operations() {
@@ -96,7 +530,7 @@ mod tests {
     #[test]
     fn test_cache_capacity() {
         let cache = BlockCache::new(1000);
-        assert_eq!(cache.capacity(), 1024);
+        assert_eq!(cache.capacity(), 1000);
 
         cache.insert(1);
         cache.insert(2);
@@ -105,6 +539,71 @@ mod tests {
         assert_eq!(cache.len(), 3);
     }
 
+    #[test]
+    fn test_resize_evicts_oldest_and_respects_memory_cap() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let recorded = evicted.clone();
+        let cache = BlockCache::new(4)
+            .with_max_memory_bytes(6 * ENTRY_SIZE_BYTES)
+            .with_eviction_callback(move |block_number| {
+                recorded.lock().unwrap().push(block_number)
+            });
+        for block_number in 1..=4 {
+            cache.insert(block_number);
+        }
+        cache.contains(1);
+
+        assert_eq!(cache.resize(2), 2);
+        assert_eq!(cache.capacity(), 2);
+        assert_eq!(*evicted.lock().unwrap(), vec![2, 3]);
+        assert!(cache.peek(1) && cache.peek(4));
+
+        // Growing is bounded by the memory cap, and later inserts use the
+        // new capacity.
+        assert_eq!(cache.resize(100), 6);
+        for block_number in 5..=10 {
+            cache.insert(block_number);
+        }
+        assert_eq!(cache.len(), 6);
+        assert_eq!(cache.resize(0), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_eviction_callback_reports_evicted_slots() {
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let recorded = evicted.clone();
+        let cache = BlockCache::new(3).with_eviction_callback(move |block_number| {
+            recorded.lock().unwrap().push(block_number)
+        });
+
+        for block_number in 1..=3 {
+            cache.insert(block_number);
+        }
+        assert!(evicted.lock().unwrap().is_empty());
+
+        assert!(cache.contains(1));
+        cache.insert(4);
+        cache.upsert(5, BlockMetadata::default());
+        cache.upsert(5, BlockMetadata::default());
+
+        assert_eq!(*evicted.lock().unwrap(), vec![2, 3]);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_min_and_max_slot() {
+        let cache = BlockCache::new(100);
+        assert_eq!(cache.min_slot(), None);
+        assert_eq!(cache.max_slot(), None);
+
+        for block_number in [420, 17, 9_001, 256, 33] {
+            cache.insert(block_number);
+        }
+        assert_eq!(cache.min_slot(), Some(17));
+        assert_eq!(cache.max_slot(), Some(9_001));
+    }
+
     #[test]
     fn test_cache_clear() {
         let cache = BlockCache::new(5);
@@ -118,6 +617,204 @@ mod tests {
         assert_eq!(cache.len(), 0);
     }
 
+    #[test]
+    fn test_remove_keeps_lru_order() {
+        let cache = BlockCache::new(3);
+        for block_number in 1..=3 {
+            cache.insert(block_number);
+        }
+
+        assert!(cache.remove(2));
+        assert!(!cache.remove(2));
+        assert_eq!(cache.len(), 2);
+
+        // The freed room is used before anything is evicted.
+        cache.insert(4);
+        cache.insert(5);
+        let remaining: Vec<u64> = (1..=5).filter(|slot| cache.peek(*slot)).collect();
+        assert_eq!(remaining, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_remove_below_drops_older_blocks() {
+        let cache = BlockCache::new(10);
+        for block_number in [5, 1, 8, 3, 9] {
+            cache.insert(block_number);
+        }
+
+        assert_eq!(cache.remove_below(5), 2);
+        assert_eq!(cache.remove_below(5), 0);
+        assert_eq!(cache.cached_in_range(0, 10), vec![5, 8, 9]);
+
+        // Removed blocks leave the LRU order too, so the oldest survivor is
+        // evicted first once the cache fills up.
+        cache.resize(3);
+        cache.insert(10);
+        assert_eq!(cache.cached_in_range(0, 10), vec![8, 9, 10]);
+    }
+
+    #[test]
+    fn test_capacity_memory_estimate_scales_with_capacity() {
+        let small = BlockCache::new(1_000);
+        let large = BlockCache::new(10_000);
+        assert_eq!(small.capacity_memory_bytes(), 1_000 * ENTRY_SIZE_BYTES);
+        assert_eq!(
+            large.capacity_memory_bytes(),
+            10 * small.capacity_memory_bytes()
+        );
+        assert_eq!(small.estimated_memory_bytes(), 0);
+
+        small.resize(2_000);
+        assert_eq!(small.capacity_memory_bytes(), 2_000 * ENTRY_SIZE_BYTES);
+
+        let capped = BlockCache::new(10_000).with_max_memory_bytes(500 * ENTRY_SIZE_BYTES);
+        assert_eq!(capped.capacity_memory_bytes(), 500 * ENTRY_SIZE_BYTES);
+    }
+
+    #[test]
+    fn test_estimated_memory_tracks_inserts_and_evictions() {
+        let cache = BlockCache::new(3);
+        assert_eq!(cache.estimated_memory_bytes(), 0);
+
+        cache.insert(1);
+        assert_eq!(cache.estimated_memory_bytes(), ENTRY_SIZE_BYTES);
+
+        for block_number in 2..=5 {
+            cache.insert(block_number);
+        }
+        assert_eq!(cache.estimated_memory_bytes(), 3 * ENTRY_SIZE_BYTES);
+
+        cache.clear();
+        assert_eq!(cache.estimated_memory_bytes(), 0);
+    }
+
+    #[test]
+    fn test_max_memory_bounds_capacity() {
+        let cache = BlockCache::new(100).with_max_memory_bytes(2 * ENTRY_SIZE_BYTES);
+        assert_eq!(cache.capacity(), 2);
+        assert_eq!(cache.max_memory_bytes(), Some(2 * ENTRY_SIZE_BYTES));
+
+        for block_number in 1..=5 {
+            cache.insert(block_number);
+        }
+        assert_eq!(cache.len(), 2);
+        assert!(cache.estimated_memory_bytes() <= 2 * ENTRY_SIZE_BYTES);
+
+        let roomy = BlockCache::new(10).with_max_memory_bytes(1 << 20);
+        assert_eq!(roomy.capacity(), 10);
+    }
+
+    #[test]
+    fn test_contains_many_matches_contains() {
+        let cache = BlockCache::new(20_000);
+        for block_number in (0..10_000).step_by(2) {
+            cache.insert(block_number);
+        }
+
+        let slots: Vec<u64> = (0..10_000).rev().collect();
+        let batch = cache.contains_many(&slots);
+
+        assert_eq!(batch.len(), slots.len());
+        for (slot, present) in slots.iter().zip(&batch) {
+            assert_eq!(*present, cache.contains(*slot), "slot {}", slot);
+        }
+        assert_eq!(batch.iter().filter(|present| **present).count(), 5_000);
+    }
+
+    #[test]
+    fn test_cached_in_range() {
+        let cache = BlockCache::new(100);
+        for block_number in [5, 1, 9, 3, 20] {
+            cache.insert(block_number);
+        }
+
+        assert_eq!(cache.cached_in_range(2, 10), vec![3, 5, 9]);
+        assert_eq!(cache.cached_in_range(0, 1_000), vec![1, 3, 5, 9, 20]);
+        assert!(cache.cached_in_range(10, 19).is_empty());
+    }
+
+    #[test]
+    fn test_contains_many_empty_batch() {
+        let cache = BlockCache::new(100);
+        cache.insert(5);
+
+        assert_eq!(cache.contains_many(&[5, 500]), vec![true, false]);
+        assert!(cache.contains_many(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_upsert_first_insert() {
+        let cache = BlockCache::new(10);
+        let metadata = BlockMetadata {
+            block_time: Some(1_700_000_000),
+            block_height: Some(42),
+            commitment: CommitmentLevel::Confirmed,
+        };
+
+        assert!(cache.upsert(7, metadata));
+        assert!(cache.contains(7));
+        assert_eq!(cache.metadata(7), Some(metadata));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_upsert_updates_existing() {
+        let cache = BlockCache::new(10);
+        assert!(cache.insert(7));
+
+        let update = BlockMetadata {
+            block_time: Some(1_700_000_000),
+            ..BlockMetadata::default()
+        };
+        assert!(!cache.upsert(7, update));
+        assert_eq!(cache.metadata(7), Some(update));
+
+        let height_only = BlockMetadata {
+            block_height: Some(42),
+            ..BlockMetadata::default()
+        };
+        assert!(!cache.upsert(7, height_only));
+        assert_eq!(
+            cache.metadata(7),
+            Some(BlockMetadata {
+                block_time: Some(1_700_000_000),
+                block_height: Some(42),
+                commitment: CommitmentLevel::Confirmed,
+            })
+        );
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_commitment_lookups() {
+        let cache = BlockCache::new(10);
+        cache.insert_at(1, CommitmentLevel::Confirmed);
+        cache.insert_at(2, CommitmentLevel::Finalized);
+
+        assert!(cache.contains_at(1, CommitmentLevel::Confirmed));
+        assert!(!cache.contains_at(1, CommitmentLevel::Finalized));
+        assert!(cache.contains_at(2, CommitmentLevel::Confirmed));
+        assert!(cache.contains_at(2, CommitmentLevel::Finalized));
+        assert!(!cache.contains_at(3, CommitmentLevel::Confirmed));
+        assert!(!cache.contains_at(3, CommitmentLevel::Finalized));
+    }
+
+    #[test]
+    fn test_commitment_upgrade() {
+        let cache = BlockCache::new(10);
+        cache.insert(1);
+
+        assert!(cache.upgrade_commitment(1, CommitmentLevel::Finalized));
+        assert!(cache.contains_at(1, CommitmentLevel::Finalized));
+        assert!(!cache.upgrade_commitment(1, CommitmentLevel::Finalized));
+        assert!(!cache.upgrade_commitment(1, CommitmentLevel::Confirmed));
+        assert!(!cache.upgrade_commitment(2, CommitmentLevel::Finalized));
+
+        assert!(!cache.upsert(1, BlockMetadata::default()));
+        assert!(cache.contains_at(1, CommitmentLevel::Finalized));
+        assert_eq!(cache.len(), 1);
+    }
+
     #[test]
     fn test_lru_behavior() {
         let cache = BlockCache::new(2);
@@ -125,11 +822,71 @@ mod tests {
         cache.insert(1);
         cache.insert(2);
 
-        cache.contains(1);
+        assert!(cache.contains(1));
 
         cache.insert(3);
 
+        assert!(cache.peek(1));
+        assert!(!cache.peek(2));
+        assert!(cache.peek(3));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_lookups_run_alongside_inserts() {
+        let cache = BlockCache::new(100);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for slot in 0..10_000 {
+                        cache.contains(slot % 150);
+                        cache.contains_many(&[slot % 150, (slot + 1) % 150]);
+                    }
+                });
+            }
+            for slot in 0..10_000 {
+                cache.insert(slot);
+            }
+        });
+
+        assert_eq!(cache.len(), 100);
+        assert!(cache.peek(9_999));
+    }
+
+    #[test]
+    fn test_lru_eviction_order() {
+        let cache = BlockCache::new(4);
+        for block_number in 1..=4 {
+            cache.insert(block_number);
+        }
+
         assert!(cache.contains(1));
-        assert!(cache.contains(3));
+        assert_eq!(cache.contains_many(&[3]), vec![true]);
+        assert!(!cache.upsert(2, BlockMetadata::default()));
+
+        cache.insert(5);
+        cache.insert(6);
+
+        let remaining: Vec<u64> = (1..=6).filter(|slot| cache.peek(*slot)).collect();
+        assert_eq!(remaining, vec![2, 3, 5, 6]);
+    }
+
+    #[test]
+    fn test_peek_does_not_promote() {
+        let cache = BlockCache::new(3);
+        for block_number in 1..=3 {
+            cache.insert(block_number);
+        }
+
+        assert!(cache.peek(1));
+        assert!(cache.metadata(1).is_some());
+        assert!(cache.contains(2));
+
+        cache.insert(4);
+
+        assert!(!cache.peek(1));
+        assert!(cache.peek(2));
+        assert!(cache.peek(3));
+        assert!(cache.peek(4));
     }
 }
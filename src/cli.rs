@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::config::{self, ConfigError, PrintFormat};
+
+/// What the command line asked for.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(CliArgs),
+    Help,
+}
+
+/// Command-line overrides, keyed by the environment variable each flag
+/// stands for so they can be layered over the environment and env file.
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub env_file: Option<String>,
+    /// Print the effective configuration in this format and exit instead
+    /// of starting the service.
+    pub print_config: Option<PrintFormat>,
+    pub overrides: HashMap<String, String>,
+}
+
+/// Short flags for variables whose full flag name is long-winded.
+const ALIASES: &[(&str, &str)] = &[
+    ("config", "CONFIG_PATH"),
+    ("port", "SERVER_PORT"),
+    ("profile", "APP_ENV"),
+];
+
+/// Maps `--monitor-interval-ms` to `MONITOR_INTERVAL_MS`, or an alias to
+/// its variable. Flags of renamed variables map to the deprecated name so
+/// the config reports them the same way as in the environment. Returns
+/// `None` for flags no variable answers to.
+fn variable_for(flag: &str) -> Option<&'static str> {
+    if let Some((_, key)) = ALIASES.iter().find(|(alias, _)| *alias == flag) {
+        return Some(key);
+    }
+    config::variables()
+        .into_iter()
+        .map(|(name, _)| name)
+        .chain(config::DEPRECATED_ALIASES.iter().map(|(old, _)| *old))
+        .find(|name| flag_for(name) == flag)
+}
+
+fn flag_for(key: &str) -> String {
+    key.to_lowercase().replace('_', "-")
+}
+
+/// Parses the arguments after the program name.
+///
+/// Every configuration variable has a flag: `--monitor-interval-ms 200` or
+/// `--monitor-interval-ms=200` sets `MONITOR_INTERVAL_MS`. A flag with no
+/// value, followed by another flag or by nothing, is taken as `true`.
+/// Unknown flags and stray positional arguments are errors.
+pub fn parse_args<I>(args: I) -> Result<Command, ConfigError>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = CliArgs::default();
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next() {
+        if arg == "--help" || arg == "-h" {
+            return Ok(Command::Help);
+        }
+        let Some(flag) = arg.strip_prefix("--") else {
+            return Err(ConfigError::ParseError(format!(
+                "Unexpected argument: {}",
+                arg
+            )));
+        };
+        let (flag, inline) = match flag.split_once('=') {
+            Some((flag, value)) => (flag, Some(value.to_string())),
+            None => (flag, None),
+        };
+        let value = match inline {
+            Some(value) => value,
+            None => match args.next_if(|next| !next.starts_with("--")) {
+                Some(value) => value,
+                None => "true".to_string(),
+            },
+        };
+
+        if flag == "env-file" {
+            parsed.env_file = Some(value);
+            continue;
+        }
+        if flag == "print-config" {
+            let format = value.parse().map_err(|reason| {
+                ConfigError::ParseError(format!("Invalid --print-config value: {}", reason))
+            })?;
+            parsed.print_config = Some(format);
+            continue;
+        }
+        let key = variable_for(flag)
+            .ok_or_else(|| ConfigError::ParseError(format!("Unknown flag: --{}", flag)))?;
+        parsed.overrides.insert(key.to_string(), value);
+    }
+
+    Ok(Command::Run(parsed))
+}
+
+/// Usage text listing every flag with the default it falls back to.
+pub fn help() -> String {
+    let mut help = String::from(
+        "Usage: solana-block-monitor [OPTIONS]\n\n\
+         Each option overrides the environment variable of the same name, which\n\
+         in turn overrides the env file.\n\nOptions:\n",
+    );
+    let _ = writeln!(
+        help,
+        "  --env-file <PATH>  Env file to read [default: ENV_FILE, else .env in the \
+         current directory or up to {} parents, optional]",
+        config::ENV_FILE_SEARCH_DEPTH
+    );
+    let _ = writeln!(
+        help,
+        "  --print-config[=<text|json>]  Print every effective setting with its value \
+         and source, then exit"
+    );
+    for (key, default) in config::variables() {
+        let aliases: String = ALIASES
+            .iter()
+            .filter(|(_, variable)| *variable == key)
+            .map(|(alias, _)| format!(", --{}", alias))
+            .collect();
+        let default = default
+            .map(|default| format!("[default: {}]", default))
+            .unwrap_or_else(|| "[required]".to_string());
+        let _ = writeln!(
+            help,
+            "  --{}{} <{}>  {}",
+            flag_for(key),
+            aliases,
+            key,
+            default
+        );
+    }
+    let _ = writeln!(help, "  -h, --help  Print this help");
+    help
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_maps_flags_to_variables() {
+        let command = parse_args(args(&[
+            "--port",
+            "8081",
+            "--monitor-interval-ms=200",
+            "--env-file",
+            "./staging.env",
+            "--config",
+            "config.toml",
+            "--rpc-insecure",
+            "--log-level",
+            "debug",
+            "--monitoring-depth",
+            "500",
+            "--profile",
+            "staging",
+        ]))
+        .unwrap();
+
+        let Command::Run(parsed) = command else {
+            panic!("expected run, got {:?}", command);
+        };
+        assert_eq!(parsed.env_file.as_deref(), Some("./staging.env"));
+        assert_eq!(parsed.print_config, None);
+        assert_eq!(
+            parsed.overrides,
+            HashMap::from([
+                ("SERVER_PORT".to_string(), "8081".to_string()),
+                ("MONITOR_INTERVAL_MS".to_string(), "200".to_string()),
+                ("CONFIG_PATH".to_string(), "config.toml".to_string()),
+                ("RPC_INSECURE".to_string(), "true".to_string()),
+                ("LOG_LEVEL".to_string(), "debug".to_string()),
+                ("MONITORING_DEPTH".to_string(), "500".to_string()),
+                ("APP_ENV".to_string(), "staging".to_string()),
+            ])
+        );
+
+        assert_eq!(
+            parse_args(args(&[])).unwrap(),
+            Command::Run(CliArgs::default())
+        );
+        for (flags, format) in [
+            (&["--print-config"][..], PrintFormat::Text),
+            (&["--print-config", "--port", "1"], PrintFormat::Text),
+            (&["--print-config=json"], PrintFormat::Json),
+            (&["--print-config", "text"], PrintFormat::Text),
+        ] {
+            let Command::Run(parsed) = parse_args(args(flags)).unwrap() else {
+                panic!("expected run for {:?}", flags);
+            };
+            assert_eq!(parsed.print_config, Some(format), "{:?}", flags);
+        }
+        assert!(matches!(
+            parse_args(args(&["--print-config=yaml"])),
+            Err(ConfigError::ParseError(message))
+                if message == "Invalid --print-config value: unknown format `yaml`, \
+                               expected text or json"
+        ));
+        assert_eq!(
+            parse_args(args(&["--port", "1", "-h"])).unwrap(),
+            Command::Help
+        );
+    }
+
+    #[test]
+    fn test_parse_args_rejects_unknown_flags() {
+        for bad in [
+            &["--prot", "8081"][..],
+            &["--SERVER_PORT", "8081"],
+            &["--server_port", "8081"],
+            &["8081"],
+            &["-p", "8081"],
+        ] {
+            assert!(
+                matches!(parse_args(args(bad)), Err(ConfigError::ParseError(_))),
+                "{:?} should be rejected",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn test_help_lists_every_variable() {
+        let help = help();
+        for (key, _) in config::variables() {
+            assert!(
+                help.contains(&format!("--{}", flag_for(key))),
+                "{} missing",
+                key
+            );
+        }
+        assert!(help.contains("--solana-rpc-url <SOLANA_RPC_URL>  [required]"));
+        assert!(help.contains("--server-port, --port <SERVER_PORT>  [default: 8002]"));
+        assert!(help.contains("--rpc-max-concurrency <RPC_MAX_CONCURRENCY>  [default: 10]"));
+        assert!(help.contains("--env-file"));
+    }
+}
@@ -0,0 +1,361 @@
+//! Building a `Config` in code, for embedding the monitor as a library and
+//! for tests that must not depend on the environment.
+
+use std::path::PathBuf;
+
+use super::{
+    CACHE_HEADROOM_FACTOR, Config, ConfigError, DEFAULT_CACHE_PRUNE_INTERVAL_SECS,
+    DEFAULT_LOG_LEVEL, DEFAULT_MONITOR_INTERVAL_MS, DEFAULT_MONITORING_DEPTH, DEFAULT_SERVER_PORT,
+    LogLevel, RpcEndpoint,
+};
+use crate::cache::ENTRY_SIZE_BYTES;
+use crate::logic::{
+    ConfirmationStrategy, DEFAULT_CONFIRMATION_LATENCY_SAMPLE_MS, DEFAULT_CONFIRMATION_SLA_MS,
+    DEFAULT_FUTURE_SLOT_TOLERANCE, DEFAULT_MAX_RANGE_SLOTS, DEFAULT_MAX_READY_LAG,
+    DEFAULT_MAX_STALENESS_SECS, DEFAULT_STARTUP_HEALTHCHECK_DELAY_MS,
+    DEFAULT_STARTUP_HEALTHCHECK_RETRIES, SyncMode,
+};
+use crate::metrics::{
+    DEFAULT_INTERVAL_AGE_WARN_MS, DEFAULT_LATENCY_BUCKETS_MS, DEFAULT_OTLP_ENDPOINT,
+    DEFAULT_QUEUE_DEPTH_WARN, DEFAULT_SERVICE_NAME, DEFAULT_SLOW_OPERATION_THRESHOLD_MS,
+    DEFAULT_STATSD_ADDR, DEFAULT_SYNC_LAG_WARN_SLOTS, MetricsBackend,
+};
+use crate::state::DEFAULT_SLOT_BROADCAST_BUFFER;
+use crate::synchronizer::{
+    DEFAULT_QUEUE_HIGH_WATER, DEFAULT_QUEUE_LOW_WATER, DEFAULT_SYNC_INTERVAL_SIZE,
+    DEFAULT_SYNC_MAX_ATTEMPTS,
+};
+use crate::syndica_client::DEFAULT_MAX_CONCURRENCY;
+use crate::webhook::{DEFAULT_WEBHOOK_BUFFER, DEFAULT_WEBHOOK_MAX_ATTEMPTS};
+
+/// Setters assigning a field as given.
+macro_rules! setters {
+    ($($setter:ident => $field:ident: $ty:ty,)*) => {
+        $(
+            pub fn $setter(mut self, $field: $ty) -> Self {
+                self.config.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// Setters for optional fields, which are unset by default.
+macro_rules! optional_setters {
+    ($($setter:ident => $field:ident: $ty:ty,)*) => {
+        $(
+            pub fn $setter(mut self, $field: $ty) -> Self {
+                self.config.$field = Some($field);
+                self
+            }
+        )*
+    };
+}
+
+/// Builds a `Config` from values set in code.
+///
+/// Every field starts at the default the env loaders use, so only the RPC
+/// endpoint is required: `with_solana_rpc_url` or `with_rpc_endpoints`. The
+/// endpoint list takes precedence over the single URL and key, as
+/// `SOLANA_RPC_URLS` does over `SOLANA_RPC_URL`. A value set here is final;
+/// nothing is read from files or the environment.
+///
+/// `build` applies the same checks as loading from the environment, which
+/// also goes through this builder.
+#[derive(Clone)]
+pub struct ConfigBuilder {
+    pub(super) config: Config,
+    /// Unset follows `monitoring_depth`, as an unset `INITIAL_BACKFILL_DEPTH`
+    /// does.
+    pub(super) initial_backfill_depth: Option<usize>,
+    /// Unset is `monitoring_depth` times `CACHE_HEADROOM_FACTOR`, capped at
+    /// what `cache_max_memory_bytes` fits but never below the window.
+    pub(super) cache_capacity: Option<usize>,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: Config {
+                env_file: None,
+                env_overlay: None,
+                config_path: None,
+                solana_rpc_url: String::new(),
+                solana_rpc_key: String::new(),
+                rpc_endpoints: Vec::new(),
+                rpc_ws_url: None,
+                server_port: DEFAULT_SERVER_PORT,
+                log_level: DEFAULT_LOG_LEVEL,
+                log_filter: None,
+                monitor_interval_ms: DEFAULT_MONITOR_INTERVAL_MS,
+                monitoring_depth: DEFAULT_MONITORING_DEPTH,
+                initial_backfill_depth: DEFAULT_MONITORING_DEPTH,
+                slot_confirmation_offset: 0,
+                sync_batch_intervals: false,
+                sync_interval_size: DEFAULT_SYNC_INTERVAL_SIZE,
+                sync_max_attempts: DEFAULT_SYNC_MAX_ATTEMPTS,
+                sync_mode: SyncMode::default(),
+                start_from_slot: None,
+                disable_history: false,
+                confirmation_strategy: ConfirmationStrategy::default(),
+                serve_stale_on_error: false,
+                health_max_staleness_secs: DEFAULT_MAX_STALENESS_SECS,
+                max_ready_lag: DEFAULT_MAX_READY_LAG,
+                future_slot_tolerance: DEFAULT_FUTURE_SLOT_TOLERANCE,
+                max_range_slots: DEFAULT_MAX_RANGE_SLOTS,
+                confirmation_sla_ms: DEFAULT_CONFIRMATION_SLA_MS,
+                confirmation_latency_sample_ms: DEFAULT_CONFIRMATION_LATENCY_SAMPLE_MS,
+                cache_capacity: DEFAULT_MONITORING_DEPTH,
+                cache_max_memory_bytes: None,
+                cache_max_memory_mb: None,
+                rpc_max_concurrency: DEFAULT_MAX_CONCURRENCY,
+                startup_healthcheck_retries: DEFAULT_STARTUP_HEALTHCHECK_RETRIES,
+                startup_healthcheck_delay_ms: DEFAULT_STARTUP_HEALTHCHECK_DELAY_MS,
+                slot_broadcast_buffer: DEFAULT_SLOT_BROADCAST_BUFFER,
+                rpc_ca_cert: None,
+                rpc_insecure: false,
+                admin_token: None,
+                json_pretty: false,
+                webhook_url: None,
+                webhook_max_attempts: DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+                webhook_buffer: DEFAULT_WEBHOOK_BUFFER,
+                metrics_backends: vec![MetricsBackend::default()],
+                latency_buckets_ms: DEFAULT_LATENCY_BUCKETS_MS.to_vec(),
+                metrics_summary_interval_secs: 60,
+                metrics_dump_path: None,
+                heartbeat_interval_secs: 0,
+                cache_prune_interval_secs: DEFAULT_CACHE_PRUNE_INTERVAL_SECS,
+                sync_lag_warn_slots: DEFAULT_SYNC_LAG_WARN_SLOTS,
+                queue_depth_warn: DEFAULT_QUEUE_DEPTH_WARN,
+                queue_high_water: DEFAULT_QUEUE_HIGH_WATER,
+                queue_low_water: DEFAULT_QUEUE_LOW_WATER,
+                interval_age_warn_ms: DEFAULT_INTERVAL_AGE_WARN_MS,
+                slow_operation_threshold_ms: DEFAULT_SLOW_OPERATION_THRESHOLD_MS,
+                slow_thresholds_ms: Vec::new(),
+                metrics_sample_rate: 1.0,
+                statsd_addr: DEFAULT_STATSD_ADDR.to_string(),
+                statsd_prefix: None,
+                statsd_tags: Vec::new(),
+                statsd_flush_interval_ms: 1000,
+                otlp_endpoint: DEFAULT_OTLP_ENDPOINT.to_string(),
+                otlp_headers: Vec::new(),
+                otel_service_name: DEFAULT_SERVICE_NAME.to_string(),
+                otlp_export_interval_ms: 10_000,
+                otel_traces_enabled: false,
+                config_strict: false,
+                sources: Vec::new(),
+                warnings: Vec::new(),
+            },
+            initial_backfill_depth: None,
+            cache_capacity: None,
+        }
+    }
+
+    setters! {
+        with_solana_rpc_url => solana_rpc_url: String,
+        with_solana_rpc_key => solana_rpc_key: String,
+        with_rpc_endpoints => rpc_endpoints: Vec<RpcEndpoint>,
+        with_server_port => server_port: u16,
+        with_log_level => log_level: LogLevel,
+        with_monitor_interval_ms => monitor_interval_ms: u64,
+        with_monitoring_depth => monitoring_depth: usize,
+        with_slot_confirmation_offset => slot_confirmation_offset: u64,
+        with_sync_batch_intervals => sync_batch_intervals: bool,
+        with_sync_interval_size => sync_interval_size: u64,
+        with_sync_max_attempts => sync_max_attempts: u32,
+        with_sync_mode => sync_mode: SyncMode,
+        with_disable_history => disable_history: bool,
+        with_confirmation_strategy => confirmation_strategy: ConfirmationStrategy,
+        with_serve_stale_on_error => serve_stale_on_error: bool,
+        with_health_max_staleness_secs => health_max_staleness_secs: u64,
+        with_max_ready_lag => max_ready_lag: u64,
+        with_future_slot_tolerance => future_slot_tolerance: u64,
+        with_max_range_slots => max_range_slots: u64,
+        with_confirmation_sla_ms => confirmation_sla_ms: u64,
+        with_confirmation_latency_sample_ms => confirmation_latency_sample_ms: u64,
+        with_rpc_max_concurrency => rpc_max_concurrency: usize,
+        with_startup_healthcheck_retries => startup_healthcheck_retries: u32,
+        with_startup_healthcheck_delay_ms => startup_healthcheck_delay_ms: u64,
+        with_slot_broadcast_buffer => slot_broadcast_buffer: usize,
+        with_rpc_insecure => rpc_insecure: bool,
+        with_json_pretty => json_pretty: bool,
+        with_webhook_max_attempts => webhook_max_attempts: u32,
+        with_webhook_buffer => webhook_buffer: usize,
+        with_metrics_backends => metrics_backends: Vec<MetricsBackend>,
+        with_latency_buckets_ms => latency_buckets_ms: Vec<u64>,
+        with_metrics_summary_interval_secs => metrics_summary_interval_secs: u64,
+        with_heartbeat_interval_secs => heartbeat_interval_secs: u64,
+        with_cache_prune_interval_secs => cache_prune_interval_secs: u64,
+        with_sync_lag_warn_slots => sync_lag_warn_slots: u64,
+        with_queue_depth_warn => queue_depth_warn: usize,
+        with_queue_high_water => queue_high_water: usize,
+        with_queue_low_water => queue_low_water: usize,
+        with_interval_age_warn_ms => interval_age_warn_ms: u64,
+        with_slow_operation_threshold_ms => slow_operation_threshold_ms: u64,
+        with_slow_thresholds_ms => slow_thresholds_ms: Vec<(String, u64)>,
+        with_metrics_sample_rate => metrics_sample_rate: f64,
+        with_statsd_addr => statsd_addr: String,
+        with_statsd_tags => statsd_tags: Vec<String>,
+        with_statsd_flush_interval_ms => statsd_flush_interval_ms: u64,
+        with_otlp_endpoint => otlp_endpoint: String,
+        with_otlp_headers => otlp_headers: Vec<(String, String)>,
+        with_otel_service_name => otel_service_name: String,
+        with_otlp_export_interval_ms => otlp_export_interval_ms: u64,
+        with_otel_traces_enabled => otel_traces_enabled: bool,
+        with_config_strict => config_strict: bool,
+    }
+
+    optional_setters! {
+        with_env_file => env_file: PathBuf,
+        with_env_overlay => env_overlay: PathBuf,
+        with_config_path => config_path: PathBuf,
+        with_rpc_ws_url => rpc_ws_url: String,
+        with_log_filter => log_filter: String,
+        with_start_from_slot => start_from_slot: u64,
+        with_cache_max_memory_bytes => cache_max_memory_bytes: usize,
+        with_cache_max_memory_mb => cache_max_memory_mb: usize,
+        with_rpc_ca_cert => rpc_ca_cert: PathBuf,
+        with_admin_token => admin_token: String,
+        with_webhook_url => webhook_url: String,
+        with_metrics_dump_path => metrics_dump_path: PathBuf,
+        with_statsd_prefix => statsd_prefix: String,
+    }
+
+    pub fn with_initial_backfill_depth(mut self, initial_backfill_depth: usize) -> Self {
+        self.initial_backfill_depth = Some(initial_backfill_depth);
+        self
+    }
+
+    pub fn with_cache_capacity(mut self, cache_capacity: usize) -> Self {
+        self.cache_capacity = Some(cache_capacity);
+        self
+    }
+
+    /// Fills in the endpoint fields from one another and validates the
+    /// result.
+    ///
+    /// Fails with `MissingVariable("SOLANA_RPC_URL")` when no endpoint is
+    /// set, and with `Invalid` listing every value out of range, as
+    /// `Config::validate` reports them. Warnings found on the way, such as
+    /// a cache above `cache_max_memory_mb`, end up in `Config::warnings`.
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let mut config = self.config;
+        match config.rpc_endpoints.first() {
+            Some(first) => {
+                config.solana_rpc_url = first.url.clone();
+                config.solana_rpc_key = first.key.clone().unwrap_or_default();
+            }
+            None if config.solana_rpc_url.is_empty() => {
+                return Err(ConfigError::MissingVariable("SOLANA_RPC_URL".to_string()));
+            }
+            None => {
+                config.rpc_endpoints = vec![RpcEndpoint {
+                    url: config.solana_rpc_url.clone(),
+                    key: Some(config.solana_rpc_key.clone()).filter(|key| !key.is_empty()),
+                }];
+            }
+        }
+        config.initial_backfill_depth = self
+            .initial_backfill_depth
+            .unwrap_or(config.monitoring_depth);
+        config.cache_capacity = self.cache_capacity.unwrap_or_else(|| {
+            let headroom = (config.monitoring_depth as f64 * CACHE_HEADROOM_FACTOR).ceil() as usize;
+            match config.cache_max_memory_bytes {
+                Some(max_memory_bytes) => headroom
+                    .min(max_memory_bytes / ENTRY_SIZE_BYTES)
+                    .max(config.monitoring_depth),
+                None => headroom,
+            }
+        });
+
+        config.validate()?;
+        config.warnings.extend(config.cache_capacity_warning());
+        config.warnings.extend(config.cache_memory_warning());
+        Ok(config)
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ValidationError;
+
+    #[test]
+    fn test_build_in_code() {
+        assert!(matches!(
+            Config::builder().build(),
+            Err(ConfigError::MissingVariable(key)) if key == "SOLANA_RPC_URL"
+        ));
+
+        let config = Config::builder()
+            .with_solana_rpc_url("https://rpc.example.com".to_string())
+            .with_solana_rpc_key("key".to_string())
+            .with_monitoring_depth(500)
+            .with_admin_token("token".to_string())
+            .build()
+            .unwrap();
+        assert_eq!(config.server_port, DEFAULT_SERVER_PORT);
+        assert_eq!(config.monitoring_depth, 500);
+        assert_eq!(config.initial_backfill_depth, 500);
+        assert_eq!(config.admin_token.as_deref(), Some("token"));
+        assert_eq!(
+            config.rpc_endpoints,
+            vec![RpcEndpoint {
+                url: "https://rpc.example.com".to_string(),
+                key: Some("key".to_string()),
+            }]
+        );
+
+        // The endpoint list wins over the single URL.
+        let config = Config::builder()
+            .with_solana_rpc_url("https://ignored.example.com".to_string())
+            .with_rpc_endpoints(vec![
+                RpcEndpoint {
+                    url: "https://a.example.com".to_string(),
+                    key: None,
+                },
+                RpcEndpoint {
+                    url: "https://b.example.com".to_string(),
+                    key: Some("b".to_string()),
+                },
+            ])
+            .with_initial_backfill_depth(100)
+            .build()
+            .unwrap();
+        assert_eq!(config.solana_rpc_url, "https://a.example.com");
+        assert_eq!(config.solana_rpc_key, "");
+        assert_eq!(config.initial_backfill_depth, 100);
+    }
+
+    #[test]
+    fn test_build_validates_like_env_loading() {
+        let result = Config::builder()
+            .with_solana_rpc_url("https://rpc.example.com".to_string())
+            .with_server_port(0)
+            .with_monitoring_depth(0)
+            .with_metrics_sample_rate(1.5)
+            .build();
+        assert!(matches!(
+            result,
+            Err(ConfigError::Invalid(errors)) if errors == vec![
+                ValidationError::ZeroServerPort,
+                ValidationError::MonitoringDepthOutOfRange { depth: 0 },
+                ValidationError::SampleRateOutOfRange,
+            ]
+        ));
+
+        let config = Config::builder()
+            .with_solana_rpc_url("https://rpc.example.com".to_string())
+            .with_monitoring_depth(1_000_000)
+            .with_cache_max_memory_mb(1)
+            .build()
+            .unwrap();
+        assert_eq!(config.warnings.len(), 1, "{:?}", config.warnings);
+    }
+}
@@ -0,0 +1,349 @@
+//! `${VAR}`, `$VAR` and `${VAR:-default}` references inside env file values.
+//!
+//! References are expanded once the whole file is read, so a value may use
+//! a variable set further down. A reference resolves to the file's own
+//! variable first and to the process environment otherwise; values taken
+//! from the environment are used as is. A reference to a variable set in
+//! neither is kept as written and reported, so a `$` inside a secret does
+//! not fail the load. `$$` is a literal `$`, as is a `$` not followed by a
+//! name or `{`. Single-quoted values are not expanded.
+
+use std::collections::{HashMap, HashSet};
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Debug, PartialEq)]
+pub(super) struct ExpandError {
+    pub(super) key: String,
+    pub(super) reason: String,
+}
+
+/// A reference in the value of `key` to `name`, which is set neither in
+/// the file nor in the environment.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) struct UndefinedReference {
+    pub(super) key: String,
+    pub(super) name: String,
+}
+
+struct Expander<'a> {
+    file: &'a HashMap<String, String>,
+    literal: &'a HashSet<String>,
+    env: &'a HashMap<String, String>,
+    expanded: HashMap<String, String>,
+    /// File variables being expanded, innermost last, to report cycles.
+    resolving: Vec<String>,
+    undefined: Vec<UndefinedReference>,
+}
+
+impl Expander<'_> {
+    fn lookup(&mut self, name: &str) -> Result<Option<String>, String> {
+        if let Some(value) = self.expanded.get(name) {
+            return Ok(Some(value.clone()));
+        }
+        let Some(raw) = self.file.get(name) else {
+            return Ok(self.env.get(name).cloned());
+        };
+        if self.literal.contains(name) {
+            self.expanded.insert(name.to_string(), raw.clone());
+            return Ok(Some(raw.clone()));
+        }
+        if let Some(start) = self.resolving.iter().position(|key| key == name) {
+            let mut cycle = self.resolving[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(format!("circular reference {}", cycle.join(" -> ")));
+        }
+
+        self.resolving.push(name.to_string());
+        let value = self.expand(raw);
+        self.resolving.pop();
+        let value = value?;
+        self.expanded.insert(name.to_string(), value.clone());
+        Ok(Some(value))
+    }
+
+    fn expand(&mut self, raw: &str) -> Result<String, String> {
+        let mut chars = raw.chars().peekable();
+        let mut value = String::new();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                value.push(c);
+                continue;
+            }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                    value.push('$');
+                }
+                Some('{') => {
+                    chars.next();
+                    value.push_str(&self.braced(&mut chars)?);
+                }
+                Some(&c) if is_name_start(c) => {
+                    let name = take_name(&mut chars);
+                    value.push_str(&self.resolve(&name, format!("${}", name))?);
+                }
+                _ => value.push('$'),
+            }
+        }
+        Ok(value)
+    }
+
+    /// Expands the rest of a `${...}` reference, after the `{`.
+    fn braced(&mut self, chars: &mut Peekable<Chars<'_>>) -> Result<String, String> {
+        let name = take_name(chars);
+        match chars.next() {
+            Some('}') if !name.is_empty() => self.resolve(&name, format!("${{{}}}", name)),
+            Some(':') if !name.is_empty() && chars.peek() == Some(&'-') => {
+                chars.next();
+                let default = take_default(chars)?;
+                match self.lookup(&name)? {
+                    Some(value) if !value.is_empty() => Ok(value),
+                    _ => self.expand(&default),
+                }
+            }
+            None => Err("unterminated `${`".to_string()),
+            Some(_) => Err(format!("invalid reference `${{{}`", name)),
+        }
+    }
+
+    /// The value of `name`, or `reference` as written when it is undefined.
+    fn resolve(&mut self, name: &str, reference: String) -> Result<String, String> {
+        if let Some(value) = self.lookup(name)? {
+            return Ok(value);
+        }
+        self.undefined.push(UndefinedReference {
+            key: self.resolving.last().cloned().unwrap_or_default(),
+            name: name.to_string(),
+        });
+        Ok(reference)
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn take_name(chars: &mut Peekable<Chars<'_>>) -> String {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek() {
+        if !(c.is_ascii_alphanumeric() || c == '_') || (name.is_empty() && !is_name_start(c)) {
+            break;
+        }
+        name.push(c);
+        chars.next();
+    }
+    name
+}
+
+/// The default of `${VAR:-default}` up to its closing brace, unexpanded.
+/// Braces of references nested in the default are matched.
+fn take_default(chars: &mut Peekable<Chars<'_>>) -> Result<String, String> {
+    let mut default = String::new();
+    let mut depth = 0;
+    for c in chars.by_ref() {
+        match c {
+            '}' if depth == 0 => return Ok(default),
+            '}' => depth -= 1,
+            '{' => depth += 1,
+            _ => {}
+        }
+        default.push(c);
+    }
+    Err("unterminated `${`".to_string())
+}
+
+/// Names that the values of `file` other than the keys in `literal` refer
+/// to, including references inside a `${VAR:-default}` default.
+pub(super) fn references(
+    file: &HashMap<String, String>,
+    literal: &HashSet<String>,
+) -> HashSet<String> {
+    let mut names = HashSet::new();
+    for (key, raw) in file {
+        if literal.contains(key) {
+            continue;
+        }
+        let mut chars = raw.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                continue;
+            }
+            match chars.peek() {
+                Some('$') => {
+                    chars.next();
+                }
+                Some('{') => {
+                    chars.next();
+                    names.insert(take_name(&mut chars));
+                }
+                Some(&c) if is_name_start(c) => {
+                    names.insert(take_name(&mut chars));
+                }
+                _ => {}
+            }
+        }
+    }
+    names
+}
+
+/// Expands every value of `file` except the keys in `literal`, along with
+/// the undefined references that were kept as written.
+pub(super) fn expand(
+    file: &HashMap<String, String>,
+    literal: &HashSet<String>,
+    env: &HashMap<String, String>,
+) -> Result<(HashMap<String, String>, Vec<UndefinedReference>), ExpandError> {
+    let mut expander = Expander {
+        file,
+        literal,
+        env,
+        expanded: HashMap::new(),
+        resolving: Vec::new(),
+        undefined: Vec::new(),
+    };
+    let mut keys: Vec<&String> = file.keys().collect();
+    keys.sort();
+    for key in keys {
+        expander.lookup(key).map_err(|reason| ExpandError {
+            key: key.clone(),
+            reason,
+        })?;
+    }
+    expander.undefined.sort();
+    expander.undefined.dedup();
+    Ok((expander.expanded, expander.undefined))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn expand_one(value: &str, env: &[(&str, &str)]) -> Result<String, String> {
+        let file = vars(&[("KEY", value)]);
+        expand(&file, &HashSet::new(), &vars(env))
+            .map(|(mut expanded, _)| expanded.remove("KEY").unwrap())
+            .map_err(|error| error.reason)
+    }
+
+    #[test]
+    fn test_expand_references_in_any_order() {
+        let file = vars(&[
+            ("URL", "https://${REGION}.syndica.io/$PATH_PART"),
+            ("REGION", "${ZONE}-1"),
+            ("ZONE", "eu"),
+            ("RAW", "${ZONE}"),
+        ]);
+        let literal = HashSet::from(["RAW".to_string()]);
+        let env = vars(&[("PATH_PART", "api"), ("ZONE", "us")]);
+
+        let (expanded, undefined) = expand(&file, &literal, &env).unwrap();
+        assert!(undefined.is_empty());
+        // The file's own ZONE wins over the environment's.
+        assert_eq!(expanded["URL"], "https://eu-1.syndica.io/api");
+        assert_eq!(expanded["REGION"], "eu-1");
+        assert_eq!(expanded["RAW"], "${ZONE}");
+
+        let referenced = references(&file, &literal);
+        assert_eq!(
+            referenced,
+            HashSet::from([
+                "REGION".to_string(),
+                "PATH_PART".to_string(),
+                "ZONE".to_string(),
+            ])
+        );
+        let nested = vars(&[("KEY", "pa$$word ${UNSET:-${INNER}}")]);
+        assert_eq!(
+            references(&nested, &HashSet::new()),
+            HashSet::from(["UNSET".to_string(), "INNER".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_expand_defaults_and_escapes() {
+        let env = [("SET", "value"), ("EMPTY", "")];
+        let cases = [
+            ("${SET:-fallback}", "value"),
+            ("${UNSET:-fallback}", "fallback"),
+            ("${EMPTY:-fallback}", "fallback"),
+            ("${UNSET:-}", ""),
+            ("${UNSET:-${OTHER:-${SET}}}", "value"),
+            ("${UNSET:-a{b}c}", "a{b}c"),
+            ("pa$$word", "pa$word"),
+            ("$${SET}", "${SET}"),
+            ("cost: 5$", "cost: 5$"),
+            ("$1 and $-", "$1 and $-"),
+            ("$SET.$SET", "value.value"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                expand_one(value, &env),
+                Ok(expected.to_string()),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_undefined_references_are_kept_as_written() {
+        let file = vars(&[
+            ("KEY", "pa$word"),
+            ("URL", "https://${HOST}/$word/${UNSET:-$PATH_PART}"),
+            ("SET", "${KEY}"),
+        ]);
+        let (expanded, undefined) = expand(&file, &HashSet::new(), &HashMap::new()).unwrap();
+        assert_eq!(expanded["KEY"], "pa$word");
+        assert_eq!(expanded["URL"], "https://${HOST}/$word/$PATH_PART");
+        assert_eq!(expanded["SET"], "pa$word");
+
+        let reference = |key: &str, name: &str| UndefinedReference {
+            key: key.to_string(),
+            name: name.to_string(),
+        };
+        assert_eq!(
+            undefined,
+            vec![
+                reference("KEY", "word"),
+                reference("URL", "HOST"),
+                reference("URL", "PATH_PART"),
+                reference("URL", "word"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_errors() {
+        let cases = [
+            ("${SET", "unterminated `${`"),
+            ("${UNSET:-open", "unterminated `${`"),
+            ("${}", "invalid reference `${`"),
+            ("${SET-x}", "invalid reference `${SET`"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(
+                expand_one(value, &[("SET", "value")]),
+                Err(expected.to_string()),
+                "{}",
+                value
+            );
+        }
+
+        let file = vars(&[("A", "${B}"), ("B", "x$A")]);
+        assert_eq!(
+            expand(&file, &HashSet::new(), &HashMap::new()),
+            Err(ExpandError {
+                key: "A".to_string(),
+                reason: "circular reference A -> B -> A".to_string(),
+            })
+        );
+    }
+}
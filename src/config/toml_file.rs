@@ -0,0 +1,376 @@
+//! Reader for the subset of TOML the config file needs: tables, dotted bare
+//! keys, strings, integers, floats, booleans and arrays of those. Arrays of
+//! tables and inline tables are rejected rather than misread.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// One `key = value` assignment, with its table prefix folded into `key`.
+/// Arrays are flattened to comma-separated values, the form list variables
+/// take in the environment.
+#[derive(Debug, PartialEq)]
+pub(super) struct Entry {
+    pub(super) key: String,
+    pub(super) value: String,
+    pub(super) line: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) struct TomlError {
+    pub(super) line: usize,
+    pub(super) key: Option<String>,
+    pub(super) reason: &'static str,
+}
+
+struct Cursor<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, reason: &'static str) -> TomlError {
+        TomlError {
+            line: self.line,
+            key: None,
+            reason,
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.bump();
+            }
+        }
+    }
+
+    /// Skips spaces, comments and line breaks, as allowed inside arrays.
+    fn skip_blank_lines(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some('\n' | '\r') => {
+                    self.bump();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Requires the rest of the line to be blank or a comment.
+    fn end_of_line(&mut self) -> Result<(), TomlError> {
+        self.skip_spaces();
+        self.skip_comment();
+        if self.peek() == Some('\r') {
+            self.bump();
+        }
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.bump();
+                Ok(())
+            }
+            Some(_) => Err(self.error("unexpected text after value")),
+        }
+    }
+
+    /// A bare key, possibly dotted (`retry.max_attempts`).
+    fn key(&mut self) -> Result<String, TomlError> {
+        let mut segments = Vec::new();
+        loop {
+            self.skip_spaces();
+            let mut segment = String::new();
+            while let Some(c) = self.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                    break;
+                }
+                segment.push(c);
+                self.bump();
+            }
+            if segment.is_empty() {
+                return Err(self.error("expected a key"));
+            }
+            segments.push(segment);
+            self.skip_spaces();
+            if self.peek() != Some('.') {
+                return Ok(segments.join("."));
+            }
+            self.bump();
+        }
+    }
+
+    fn value(&mut self) -> Result<String, TomlError> {
+        match self.peek() {
+            Some('"') => self.basic_string(),
+            Some('\'') => self.literal_string(),
+            Some('[') => self.array(),
+            Some('{') => Err(self.error("inline tables are not supported")),
+            _ => self.scalar(),
+        }
+    }
+
+    fn basic_string(&mut self) -> Result<String, TomlError> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some('"') => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some('\\') => {
+                    self.bump();
+                    match self.bump() {
+                        Some('"') => value.push('"'),
+                        Some('\\') => value.push('\\'),
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        _ => return Err(self.error("unsupported escape in string")),
+                    }
+                }
+                Some(c) => {
+                    self.bump();
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    fn literal_string(&mut self) -> Result<String, TomlError> {
+        self.bump();
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some('\n') | None => return Err(self.error("unterminated string")),
+                Some('\'') => {
+                    self.bump();
+                    return Ok(value);
+                }
+                Some(c) => {
+                    self.bump();
+                    value.push(c);
+                }
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<String, TomlError> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(']') => break,
+                Some('[') => return Err(self.error("nested arrays are not supported")),
+                None => return Err(self.error("unterminated array")),
+                _ => items.push(self.value()?),
+            }
+            self.skip_blank_lines();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => break,
+                _ => return Err(self.error("expected `,` or `]` in array")),
+            }
+        }
+        self.bump();
+        Ok(items.join(","))
+    }
+
+    /// Booleans and numbers, with `_` digit separators removed.
+    fn scalar(&mut self) -> Result<String, TomlError> {
+        let mut token = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, ',' | ']' | '#') {
+                break;
+            }
+            token.push(c);
+            self.bump();
+        }
+        if token == "true" || token == "false" {
+            return Ok(token);
+        }
+        let number = token.replace('_', "");
+        if !token.starts_with('_')
+            && !token.ends_with('_')
+            && (number.parse::<i64>().is_ok() || number.parse::<f64>().is_ok())
+        {
+            return Ok(number);
+        }
+        Err(self.error("invalid value"))
+    }
+}
+
+/// Parses `content` into its assignments in file order. Keys may not be
+/// assigned twice, whether written out in full or through a table.
+pub(super) fn parse(content: &str) -> Result<Vec<Entry>, TomlError> {
+    let mut cursor = Cursor {
+        chars: content.chars().peekable(),
+        line: 1,
+    };
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut table = String::new();
+
+    loop {
+        cursor.skip_blank_lines();
+        match cursor.peek() {
+            None => return Ok(entries),
+            Some('[') => {
+                cursor.bump();
+                if cursor.peek() == Some('[') {
+                    return Err(cursor.error("arrays of tables are not supported"));
+                }
+                table = cursor.key()?;
+                if cursor.bump() != Some(']') {
+                    return Err(cursor.error("expected `]` after table name"));
+                }
+                cursor.end_of_line()?;
+            }
+            Some(_) => {
+                let line = cursor.line;
+                let key = cursor.key()?;
+                let key = match table.as_str() {
+                    "" => key,
+                    table => format!("{}.{}", table, key),
+                };
+                let with_key = |mut err: TomlError| {
+                    err.key = Some(key.clone());
+                    err
+                };
+                if cursor.bump() != Some('=') {
+                    return Err(with_key(cursor.error("expected `=` after key")));
+                }
+                cursor.skip_spaces();
+                let value = cursor.value().map_err(with_key)?;
+                cursor.end_of_line().map_err(with_key)?;
+                if entries.iter().any(|entry| entry.key == key) {
+                    return Err(with_key(TomlError {
+                        line,
+                        key: None,
+                        reason: "duplicate key",
+                    }));
+                }
+                entries.push(Entry { key, value, line });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(content: &str) -> Vec<(String, String, usize)> {
+        parse(content)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.key, entry.value, entry.line))
+            .collect()
+    }
+
+    fn error(content: &str) -> String {
+        let err = parse(content).unwrap_err();
+        format!(
+            "{}:{}: {}",
+            err.line,
+            err.key.unwrap_or_default(),
+            err.reason
+        )
+    }
+
+    #[test]
+    fn test_parse_tables_and_values() {
+        let content = r#"
+# Top-level keys
+log_level = "info"   # trailing comment
+monitoring_depth = 1_000
+
+[server]
+port = 8081
+
+[metrics]
+backend = [
+    "tracing",  # the default
+    'prometheus',
+]
+sample_rate = 0.5
+slow.thresholds = ["get_blocks=2000"]
+rpc_insecure = false
+"#;
+
+        assert_eq!(
+            entries(content),
+            vec![
+                ("log_level".to_string(), "info".to_string(), 3),
+                ("monitoring_depth".to_string(), "1000".to_string(), 4),
+                ("server.port".to_string(), "8081".to_string(), 7),
+                (
+                    "metrics.backend".to_string(),
+                    "tracing,prometheus".to_string(),
+                    10
+                ),
+                ("metrics.sample_rate".to_string(), "0.5".to_string(), 14),
+                (
+                    "metrics.slow.thresholds".to_string(),
+                    "get_blocks=2000".to_string(),
+                    15
+                ),
+                ("metrics.rpc_insecure".to_string(), "false".to_string(), 16),
+            ]
+        );
+        assert_eq!(
+            entries("token = \"a \\\"quoted\\\" # value\""),
+            vec![("token".to_string(), "a \"quoted\" # value".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_report_line_and_key() {
+        for (content, expected) in [
+            (
+                "a = 1\n\nport = 80 80\n",
+                "3:port: unexpected text after value",
+            ),
+            ("[server]\nport = eighty", "2:server.port: invalid value"),
+            ("name = \"open\nnext = 1", "1:name: unterminated string"),
+            (
+                "a = 1\n[server]\nport = 1\n[server]\nport = 2",
+                "5:server.port: duplicate key",
+            ),
+            ("port 80", "1:port: expected `=` after key"),
+            ("list = [1, [2]]", "1:list: nested arrays are not supported"),
+            (
+                "\n[[rpc]]\nurl = \"x\"",
+                "2:: arrays of tables are not supported",
+            ),
+            (
+                "retry = { attempts = 3 }",
+                "1:retry: inline tables are not supported",
+            ),
+            ("= 1", "1:: expected a key"),
+        ] {
+            assert_eq!(error(content), expected, "{:?}", content);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use solana_sdk::signature::Signature;
+use tracing::{info, warn};
+
+use crate::syndica_client::{RpcCallCounts, RpcErrorKind, SolanaRpc};
+use crate::types::{BoxError, BoxFuture};
+
+/// Weight of the newest call in the latency and failure rate averages.
+const EWMA_ALPHA: f64 = 0.2;
+/// How much the failure rate inflates an endpoint's score: a rate of 0.1
+/// doubles it.
+const FAILURE_PENALTY: f64 = 10.0;
+/// Latencies below this count as equal, so that sub-millisecond jitter
+/// does not move calls between endpoints.
+const MIN_SCORED_LATENCY_MS: f64 = 1.0;
+/// Every this many calls one goes to another endpoint to refresh its stats.
+const PROBE_INTERVAL: u64 = 20;
+/// Another endpoint takes over only when its score is below this fraction
+/// of the active one's, so close scores do not flap.
+const SWITCH_MARGIN: f64 = 0.8;
+
+/// Observed health of one endpoint, as reported by `/stats`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct EndpointStats {
+    pub endpoint: usize,
+    pub active: bool,
+    pub calls: u64,
+    pub failures: u64,
+    /// Moving average of successful call latency, `None` until one answers.
+    pub ewma_latency_ms: Option<f64>,
+    /// Moving average of failed calls, from 0.0 to 1.0.
+    pub failure_rate: f64,
+    /// Lower is better; `None` until the endpoint answers.
+    pub score: Option<f64>,
+}
+
+impl EndpointStats {
+    fn record_success(&mut self, latency_ms: f64) {
+        self.calls += 1;
+        self.failure_rate *= 1.0 - EWMA_ALPHA;
+        self.ewma_latency_ms = Some(match self.ewma_latency_ms {
+            Some(average) => average + EWMA_ALPHA * (latency_ms - average),
+            None => latency_ms,
+        });
+    }
+
+    fn record_failure(&mut self) {
+        self.calls += 1;
+        self.failures += 1;
+        self.failure_rate += EWMA_ALPHA * (1.0 - self.failure_rate);
+    }
+
+    fn score(&self) -> Option<f64> {
+        let latency_ms = self.ewma_latency_ms?.max(MIN_SCORED_LATENCY_MS);
+        Some(latency_ms * (1.0 + FAILURE_PENALTY * self.failure_rate))
+    }
+}
+
+/// Spreads calls over several RPC endpoints, in the order of
+/// `SOLANA_RPC_URLS`.
+///
+/// Calls go to the active endpoint, the first one until another proves
+/// better. A call that times out, cannot connect, is rate limited or gets
+/// a 5xx answer is tried on the following endpoints in turn, and the first
+/// one to answer becomes the active endpoint. Any other error, such as a
+/// JSON-RPC error, is returned as is, since another endpoint would give the
+/// same answer.
+///
+/// Each endpoint keeps a moving average of its latency and failure rate.
+/// Every `PROBE_INTERVAL` calls one goes to another endpoint first, so
+/// that a faster or recovered endpoint is noticed, and after every answer
+/// the endpoint with the best score becomes active if it clearly beats the
+/// active one. A probe that fails with any error counts against the probed
+/// endpoint, and the call goes on to the active endpoint.
+pub struct FailoverClient {
+    endpoints: Vec<Arc<dyn SolanaRpc>>,
+    stats: Vec<Mutex<EndpointStats>>,
+    active: AtomicUsize,
+    calls: AtomicU64,
+}
+
+impl FailoverClient {
+    /// # Panics
+    ///
+    /// If `endpoints` is empty.
+    pub fn new(endpoints: Vec<Arc<dyn SolanaRpc>>) -> Self {
+        assert!(!endpoints.is_empty(), "FailoverClient needs an endpoint");
+        let stats = (0..endpoints.len())
+            .map(|endpoint| {
+                Mutex::new(EndpointStats {
+                    endpoint,
+                    ..EndpointStats::default()
+                })
+            })
+            .collect();
+        Self {
+            endpoints,
+            stats,
+            active: AtomicUsize::new(0),
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    /// Index of the endpoint calls currently go to first.
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    fn stats_of(&self, endpoint: usize) -> MutexGuard<'_, EndpointStats> {
+        self.stats[endpoint]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn fails_over(error: &BoxError) -> bool {
+        matches!(
+            RpcErrorKind::classify(error),
+            RpcErrorKind::Timeout
+                | RpcErrorKind::Connection
+                | RpcErrorKind::RateLimited
+                | RpcErrorKind::ServerError
+        )
+    }
+
+    /// The endpoint to probe on this call, if it is a probing call. Probes
+    /// take the other endpoints in turn.
+    fn probe_target(&self, active: usize) -> Option<usize> {
+        let len = self.endpoints.len();
+        let call = self.calls.fetch_add(1, Ordering::Relaxed) + 1;
+        if len < 2 || !call.is_multiple_of(PROBE_INTERVAL) {
+            return None;
+        }
+        let turn = (call / PROBE_INTERVAL - 1) as usize % (len - 1);
+        Some((active + 1 + turn) % len)
+    }
+
+    /// Makes the best scored endpoint active when it beats the active one
+    /// by `SWITCH_MARGIN`. Endpoints that never answered are not scored.
+    fn reselect(&self) {
+        let active = self.active();
+        let Some(active_score) = self.stats_of(active).score() else {
+            return;
+        };
+        let best = (0..self.endpoints.len())
+            .filter_map(|endpoint| Some((endpoint, self.stats_of(endpoint).score()?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((best, best_score)) = best
+            && best != active
+            && best_score < SWITCH_MARGIN * active_score
+        {
+            self.active.store(best, Ordering::Relaxed);
+            info!(
+                endpoint = best,
+                previous = active,
+                score = best_score,
+                previous_score = active_score,
+                "Switched to a healthier RPC endpoint"
+            );
+        }
+    }
+
+    async fn call<T>(
+        &self,
+        method: &'static str,
+        request: impl for<'a> Fn(&'a dyn SolanaRpc) -> BoxFuture<'a, Result<T, BoxError>>,
+    ) -> Result<T, BoxError> {
+        let len = self.endpoints.len();
+        let first = self.active();
+        let probe = self.probe_target(first);
+        let mut order: Vec<usize> = probe.into_iter().collect();
+        order.extend(
+            (0..len)
+                .map(|offset| (first + offset) % len)
+                .filter(|&endpoint| Some(endpoint) != probe),
+        );
+        let mut last_error = None;
+        for endpoint in order {
+            let started = Instant::now();
+            match request(self.endpoints[endpoint].as_ref()).await {
+                Ok(value) => {
+                    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+                    self.stats_of(endpoint).record_success(latency_ms);
+                    if endpoint != first && probe != Some(endpoint) {
+                        self.active.store(endpoint, Ordering::Relaxed);
+                        info!(endpoint, previous = first, "Switched RPC endpoint");
+                    }
+                    self.reselect();
+                    return Ok(value);
+                }
+                Err(e) if Self::fails_over(&e) || probe == Some(endpoint) => {
+                    self.stats_of(endpoint).record_failure();
+                    warn!(endpoint, method, error = %e, "RPC endpoint failed");
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| "no RPC endpoint configured".into()))
+    }
+}
+
+impl SolanaRpc for FailoverClient {
+    fn get_slot(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        Box::pin(self.call("getSlot", |client| client.get_slot()))
+    }
+
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> BoxFuture<'_, Result<Vec<u64>, BoxError>> {
+        Box::pin(self.call("getBlocks", move |client| {
+            client.get_blocks(start_slot, end_slot)
+        }))
+    }
+
+    fn get_block_time(&self, slot: u64) -> BoxFuture<'_, Result<Option<i64>, BoxError>> {
+        Box::pin(self.call("getBlockTime", move |client| client.get_block_time(slot)))
+    }
+
+    fn get_slot_for_signature(
+        &self,
+        signature: Signature,
+    ) -> BoxFuture<'_, Result<Option<u64>, BoxError>> {
+        Box::pin(self.call("getSignatureStatuses", move |client| {
+            client.get_slot_for_signature(signature)
+        }))
+    }
+
+    fn get_first_available_block(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        Box::pin(self.call("getFirstAvailableBlock", |client| {
+            client.get_first_available_block()
+        }))
+    }
+
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        let active = self.active();
+        (0..self.endpoints.len())
+            .map(|endpoint| {
+                let stats = self.stats_of(endpoint);
+                EndpointStats {
+                    active: endpoint == active,
+                    score: stats.score(),
+                    ..stats.clone()
+                }
+            })
+            .collect()
+    }
+
+    fn call_counts(&self) -> RpcCallCounts {
+        let mut counts = RpcCallCounts::default();
+        for endpoint in &self.endpoints {
+            counts += endpoint.call_counts();
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::testing::{FakeClient, status_client_error, timeout_client_error};
+
+    #[tokio::test]
+    async fn test_fails_over_and_stays_on_the_healthy_endpoint() {
+        let primary = Arc::new(FakeClient::new(100));
+        let backup = Arc::new(FakeClient::new(90));
+        let client = FailoverClient::new(vec![
+            primary.clone() as Arc<dyn SolanaRpc>,
+            backup.clone() as Arc<dyn SolanaRpc>,
+        ]);
+
+        assert_eq!(client.get_slot().await.unwrap(), 100);
+
+        primary.fail_next(status_client_error(503).await.into());
+        assert_eq!(client.get_slot().await.unwrap(), 90);
+        assert_eq!(client.active(), 1);
+        assert_eq!(client.get_slot().await.unwrap(), 90);
+        assert_eq!(primary.get_slot_calls(), 2);
+        assert_eq!(backup.get_slot_calls(), 2);
+
+        // Failing over wraps around to the first endpoint.
+        backup.fail_next(timeout_client_error().await.into());
+        assert_eq!(client.get_slot().await.unwrap(), 100);
+        assert_eq!(client.active(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_returns_errors_another_endpoint_would_repeat() {
+        let primary = Arc::new(FakeClient::new(100));
+        let backup = Arc::new(FakeClient::new(90));
+        let client = FailoverClient::new(vec![
+            primary.clone() as Arc<dyn SolanaRpc>,
+            backup.clone() as Arc<dyn SolanaRpc>,
+        ]);
+
+        primary.set_failing(true);
+        assert!(client.get_blocks(0, 10).await.is_err());
+        assert_eq!(backup.get_blocks_calls(), 0);
+        assert_eq!(client.active(), 0);
+
+        // When every endpoint fails the last error is returned.
+        primary.fail_next(status_client_error(503).await.into());
+        backup.fail_next(status_client_error(429).await.into());
+        primary.set_failing(false);
+        let error = client.get_slot().await.unwrap_err();
+        assert_eq!(RpcErrorKind::classify(&error), RpcErrorKind::RateLimited);
+    }
+
+    #[tokio::test]
+    async fn test_failed_probe_falls_through_to_the_active_endpoint() {
+        let primary = Arc::new(FakeClient::new(100));
+        let backup = Arc::new(FakeClient::new(90));
+        let client = FailoverClient::new(vec![
+            primary.clone() as Arc<dyn SolanaRpc>,
+            backup.clone() as Arc<dyn SolanaRpc>,
+        ]);
+
+        // The backup answers with an error the primary would not repeat.
+        backup.set_failing(true);
+        for _ in 0..PROBE_INTERVAL {
+            assert_eq!(client.get_slot().await.unwrap(), 100);
+        }
+
+        assert_eq!(backup.get_slot_calls(), 1);
+        assert_eq!(client.active(), 0);
+        let stats = client.endpoint_stats();
+        assert_eq!((stats[1].calls, stats[1].failures), (1, 1));
+        assert_eq!(stats[0].calls, PROBE_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn test_prefers_the_faster_endpoint() {
+        let slow = Arc::new(FakeClient::new(100));
+        slow.set_latency(Duration::from_millis(10));
+        let fast = Arc::new(FakeClient::new(100));
+        let client = FailoverClient::new(vec![
+            slow.clone() as Arc<dyn SolanaRpc>,
+            fast.clone() as Arc<dyn SolanaRpc>,
+        ]);
+
+        for _ in 0..100 {
+            client.get_slot().await.unwrap();
+        }
+
+        // The slow endpoint keeps the calls until the first probe finds the
+        // fast one, then only gets the probes.
+        assert_eq!(client.active(), 1);
+        assert!(
+            fast.get_slot_calls() > 2 * slow.get_slot_calls(),
+            "fast {} slow {}",
+            fast.get_slot_calls(),
+            slow.get_slot_calls()
+        );
+        let stats = client.endpoint_stats();
+        assert!(stats[1].active && !stats[0].active);
+        assert_eq!(stats[0].calls + stats[1].calls, 100);
+        assert!(stats[0].ewma_latency_ms.unwrap() >= 10.0);
+        assert!(stats[1].score.unwrap() < stats[0].score.unwrap());
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::info;
+
+use crate::logic::SyndicaAppLogic;
+
+/// One heartbeat line. RPC counts cover the time since the previous one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeartbeatSnapshot {
+    pub tip_slot: u64,
+    pub sync_lag: u64,
+    pub cache_size: usize,
+    pub hit_ratio_1m: Option<f64>,
+    pub rpc_calls: u64,
+    pub rpc_errors: u64,
+    pub queue_depth: usize,
+}
+
+/// Periodic one-line status log for deployments without a metrics scraper.
+///
+/// Everything is read from in-memory state, so a heartbeat never makes an
+/// RPC call and keeps logging while the endpoint is down.
+pub struct Heartbeat {
+    logic: Arc<SyndicaAppLogic>,
+    previous_rpc_counts: (u64, u64),
+}
+
+impl Heartbeat {
+    pub fn new(logic: Arc<SyndicaAppLogic>) -> Self {
+        let previous_rpc_counts = logic.state().rpc_call_counts();
+        Self {
+            logic,
+            previous_rpc_counts,
+        }
+    }
+
+    pub fn snapshot(&mut self) -> HeartbeatSnapshot {
+        let state = self.logic.state();
+        let (calls, errors) = state.rpc_call_counts();
+        let (previous_calls, previous_errors) =
+            std::mem::replace(&mut self.previous_rpc_counts, (calls, errors));
+
+        HeartbeatSnapshot {
+            tip_slot: state.last_processed_slot(),
+            sync_lag: state.sync_lag(),
+            cache_size: state.cache().len(),
+            hit_ratio_1m: state
+                .metrics()
+                .cache_hit_ratios()
+                .and_then(|ratios| ratios.ratio_1m),
+            rpc_calls: calls - previous_calls,
+            rpc_errors: errors - previous_errors,
+            queue_depth: state.interval_queue().len(),
+        }
+    }
+
+    pub fn log(&mut self) {
+        let snapshot = self.snapshot();
+        info!(
+            target: "heartbeat",
+            tip_slot = snapshot.tip_slot,
+            sync_lag = snapshot.sync_lag,
+            cache_size = snapshot.cache_size,
+            hit_ratio_1m = snapshot.hit_ratio_1m,
+            rpc_calls = snapshot.rpc_calls,
+            rpc_errors = snapshot.rpc_errors,
+            queue_depth = snapshot.queue_depth,
+            "Heartbeat"
+        );
+    }
+
+    /// Logs a heartbeat every `period`, starting one period from now. The
+    /// task holds no resources beyond `logic`, so aborting it is safe.
+    pub fn spawn(logic: Arc<SyndicaAppLogic>, period: Duration) -> JoinHandle<()> {
+        let mut heartbeat = Self::new(logic);
+        tokio::spawn(async move {
+            let mut heartbeat_timer = tokio::time::interval(period);
+            heartbeat_timer.tick().await;
+            loop {
+                heartbeat_timer.tick().await;
+                heartbeat.log();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::PrometheusMetrics;
+    use crate::synchronizer::SlotInterval;
+    use crate::testing::{FakeClient, test_state_with_metrics};
+
+    #[tokio::test]
+    async fn test_snapshot_reads_state_and_rpc_deltas() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed([10]));
+        let state =
+            test_state_with_metrics(client.clone(), 100, Arc::new(PrometheusMetrics::new()));
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()));
+
+        logic.update_latest_slot().await.unwrap();
+        let mut heartbeat = Heartbeat::new(logic.clone());
+
+        logic.get_block(10).await.unwrap();
+        logic.get_block(10).await.unwrap();
+        client.set_failing(true);
+        assert!(logic.get_latest_slot().await.is_err());
+        state.set_sync_lag(12);
+        state.interval_queue().push(SlotInterval::new(0, 99));
+
+        let snapshot = heartbeat.snapshot();
+        assert_eq!(
+            snapshot,
+            HeartbeatSnapshot {
+                tip_slot: 1_000,
+                sync_lag: 12,
+                cache_size: 1,
+                hit_ratio_1m: Some(0.5),
+                rpc_calls: 2,
+                rpc_errors: 1,
+                queue_depth: 1,
+            }
+        );
+
+        let snapshot = heartbeat.snapshot();
+        assert_eq!((snapshot.rpc_calls, snapshot.rpc_errors), (0, 0));
+        assert_eq!(client.get_slot_calls(), 2);
+    }
+}
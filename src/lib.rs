@@ -1,9 +1,18 @@
 pub mod cache;
+pub mod cli;
 pub mod config;
+pub mod failover;
+pub mod heartbeat;
 pub mod logic;
 pub mod metrics;
+pub mod pruner;
+pub mod redact;
+pub mod reload;
 pub mod server;
 pub mod state;
 pub mod synchronizer;
 pub mod syndica_client;
+#[cfg(test)]
+pub mod testing;
 pub mod types;
+pub mod webhook;
@@ -1,8 +1,104 @@
-use crate::state::AppState;
+use crate::cache::BlockMetadata;
+use crate::config::DEFAULT_MONITOR_INTERVAL_MS;
+use crate::metrics::names;
+use crate::state::{Activity, AppState};
+use crate::syndica_client::RpcErrorKind;
 use crate::types::BoxError;
-use std::sync::Arc;
-use std::time::Instant;
-use tracing::{debug, info, warn};
+use crate::webhook::{SlotConfirmed, WebhookSender};
+use serde::Serialize;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
+
+/// Solana's target slot time, used to estimate block times without RPC.
+pub const NOMINAL_SLOT_DURATION_MS: i64 = 400;
+pub const DEFAULT_STARTUP_HEALTHCHECK_RETRIES: u32 = 5;
+pub const DEFAULT_STARTUP_HEALTHCHECK_DELAY_MS: u64 = 500;
+const STARTUP_HEALTHCHECK_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Extra `getSlot` calls made when the endpoint reports a slot below the
+/// latest one seen, before keeping the latest one instead.
+const SLOT_REGRESSION_RETRIES: u32 = 1;
+/// How long `getSlot`, `getBlocks` or cache inserts may go without a success
+/// before `/health` reports the monitor as stalled.
+pub const DEFAULT_MAX_STALENESS_SECS: u64 = 120;
+/// Sync lag, in slots, above which `/readyz` reports the monitor as not
+/// ready: about a minute of slots.
+pub const DEFAULT_MAX_READY_LAG: u64 = 150;
+/// Slots past the last known tip a queried slot may be before it is
+/// answered as in the future: about a minute of slots, so a tip that is a
+/// few polls behind the chain does not turn away slots that exist.
+pub const DEFAULT_FUTURE_SLOT_TOLERANCE: u64 = 150;
+/// Longest a slot may take from being queued at the tip to being cached
+/// before it counts as an SLA violation.
+pub const DEFAULT_CONFIRMATION_SLA_MS: u64 = 10_000;
+/// Shortest time between two confirmation latency samples, each of which
+/// costs a `getBlockTime` call.
+pub const DEFAULT_CONFIRMATION_LATENCY_SAMPLE_MS: u64 = 10_000;
+/// Widest slot range `/confirmedSlots` answers in one request. The response
+/// is streamed, so this bounds the work per request rather than memory.
+pub const DEFAULT_MAX_RANGE_SLOTS: u64 = 1_000_000;
+
+/// How `SyndicaAppLogic::get_block` verifies a single slot on a cache miss.
+///
+/// Providers price and rate-limit RPC methods differently, so operators can
+/// pick whichever call is cheapest for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfirmationStrategy {
+    /// Check whether the slot appears in `getBlocks(slot, slot)`.
+    #[default]
+    GetBlocks,
+    /// Ask for the slot's block directly via `getBlockTime`.
+    GetBlock,
+}
+
+impl FromStr for ConfirmationStrategy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "get_blocks" => Ok(ConfirmationStrategy::GetBlocks),
+            "get_block" => Ok(ConfirmationStrategy::GetBlock),
+            other => Err(format!("unknown confirmation strategy: {}", other)),
+        }
+    }
+}
+
+/// Which slots the synchronizer keeps in sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncMode {
+    /// Backfill the whole monitoring window, then follow the tip.
+    #[default]
+    Full,
+    /// Start at the tip seen on the first tick and only follow newly
+    /// produced slots, never requesting older history.
+    TipOnly,
+}
+
+impl SyncMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncMode::Full => "full",
+            SyncMode::TipOnly => "tip-only",
+        }
+    }
+}
+
+impl FromStr for SyncMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "full" => Ok(SyncMode::Full),
+            "tip-only" => Ok(SyncMode::TipOnly),
+            other => Err(format!("unknown sync mode: {}", other)),
+        }
+    }
+}
 
 /// Business logic layer for the Syndica application.
 ///
@@ -24,11 +120,119 @@ use tracing::{debug, info, warn};
 /// - Provides a single point for all business metrics
 pub struct SyndicaAppLogic {
     state: Arc<AppState>,
+    confirmation_strategy: ConfirmationStrategy,
+    monitoring_depth: AtomicU64,
+    monitor_interval_ms: AtomicU64,
+    sync_mode: SyncMode,
+    serve_stale_on_error: bool,
+    max_staleness: Duration,
+    max_ready_lag: u64,
+    future_slot_tolerance: u64,
+    confirmation_sla: Duration,
+    confirmation_latency_sample: Duration,
+    last_latency_sample: Mutex<Option<Instant>>,
+    max_range_slots: u64,
+    /// When the slot updater queued each tip interval, keyed by its first
+    /// slot with its last slot. Pruned as the window moves past them.
+    queued_at: Mutex<BTreeMap<u64, (u64, Instant)>>,
+    webhook: Option<WebhookSender>,
 }
 
 impl SyndicaAppLogic {
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        Self {
+            state,
+            confirmation_strategy: ConfirmationStrategy::default(),
+            monitoring_depth: AtomicU64::new(0),
+            monitor_interval_ms: AtomicU64::new(DEFAULT_MONITOR_INTERVAL_MS),
+            sync_mode: SyncMode::default(),
+            serve_stale_on_error: false,
+            max_staleness: Duration::from_secs(DEFAULT_MAX_STALENESS_SECS),
+            max_ready_lag: DEFAULT_MAX_READY_LAG,
+            future_slot_tolerance: DEFAULT_FUTURE_SLOT_TOLERANCE,
+            confirmation_sla: Duration::from_millis(DEFAULT_CONFIRMATION_SLA_MS),
+            confirmation_latency_sample: Duration::from_millis(
+                DEFAULT_CONFIRMATION_LATENCY_SAMPLE_MS,
+            ),
+            last_latency_sample: Mutex::new(None),
+            max_range_slots: DEFAULT_MAX_RANGE_SLOTS,
+            queued_at: Mutex::new(BTreeMap::new()),
+            webhook: None,
+        }
+    }
+
+    pub fn with_confirmation_strategy(mut self, strategy: ConfirmationStrategy) -> Self {
+        self.confirmation_strategy = strategy;
+        self
+    }
+
+    pub fn with_monitoring_depth(mut self, monitoring_depth: usize) -> Self {
+        self.monitoring_depth = AtomicU64::new(monitoring_depth as u64);
+        self
+    }
+
+    pub fn with_monitor_interval_ms(mut self, monitor_interval_ms: u64) -> Self {
+        self.monitor_interval_ms = AtomicU64::new(monitor_interval_ms);
+        self
+    }
+
+    pub fn with_sync_mode(mut self, sync_mode: SyncMode) -> Self {
+        self.sync_mode = sync_mode;
+        self
+    }
+
+    /// When enabled, RPC failures for slots inside the monitoring window are
+    /// answered from the cache instead of being surfaced as errors.
+    pub fn with_serve_stale_on_error(mut self, enabled: bool) -> Self {
+        self.serve_stale_on_error = enabled;
+        self
+    }
+
+    /// Longest any tracked activity may go without a success before the
+    /// monitor is reported unhealthy.
+    pub fn with_max_staleness(mut self, max_staleness: Duration) -> Self {
+        self.max_staleness = max_staleness;
+        self
+    }
+
+    /// Sync lag, in slots, above which the monitor is not ready to serve.
+    pub fn with_max_ready_lag(mut self, max_ready_lag: u64) -> Self {
+        self.max_ready_lag = max_ready_lag;
+        self
+    }
+
+    /// Slots past the last known tip a queried slot may be before it is
+    /// answered as in the future, see `is_future_slot`.
+    pub fn with_future_slot_tolerance(mut self, future_slot_tolerance: u64) -> Self {
+        self.future_slot_tolerance = future_slot_tolerance;
+        self
+    }
+
+    /// Time from a slot being queued at the tip to being cached above which
+    /// it counts as an SLA violation.
+    pub fn with_confirmation_sla(mut self, confirmation_sla: Duration) -> Self {
+        self.confirmation_sla = confirmation_sla;
+        self
+    }
+
+    /// Shortest time between two confirmation latency samples; zero
+    /// disables sampling, and with it the extra `getBlockTime` calls.
+    pub fn with_confirmation_latency_sample(mut self, period: Duration) -> Self {
+        self.confirmation_latency_sample = period;
+        self
+    }
+
+    /// Widest slot range a single `/confirmedSlots` request may ask for.
+    pub fn with_max_range_slots(mut self, max_range_slots: u64) -> Self {
+        self.max_range_slots = max_range_slots;
+        self
+    }
+
+    /// Queues a `SlotConfirmed` event for every slot `query_slot_range`
+    /// newly confirms.
+    pub fn with_webhook(mut self, webhook: WebhookSender) -> Self {
+        self.webhook = Some(webhook);
+        self
     }
 }
 
@@ -37,51 +241,422 @@ impl SyndicaAppLogic {
         &self.state
     }
 
+    pub fn max_staleness(&self) -> Duration {
+        self.max_staleness
+    }
+
+    pub fn max_ready_lag(&self) -> u64 {
+        self.max_ready_lag
+    }
+
+    pub fn max_range_slots(&self) -> u64 {
+        self.max_range_slots
+    }
+
+    /// Healthy, and no further behind the last known tip than
+    /// `max_ready_lag` slots.
+    pub fn is_ready_at(&self, now: Instant) -> bool {
+        self.is_healthy_at(now) && self.state.sync_lag() <= self.max_ready_lag
+    }
+
+    /// Whether every tracked activity has succeeded within the staleness
+    /// bound as of `now`.
+    pub fn is_healthy_at(&self, now: Instant) -> bool {
+        Activity::ALL
+            .iter()
+            .all(|activity| self.state.since_last_success_at(*activity, now) <= self.max_staleness)
+    }
+
+    /// Slots behind the latest processed slot that are kept synchronized.
+    pub fn monitoring_depth(&self) -> u64 {
+        self.monitoring_depth.load(Ordering::Relaxed)
+    }
+
+    /// How often the synchronizer polls for the latest slot.
+    pub fn monitor_interval_ms(&self) -> u64 {
+        self.monitor_interval_ms.load(Ordering::Relaxed)
+    }
+
+    /// Changes the poll interval at runtime. The slot updater and the
+    /// workers read it on every pass, so it applies from their next tick.
+    pub fn set_monitor_interval_ms(&self, monitor_interval_ms: u64) {
+        let previous = self
+            .monitor_interval_ms
+            .swap(monitor_interval_ms, Ordering::Relaxed);
+        info!(previous, monitor_interval_ms, "Changed monitor interval");
+    }
+
+    pub fn sync_mode(&self) -> SyncMode {
+        self.sync_mode
+    }
+
+    /// Changes the monitoring depth at runtime and scales the cache
+    /// capacity with it, keeping the headroom `CACHE_CAPACITY` or its
+    /// default gave over the window, and returns the cache capacity now in
+    /// effect. The capacity never drops below the depth. The synchronizer
+    /// reads the depth on every tick, so the window follows on its next
+    /// update. Callers must reject a depth of zero.
+    pub fn set_monitoring_depth(&self, monitoring_depth: usize) -> usize {
+        let previous = self
+            .monitoring_depth
+            .swap(monitoring_depth as u64, Ordering::Relaxed);
+        let cache = self.state.cache();
+        let scaled_capacity = (cache.capacity() as u64)
+            .saturating_mul(monitoring_depth as u64)
+            .div_ceil(previous.max(1));
+        let cache_capacity = cache.resize((scaled_capacity as usize).max(monitoring_depth));
+        info!(
+            previous,
+            monitoring_depth, cache_capacity, "Changed monitoring depth"
+        );
+        cache_capacity
+    }
+
+    pub fn is_within_monitoring_window(&self, slot: u64) -> bool {
+        let last_processed_slot = self.state.last_processed_slot();
+        slot <= last_processed_slot
+            && slot >= last_processed_slot.saturating_sub(self.monitoring_depth())
+    }
+
+    /// Whether `slot` is more than `future_slot_tolerance` slots past the
+    /// last known tip, so it cannot have a block yet. Always `false` before
+    /// the first slot update.
+    pub fn is_future_slot(&self, slot: u64) -> bool {
+        let last_processed_slot = self.state.last_processed_slot();
+        last_processed_slot > 0
+            && slot > last_processed_slot.saturating_add(self.future_slot_tolerance)
+    }
+
+    /// Whether a failed lookup of `slot` may fall back to the cached answer.
+    pub fn can_serve_stale(&self, slot: u64) -> bool {
+        self.serve_stale_on_error && self.is_within_monitoring_window(slot)
+    }
+
+    /// The endpoint's latest slot, never below the last processed slot.
+    ///
+    /// Load-balanced endpoints may answer from a node that lags behind the
+    /// one that answered before. A slot below `last_processed_slot` is
+    /// retried up to `SLOT_REGRESSION_RETRIES` times, then the last
+    /// processed slot is returned in its place, so the synchronizer never
+    /// sees the tip move backwards.
     pub async fn get_latest_slot(&self) -> Result<u64, BoxError> {
-        let result = self.state.client().get_slot().await;
+        let previous = self.state.last_processed_slot();
+        let mut slot = self.fetch_slot().await?;
+        for attempt in 1..=SLOT_REGRESSION_RETRIES {
+            if slot >= previous {
+                break;
+            }
+            warn!(slot, previous, attempt, "getSlot went backwards, retrying");
+            slot = self.fetch_slot().await?;
+        }
+        Ok(self.accept_latest_slot(slot, previous))
+    }
+
+    /// `slot`, or `previous` when `slot` is below it, recorded as the
+    /// latest slot.
+    fn accept_latest_slot(&self, slot: u64, previous: u64) -> u64 {
+        let slot = if slot < previous {
+            warn!(
+                slot,
+                previous, "Latest slot went backwards, keeping the previous slot"
+            );
+            self.state
+                .metrics()
+                .increment(names::SLOT_REGRESSIONS, &[], 1);
+            previous
+        } else {
+            slot
+        };
+
+        self.state
+            .metrics()
+            .set_gauge(names::LATEST_SLOT, &[], slot as f64);
+        debug!(slot, "Retrieved latest slot");
+        slot
+    }
 
+    async fn fetch_slot(&self) -> Result<u64, BoxError> {
+        let result = self.state.client().get_slot().await;
+        self.record_rpc_result("getSlot", &result);
         match &result {
-            Ok(slot) => {
-                self.state.metrics().record_latest_slot(*slot);
-                debug!(slot = *slot, "Retrieved latest slot");
-            }
-            Err(e) => {
-                warn!(error = %e, "Failed to get latest slot");
-            }
+            Ok(_) => self.record_success(Activity::GetSlot),
+            Err(e) => warn!(error = %e, "Failed to get latest slot"),
         }
         result
     }
 
+    /// The slot of the transaction with `signature`, or `None` if the
+    /// endpoint does not know the signature. Not cached, since a signature
+    /// is usually looked up once.
+    pub async fn get_signature_slot(&self, signature: Signature) -> Result<Option<u64>, BoxError> {
+        let result = self.state.client().get_slot_for_signature(signature).await;
+        self.record_rpc_result("getSignatureStatuses", &result);
+        result
+    }
+
     pub async fn get_block(&self, slot: u64) -> Result<Option<u64>, BoxError> {
         if self.state.cache().contains(slot) {
-            self.state.metrics().record_cache_hit(true);
+            self.state
+                .metrics()
+                .increment(names::CACHE_LOOKUPS, &[("result", "hit")], 1);
             return Ok(Some(slot));
         }
-        self.state.metrics().record_cache_hit(false);
+        self.state
+            .metrics()
+            .increment(names::CACHE_LOOKUPS, &[("result", "miss")], 1);
+
+        match self.fetch_block(slot).await? {
+            Some(metadata) => {
+                self.cache_confirmed(slot, metadata);
+                Ok(Some(slot))
+            }
+            None => Ok(None),
+        }
+    }
 
-        let start_time = Instant::now();
-        let blocks = self.state.client().get_blocks(slot, slot).await?;
+    /// Checks `slot` against the RPC endpoint whatever the cache holds, and
+    /// brings the cache in line with the answer: a confirmed slot is
+    /// cached, and a cached slot the endpoint no longer confirms, as after
+    /// a reorg, is removed. On an RPC error the cache is left as it was.
+    pub async fn verify_block(&self, slot: u64) -> Result<Option<u64>, BoxError> {
+        match self.fetch_block(slot).await? {
+            Some(metadata) => {
+                self.cache_confirmed(slot, metadata);
+                Ok(Some(slot))
+            }
+            None => {
+                if self.state.cache().remove(slot) {
+                    warn!(slot, "Cached slot is no longer confirmed, removed it");
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Asks the RPC endpoint whether `slot` has a block, using the
+    /// configured confirmation strategy.
+    async fn fetch_block(&self, slot: u64) -> Result<Option<BlockMetadata>, BoxError> {
+        let confirmed = match self.confirmation_strategy {
+            ConfirmationStrategy::GetBlocks => {
+                let start_time = Instant::now();
+                let result = self.state.client().get_blocks(slot, slot).await;
+                self.state
+                    .metrics()
+                    .record_duration(names::GET_BLOCKS, &[], start_time.elapsed());
+                self.record_rpc_result("getBlocks", &result);
+                let blocks = result?;
+                self.record_success(Activity::GetBlocks);
+
+                blocks.contains(&slot).then(BlockMetadata::default)
+            }
+            ConfirmationStrategy::GetBlock => {
+                let result = self.state.client().get_block_time(slot).await;
+                self.record_rpc_result("getBlockTime", &result);
+                let block_time = result?;
+                if let Some(block_time) = block_time {
+                    self.refresh_slot_time_reference(slot, block_time);
+                }
+                block_time.map(|block_time| BlockMetadata {
+                    block_time: Some(block_time),
+                    ..BlockMetadata::default()
+                })
+            }
+        };
+        Ok(confirmed)
+    }
+
+    /// Block time of `slot` from `getBlockTime`, or `None` if the slot has
+    /// no block. A cached block time is returned without an RPC call.
+    pub async fn get_slot_time(&self, slot: u64) -> Result<Option<i64>, BoxError> {
+        if let Some(block_time) = self
+            .state
+            .cache()
+            .metadata(slot)
+            .and_then(|metadata| metadata.block_time)
+        {
+            return Ok(Some(block_time));
+        }
+
+        let result = self.state.client().get_block_time(slot).await;
+        self.record_rpc_result("getBlockTime", &result);
+        let block_time = result?;
+        if let Some(block_time) = block_time {
+            self.refresh_slot_time_reference(slot, block_time);
+            self.cache_confirmed(
+                slot,
+                BlockMetadata {
+                    block_time: Some(block_time),
+                    ..BlockMetadata::default()
+                },
+            );
+        }
+        Ok(block_time)
+    }
+
+    /// Estimates the block time of `slot` from the reference slot and the
+    /// nominal slot duration, without an RPC call. `None` until a real
+    /// block time has been seen.
+    ///
+    /// Slots are often slower than nominal, so the error grows with the
+    /// distance from the reference slot.
+    pub fn estimate_slot_time(&self, slot: u64) -> Option<i64> {
+        let (reference_slot, reference_time) = self.state.slot_time_reference()?;
+        let slot_offset = slot as i64 - reference_slot as i64;
+        Some(reference_time + (slot_offset * NOMINAL_SLOT_DURATION_MS).div_euclid(1000))
+    }
+
+    /// Moves the estimation reference to `slot` if it is newer than the
+    /// current one, keeping estimates for recent slots accurate.
+    pub fn refresh_slot_time_reference(&self, slot: u64, block_time: i64) {
+        let newer = self
+            .state
+            .slot_time_reference()
+            .is_none_or(|(reference_slot, _)| slot >= reference_slot);
+        if newer {
+            self.state.set_slot_time_reference(slot, block_time);
+        }
+    }
+
+    /// Checks which of `slots` are cached, recording a single aggregated
+    /// hit/miss metric for the whole batch.
+    pub fn cached_slots(&self, slots: &[u64]) -> Vec<bool> {
+        let present = self.state.cache().contains_many(slots);
+        let hits = present.iter().filter(|hit| **hit).count();
         self.state
             .metrics()
-            .record_get_blocks_elapsed(start_time.elapsed());
+            .record_cache_lookups(hits, present.len() - hits);
+        present
+    }
+
+    /// Confirms a batch of slots, answering cached slots directly and
+    /// checking the rest with one `get_blocks` call per contiguous run.
+    pub async fn confirm_slots(&self, slots: &[u64]) -> Result<HashMap<u64, bool>, BoxError> {
+        let mut confirmed = HashMap::with_capacity(slots.len());
+        let mut missing = Vec::new();
+        for (slot, cached) in slots.iter().zip(self.cached_slots(slots)) {
+            if cached {
+                confirmed.insert(*slot, true);
+            } else {
+                missing.push(*slot);
+            }
+        }
+        missing.sort_unstable();
+        missing.dedup();
+
+        for (start_slot, end_slot) in Self::contiguous_ranges(&missing) {
+            let blocks = self.get_blocks(start_slot, end_slot).await?;
+            for slot in start_slot..=end_slot {
+                confirmed.insert(slot, false);
+            }
+            for slot in blocks {
+                self.cache_confirmed(slot, BlockMetadata::default());
+                confirmed.insert(slot, true);
+            }
+        }
+
+        Ok(confirmed)
+    }
 
-        if blocks.contains(&slot) {
-            self.state.cache().insert(slot);
-            Ok(Some(slot))
+    /// Inclusive ranges of slots in `start..=end` that are not in the cache.
+    ///
+    /// The cache only holds confirmed blocks, so a gap is either a slot the
+    /// synchronizer has not confirmed yet or a skipped slot; telling them
+    /// apart needs an RPC call, which this does not make.
+    pub fn find_gaps(&self, start: u64, end: u64) -> Vec<(u64, u64)> {
+        if start > end {
+            return Vec::new();
+        }
+
+        let mut gaps = Vec::new();
+        let mut next = start;
+        for slot in self.state.cache().cached_in_range(start, end) {
+            if slot > next {
+                gaps.push((next, slot - 1));
+            }
+            next = slot + 1;
+        }
+        if next <= end {
+            gaps.push((next, end));
+        }
+        gaps
+    }
+
+    /// Whether each of the last `n` slots up to the latest processed slot is
+    /// cached, oldest first. `n` is capped at the monitoring depth, and like
+    /// `find_gaps` this reads only the cache.
+    pub fn recent_slots(&self, n: usize) -> Vec<(u64, bool)> {
+        let n = (n as u64).min(self.monitoring_depth());
+        if n == 0 {
+            return Vec::new();
+        }
+        let end = self.state.last_processed_slot();
+        let start = end.saturating_sub(n - 1);
+        // Peeks, so dashboards polling this do not change what gets evicted.
+        let cached = self.state.cache().cached_in_range(start, end);
+        (start..=end)
+            .map(|slot| (slot, cached.binary_search(&slot).is_ok()))
+            .collect()
+    }
+
+    /// The monitoring window ending at the latest processed slot.
+    pub fn monitoring_window(&self) -> (u64, u64) {
+        let last_processed_slot = self.state.last_processed_slot();
+        (
+            last_processed_slot.saturating_sub(self.monitoring_depth()),
+            last_processed_slot,
+        )
+    }
+
+    /// Collapses sorted, deduplicated slots into inclusive contiguous ranges.
+    fn contiguous_ranges(slots: &[u64]) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for &slot in slots {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == slot => *end = slot,
+                _ => ranges.push((slot, slot)),
+            }
+        }
+        ranges
+    }
+
+    /// Confirmed slots in `start..=end`, sorted ascending. The part inside
+    /// the monitoring window is read from the cache; anything older is
+    /// fetched with one `getBlocks` call, so callers keep ranges within the
+    /// provider's limit.
+    pub async fn confirmed_slots_in_range(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u64>, BoxError> {
+        let (window_start, _) = self.monitoring_window();
+        let mut slots = if start < window_start {
+            self.get_blocks(start, end.min(window_start - 1)).await?
         } else {
-            Ok(None)
+            Vec::new()
+        };
+        if end >= window_start {
+            slots.extend(
+                self.state
+                    .cache()
+                    .cached_in_range(start.max(window_start), end),
+            );
         }
+        Ok(slots)
     }
 
     pub async fn get_blocks(&self, start_slot: u64, end_slot: u64) -> Result<Vec<u64>, BoxError> {
         let start_time = Instant::now();
         let result = self.state.client().get_blocks(start_slot, end_slot).await;
         let elapsed = start_time.elapsed();
+        self.record_rpc_result("getBlocks", &result);
 
-        self.state.metrics().record_get_blocks_elapsed(elapsed);
+        self.state
+            .metrics()
+            .record_duration(names::GET_BLOCKS, &[], elapsed);
 
         match &result {
             Ok(blocks) => {
+                self.record_success(Activity::GetBlocks);
                 debug!(
                     start_slot,
                     end_slot,
@@ -104,6 +679,150 @@ impl SyndicaAppLogic {
         result
     }
 
+    /// Caches a confirmed slot and publishes it to subscribers if it was
+    /// not cached yet.
+    fn cache_confirmed(&self, slot: u64, metadata: BlockMetadata) -> bool {
+        let inserted = self.state.cache().upsert(slot, metadata);
+        if inserted {
+            self.state.publish_confirmed_slot(slot);
+            self.record_success(Activity::CacheInsert);
+            self.record_time_to_cache(slot);
+        }
+        inserted
+    }
+
+    /// Notes that the slot updater queued the newly produced slots
+    /// `start..=end`, so that the time until each is cached can be checked
+    /// against the confirmation SLA. Backfilled history is not noted: it
+    /// was produced long before it was queued.
+    pub fn record_slots_queued(&self, start: u64, end: u64) {
+        self.record_slots_queued_at(start, end, Instant::now());
+    }
+
+    pub(crate) fn record_slots_queued_at(&self, start: u64, end: u64, at: Instant) {
+        let window_start = self
+            .state
+            .last_processed_slot()
+            .saturating_sub(self.monitoring_depth());
+        let mut queued_at = self
+            .queued_at
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        queued_at.retain(|_, (last, _)| *last >= window_start);
+        queued_at.insert(start, (end, at));
+    }
+
+    /// Records how long `slot` took from being queued to being cached, and
+    /// whether that broke the confirmation SLA. Slots that were never
+    /// queued at the tip are skipped.
+    fn record_time_to_cache(&self, slot: u64) {
+        let queued = self
+            .queued_at
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .range(..=slot)
+            .next_back()
+            .filter(|(_, (last, _))| slot <= *last)
+            .map(|(_, &(_, at))| at);
+        let Some(queued) = queued else {
+            return;
+        };
+
+        let elapsed = queued.elapsed();
+        let metrics = self.state.metrics();
+        metrics.record_time_to_cache(elapsed);
+        if elapsed > self.confirmation_sla {
+            metrics.record_confirmation_sla_violation();
+            debug!(
+                slot,
+                elapsed_ms = elapsed.as_millis() as u64,
+                sla_ms = self.confirmation_sla.as_millis() as u64,
+                "Slot cached after the confirmation SLA"
+            );
+        }
+    }
+
+    /// Records how long after its block time `slot` was cached, at the cost
+    /// of one `getBlockTime` call. Only slots at the tip are sampled, at
+    /// most once per `confirmation_latency_sample`: backfilled history would
+    /// measure its own age rather than how fresh the monitor is. A failed
+    /// lookup skips the sample.
+    async fn measure_confirmation_latency(&self, slot: u64) {
+        let Some(inserted_at) = self.state.cache().inserted_at(slot) else {
+            return;
+        };
+        if !self.latency_sample_due() {
+            return;
+        }
+        let result = self.state.client().get_block_time(slot).await;
+        self.record_rpc_result("getBlockTime", &result);
+        let block_time = match result {
+            Ok(Some(block_time)) => block_time,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(slot, error = %e, "Failed to get block time for confirmation latency");
+                return;
+            }
+        };
+        self.refresh_slot_time_reference(slot, block_time);
+        self.state.cache().upsert(
+            slot,
+            BlockMetadata {
+                block_time: Some(block_time),
+                ..BlockMetadata::default()
+            },
+        );
+
+        let latency = confirmation_latency(inserted_at, block_time);
+        self.state.record_confirmation_latency(latency);
+        self.state.metrics().record_confirmation_latency(latency);
+        debug!(
+            slot,
+            latency_ms = latency.as_millis() as u64,
+            "Measured confirmation latency"
+        );
+    }
+
+    /// Whether a confirmation latency sample may be taken now, claiming the
+    /// slot for it if so.
+    fn latency_sample_due(&self) -> bool {
+        if self.confirmation_latency_sample.is_zero() {
+            return false;
+        }
+        let mut last_sample = self
+            .last_latency_sample
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let due = last_sample.is_none_or(|at| at.elapsed() >= self.confirmation_latency_sample);
+        if due {
+            *last_sample = Some(Instant::now());
+        }
+        due
+    }
+
+    /// Marks `activity` as having just succeeded, in `AppState` and metrics.
+    fn record_success(&self, activity: Activity) {
+        self.state.record_success(activity);
+        self.state
+            .metrics()
+            .record_last_success(activity.as_str(), SystemTime::now());
+    }
+
+    /// Counts an RPC call in `AppState` and reports a failure to metrics.
+    fn record_rpc_result<T>(&self, method: &str, result: &Result<T, BoxError>) {
+        self.state.record_rpc_call(result.is_ok());
+        if let Err(error) = result {
+            self.state.metrics().increment(
+                names::RPC_ERRORS,
+                &[
+                    ("method", method),
+                    ("kind", RpcErrorKind::classify(error).as_str()),
+                ],
+                1,
+            );
+        }
+    }
+
     pub async fn update_latest_slot(&self) -> Result<u64, BoxError> {
         let current_slot = self.get_latest_slot().await?;
         self.state.set_last_processed_slot(current_slot);
@@ -113,21 +832,129 @@ impl SyndicaAppLogic {
         Ok(current_slot)
     }
 
+    /// Like `update_latest_slot` with a slot pushed by a slot subscription
+    /// instead of one fetched with `getSlot`. The notification counts as a
+    /// `get_slot` success for `/health`.
+    pub fn update_latest_slot_from(&self, slot: u64) -> u64 {
+        let current_slot = self.accept_latest_slot(slot, self.state.last_processed_slot());
+        self.record_success(Activity::GetSlot);
+        self.state.set_last_processed_slot(current_slot);
+        current_slot
+    }
+
+    /// Calls `getSlot` until it succeeds, retrying up to `retries` times.
+    ///
+    /// The delay before a retry starts at `delay` and doubles after every
+    /// failed attempt, capped at 30 seconds. Returns the last error once all
+    /// attempts have failed.
+    pub async fn startup_health_check(
+        &self,
+        retries: u32,
+        delay: Duration,
+    ) -> Result<u64, BoxError> {
+        let attempts = retries.saturating_add(1);
+        let mut delay = delay;
+        for attempt in 1..=attempts {
+            info!(attempt, attempts, "Checking RPC endpoint");
+            match self.get_latest_slot().await {
+                Ok(slot) => {
+                    info!(attempt, slot, "RPC endpoint is reachable");
+                    return Ok(slot);
+                }
+                Err(e) if attempt < attempts => {
+                    warn!(
+                        attempt,
+                        attempts,
+                        retry_in_ms = delay.as_millis(),
+                        error = %e,
+                        "RPC health check failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(STARTUP_HEALTHCHECK_MAX_DELAY);
+                }
+                Err(e) => {
+                    error!(attempts, error = %e, "RPC health check failed, giving up");
+                    return Err(e);
+                }
+            }
+        }
+        unreachable!("at least one health check attempt is made")
+    }
+
+    /// Checks a `START_FROM_SLOT` of `slot` against the `tip` seen at
+    /// startup, and moves it up to the oldest slot the endpoint still has a
+    /// block for, since purged history cannot be backfilled.
+    pub async fn resolve_start_slot(&self, slot: u64, tip: u64) -> Result<u64, BoxError> {
+        if slot > tip {
+            return Err(
+                format!("START_FROM_SLOT {} is above the current tip {}", slot, tip).into(),
+            );
+        }
+        let result = self.state.client().get_first_available_block().await;
+        self.record_rpc_result("getFirstAvailableBlock", &result);
+        let first_available = result?;
+        if first_available > slot {
+            warn!(
+                start_from_slot = slot,
+                first_available,
+                "START_FROM_SLOT is older than the endpoint's first available block, starting there"
+            );
+            return Ok(first_available.min(tip));
+        }
+        Ok(slot)
+    }
+
+    /// Publishes how far `covered_slot` trails the latest known slot.
+    pub fn record_sync_lag(&self, covered_slot: u64) -> u64 {
+        let lag = self
+            .state
+            .last_processed_slot()
+            .saturating_sub(covered_slot);
+        self.state.set_sync_lag(lag);
+        self.state
+            .metrics()
+            .set_gauge(names::SYNC_LAG, &[], lag as f64);
+        lag
+    }
+
+    /// Caches the confirmed slots in `start_slot..=end_slot`. When the range
+    /// reaches the latest processed slot, the newest slot it adds is also
+    /// sampled for confirmation latency.
     pub async fn query_slot_range(
         &self,
         start_slot: u64,
         end_slot: u64,
     ) -> Result<usize, BoxError> {
+        let reaches_tip = end_slot >= self.state.last_processed_slot();
         let confirmed_blocks = self.get_blocks(start_slot, end_slot).await?;
 
-        let mut inserted_count = 0;
+        let mut inserted = Vec::new();
         for block_slot in confirmed_blocks {
-            if !self.state.cache().contains(block_slot) && self.state.cache().insert(block_slot) {
-                inserted_count += 1;
+            if self.cache_confirmed(block_slot, BlockMetadata::default()) {
+                inserted.push(block_slot);
+            }
+        }
+        let newest_inserted = inserted.iter().max().copied();
+        if let Some(slot) = newest_inserted.filter(|_| reaches_tip) {
+            self.measure_confirmation_latency(slot).await;
+        }
+        // Sent after the latency sample, which, when one is due, refreshes
+        // the time reference with the real block time of the newest slot.
+        // Block times are estimates, see `SlotConfirmed::block_time`.
+        if let Some(webhook) = &self.webhook {
+            for &slot in &inserted {
+                webhook.notify(SlotConfirmed {
+                    slot,
+                    block_time: self.estimate_slot_time(slot),
+                });
             }
         }
 
+        let inserted_count = inserted.len();
         if inserted_count > 0 {
+            self.state
+                .metrics()
+                .increment(names::SLOTS_INSERTED, &[], inserted_count as u64);
             info!(
                 start_slot,
                 end_slot,
@@ -140,3 +967,491 @@ impl SyndicaAppLogic {
         Ok(inserted_count)
     }
 }
+
+/// Time from `block_time` (Unix seconds) to `confirmed_at`, zero if the
+/// block time is later, as it can be by up to a second of clock skew.
+fn confirmation_latency(confirmed_at: SystemTime, block_time: i64) -> Duration {
+    let block_time = UNIX_EPOCH + Duration::from_secs(block_time.max(0) as u64);
+    confirmed_at.duration_since(block_time).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::BlockCache;
+    use crate::metrics::{InMemoryMetrics, NoOpMetrics};
+    use crate::testing::{
+        FakeClient, status_client_error, test_logic, test_state_with_metrics, timeout_client_error,
+    };
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn test_depth_change_keeps_cache_headroom() {
+        let state =
+            test_state_with_metrics(Arc::new(FakeClient::new(100)), 1_250, Arc::new(NoOpMetrics));
+        let logic = SyndicaAppLogic::new(state).with_monitoring_depth(1_000);
+
+        assert_eq!(logic.set_monitoring_depth(2_000), 2_500);
+        assert_eq!(logic.set_monitoring_depth(400), 500);
+        assert_eq!(logic.state().cache().capacity(), 500);
+        assert_eq!(logic.monitoring_depth(), 400);
+    }
+
+    #[tokio::test]
+    async fn test_get_blocks_strategy() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10]));
+        let logic = test_logic(client.clone());
+
+        assert_eq!(logic.get_block(10).await.unwrap(), Some(10));
+        assert_eq!(logic.get_block(11).await.unwrap(), None);
+        assert_eq!(client.get_blocks_calls(), 2);
+        assert_eq!(client.get_block_time_calls(), 0);
+
+        assert_eq!(logic.get_block(10).await.unwrap(), Some(10));
+        assert_eq!(client.get_blocks_calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_block_strategy() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10]));
+        let logic =
+            test_logic(client.clone()).with_confirmation_strategy(ConfirmationStrategy::GetBlock);
+
+        assert_eq!(logic.get_block(10).await.unwrap(), Some(10));
+        assert_eq!(logic.get_block(11).await.unwrap(), None);
+        assert_eq!(client.get_block_time_calls(), 2);
+        assert_eq!(client.get_blocks_calls(), 0);
+        assert_eq!(
+            logic.state().cache().metadata(10).unwrap().block_time,
+            Some(FakeClient::block_time(10))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rpc_errors_are_counted_by_kind() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10]));
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let logic = SyndicaAppLogic::new(test_state_with_metrics(
+            client.clone(),
+            1000,
+            metrics.clone(),
+        ));
+
+        client.fail_next(timeout_client_error().await.into());
+        assert!(logic.get_latest_slot().await.is_err());
+        client.fail_next(status_client_error(429).await.into());
+        assert!(logic.get_blocks(0, 10).await.is_err());
+        client.fail_next(status_client_error(429).await.into());
+        assert!(logic.get_block(11).await.is_err());
+
+        assert_eq!(
+            metrics.rpc_errors(),
+            vec![
+                ("getSlot".to_string(), "timeout".to_string()),
+                ("getBlocks".to_string(), "rate_limited".to_string()),
+                ("getBlocks".to_string(), "rate_limited".to_string()),
+            ]
+        );
+        assert_eq!(metrics.total_rpc_calls(), 3);
+        assert_eq!(metrics.latest_slot(), None);
+    }
+
+    #[tokio::test]
+    async fn test_slow_slots_count_as_sla_violations() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10, 11, 20, 30]));
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let logic = SyndicaAppLogic::new(test_state_with_metrics(
+            client.clone(),
+            1000,
+            metrics.clone(),
+        ))
+        .with_confirmation_sla(Duration::from_secs(1));
+
+        logic.record_slots_queued_at(10, 12, Instant::now() - Duration::from_secs(5));
+        logic.record_slots_queued(20, 20);
+        assert_eq!(logic.query_slot_range(0, 40).await.unwrap(), 4);
+
+        // Slot 30 was never queued at the tip, so it is not measured.
+        let recorded = metrics.snapshot();
+        assert_eq!(recorded.durations_named(names::TIME_TO_CACHE).len(), 3);
+        assert_eq!(
+            recorded.counter_total(names::CONFIRMATION_SLA_VIOLATIONS, &[]),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_latest_slot_never_goes_backwards() {
+        let client = Arc::new(FakeClient::new(1_000));
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let logic = SyndicaAppLogic::new(test_state_with_metrics(
+            client.clone(),
+            1000,
+            metrics.clone(),
+        ));
+        assert_eq!(logic.update_latest_slot().await.unwrap(), 1_000);
+
+        // A lagging answer is retried and the retry is accepted.
+        client.push_slots([990]);
+        assert_eq!(logic.update_latest_slot().await.unwrap(), 1_000);
+        assert_eq!(client.get_slot_calls(), 3);
+
+        // Lagging answers on every attempt keep the previous slot.
+        client.push_slots([990, 980, 1_010, 1_005, 1_003]);
+        assert_eq!(logic.update_latest_slot().await.unwrap(), 1_000);
+        assert_eq!(logic.state().last_processed_slot(), 1_000);
+        assert_eq!(logic.update_latest_slot().await.unwrap(), 1_010);
+        assert_eq!(logic.update_latest_slot().await.unwrap(), 1_010);
+        assert_eq!(logic.state().last_processed_slot(), 1_010);
+        assert_eq!(client.get_slot_calls(), 8);
+
+        assert_eq!(
+            metrics
+                .snapshot()
+                .counter_total(names::SLOT_REGRESSIONS, &[]),
+            2
+        );
+        assert_eq!(metrics.latest_slot(), Some(1_010));
+    }
+
+    #[tokio::test]
+    async fn test_startup_health_check_retries_until_success() {
+        let client = Arc::new(FakeClient::new(100));
+        let logic = test_logic(client.clone());
+
+        client.fail_next_calls(2);
+        let slot = logic
+            .startup_health_check(3, Duration::from_millis(1))
+            .await
+            .unwrap();
+        assert_eq!(slot, 100);
+        assert_eq!(client.get_slot_calls(), 3);
+
+        client.fail_next_calls(2);
+        assert!(
+            logic
+                .startup_health_check(1, Duration::from_millis(1))
+                .await
+                .is_err()
+        );
+        assert_eq!(client.get_slot_calls(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_start_slot_checks_tip_and_clamps_to_ledger() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let logic = test_logic(client.clone());
+
+        assert_eq!(
+            logic.resolve_start_slot(5_000, 10_000).await.unwrap(),
+            5_000
+        );
+        assert_eq!(
+            logic
+                .resolve_start_slot(10_001, 10_000)
+                .await
+                .unwrap_err()
+                .to_string(),
+            "START_FROM_SLOT 10001 is above the current tip 10000"
+        );
+
+        client.set_first_available_block(7_000);
+        assert_eq!(
+            logic.resolve_start_slot(5_000, 10_000).await.unwrap(),
+            7_000
+        );
+        assert_eq!(
+            logic.resolve_start_slot(8_000, 10_000).await.unwrap(),
+            8_000
+        );
+
+        client.fail_next_calls(1);
+        assert!(logic.resolve_start_slot(8_000, 10_000).await.is_err());
+        assert_eq!(client.get_first_available_block_calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_estimated_slot_time_tracks_actual_nearby() {
+        let client = Arc::new(FakeClient::new(2_000).with_confirmed([1_000]));
+        let logic = test_logic(client.clone());
+        assert_eq!(logic.estimate_slot_time(1_000), None);
+
+        assert_eq!(
+            logic.get_slot_time(1_000).await.unwrap(),
+            Some(FakeClient::block_time(1_000))
+        );
+        assert_eq!(logic.state().slot_time_reference().unwrap().0, 1_000);
+
+        for slot in 950..=1_050 {
+            let estimated = logic.estimate_slot_time(slot).unwrap();
+            let actual = FakeClient::block_time(slot);
+            assert!((estimated - actual).abs() <= 1, "slot {}", slot);
+        }
+        assert_eq!(client.get_block_time_calls(), 1);
+
+        logic.refresh_slot_time_reference(900, 0);
+        assert_eq!(logic.state().slot_time_reference().unwrap().0, 1_000);
+        logic.refresh_slot_time_reference(1_100, FakeClient::block_time(1_100));
+        assert_eq!(
+            logic.estimate_slot_time(1_105),
+            Some(FakeClient::block_time(1_105))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_lags_beyond_broadcast_buffer() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed(0..10));
+        let state = AppState::new(
+            Arc::new(BlockCache::new(100)),
+            client,
+            Arc::new(NoOpMetrics),
+        )
+        .with_slot_broadcast_buffer(4);
+        let logic = SyndicaAppLogic::new(Arc::new(state));
+        let mut subscriber = logic.state().subscribe_confirmed_slots();
+
+        assert_eq!(logic.query_slot_range(0, 9).await.unwrap(), 10);
+        assert_eq!(
+            subscriber.recv().await,
+            Err(broadcast::error::RecvError::Lagged(6))
+        );
+        for slot in 6..10 {
+            assert_eq!(subscriber.recv().await, Ok(slot));
+        }
+
+        // Slots already cached are not published again.
+        assert_eq!(logic.query_slot_range(0, 9).await.unwrap(), 0);
+        assert_eq!(
+            subscriber.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_find_gaps() {
+        let client = Arc::new(FakeClient::new(100));
+        let logic = test_logic(client);
+        for slot in [10, 11, 12, 15, 18, 19, 20] {
+            logic.state().cache().insert(slot);
+        }
+
+        assert_eq!(logic.find_gaps(10, 20), vec![(13, 14), (16, 17)]);
+        assert_eq!(
+            logic.find_gaps(8, 22),
+            vec![(8, 9), (13, 14), (16, 17), (21, 22)]
+        );
+        assert!(logic.find_gaps(11, 12).is_empty());
+        assert_eq!(logic.find_gaps(30, 40), vec![(30, 40)]);
+        assert!(logic.find_gaps(20, 10).is_empty());
+    }
+
+    #[test]
+    fn test_recent_slots() {
+        let client = Arc::new(FakeClient::new(100));
+        let logic = test_logic(client).with_monitoring_depth(5);
+        for slot in [2, 8, 10] {
+            logic.state().cache().insert(slot);
+        }
+        logic.state().set_last_processed_slot(10);
+
+        assert_eq!(
+            logic.recent_slots(3),
+            vec![(8, true), (9, false), (10, true)]
+        );
+        assert_eq!(logic.recent_slots(50).len(), 5);
+        assert!(logic.recent_slots(0).is_empty());
+
+        logic.state().set_last_processed_slot(2);
+        assert_eq!(
+            logic.recent_slots(5),
+            vec![(0, false), (1, false), (2, true)]
+        );
+    }
+
+    #[test]
+    fn test_recent_slots_do_not_promote() {
+        let state =
+            test_state_with_metrics(Arc::new(FakeClient::new(100)), 3, Arc::new(NoOpMetrics));
+        let logic = SyndicaAppLogic::new(state).with_monitoring_depth(5);
+        for slot in [2, 8, 10] {
+            logic.state().cache().insert(slot);
+        }
+        logic.state().set_last_processed_slot(2);
+        assert_eq!(logic.recent_slots(1), vec![(2, true)]);
+
+        // Slot 2 is still the least recently used entry.
+        logic.state().cache().insert(11);
+        assert!(!logic.state().cache().peek(2));
+        assert!(logic.state().cache().peek(8));
+    }
+
+    #[tokio::test]
+    async fn test_confirm_slots_scattered() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10, 30]));
+        let logic = test_logic(client.clone());
+
+        let confirmed = logic.confirm_slots(&[30, 10, 20]).await.unwrap();
+
+        assert_eq!(
+            client.requested_ranges(),
+            vec![(10, 10), (20, 20), (30, 30)]
+        );
+        assert_eq!(confirmed.len(), 3);
+        assert!(confirmed[&10]);
+        assert!(!confirmed[&20]);
+        assert!(confirmed[&30]);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_slots_clustered() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([5, 6, 8, 12, 13]));
+        let logic = test_logic(client.clone());
+        logic.state().cache().insert(13);
+
+        let confirmed = logic.confirm_slots(&[8, 5, 6, 7, 6, 12, 13]).await.unwrap();
+
+        assert_eq!(client.requested_ranges(), vec![(5, 8), (12, 12)]);
+        assert_eq!(confirmed.len(), 6);
+        assert!(confirmed[&5] && confirmed[&6] && confirmed[&8]);
+        assert!(!confirmed[&7]);
+        assert!(confirmed[&12] && confirmed[&13]);
+        assert!(logic.state().cache().contains(8));
+        assert!(!logic.state().cache().contains(7));
+    }
+
+    #[test]
+    fn test_confirmation_strategy_from_str() {
+        assert_eq!(
+            "get_blocks".parse::<ConfirmationStrategy>(),
+            Ok(ConfirmationStrategy::GetBlocks)
+        );
+        assert_eq!(
+            "GET_BLOCK".parse::<ConfirmationStrategy>(),
+            Ok(ConfirmationStrategy::GetBlock)
+        );
+        assert!("getSlot".parse::<ConfirmationStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_confirmation_latency_from_block_time() {
+        let confirmed_at = UNIX_EPOCH + Duration::from_millis(1_700_000_002_500);
+        assert_eq!(
+            confirmation_latency(confirmed_at, 1_700_000_000),
+            Duration::from_millis(2_500)
+        );
+        assert_eq!(
+            confirmation_latency(confirmed_at, 1_700_000_003),
+            Duration::ZERO
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_latency_is_sampled_at_the_tip() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([10, 11, 98, 99]));
+        let metrics = Arc::new(InMemoryMetrics::new());
+        let logic = SyndicaAppLogic::new(test_state_with_metrics(
+            client.clone(),
+            1000,
+            metrics.clone(),
+        ));
+        logic.state().set_last_processed_slot(100);
+
+        // Backfilled history is not sampled.
+        assert_eq!(logic.query_slot_range(0, 20).await.unwrap(), 2);
+        assert_eq!(client.get_block_time_calls(), 0);
+        assert_eq!(logic.state().confirmation_latency(), None);
+
+        let before = SystemTime::now();
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 2);
+        assert_eq!(client.get_block_time_calls(), 1);
+        let expected = confirmation_latency(before, FakeClient::block_time(99));
+        let latency = logic.state().confirmation_latency().unwrap();
+        assert!(latency >= expected && latency - expected < Duration::from_secs(5));
+        assert_eq!(
+            logic.state().cache().metadata(99).unwrap().block_time,
+            Some(FakeClient::block_time(99))
+        );
+        let recorded = metrics.snapshot();
+        let samples = recorded.durations_named(names::CONFIRMATION_LATENCY);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].value, latency);
+
+        // Nothing new was cached, so there is nothing to sample.
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 0);
+        assert_eq!(client.get_block_time_calls(), 1);
+
+        // The average rolls over a fixed window of samples.
+        let state = logic.state();
+        for _ in 0..100 {
+            state.record_confirmation_latency(Duration::from_millis(800));
+        }
+        state.record_confirmation_latency(Duration::from_millis(1_000));
+        state.record_confirmation_latency(Duration::from_millis(1_000));
+        assert_eq!(
+            state.confirmation_latency(),
+            Some(Duration::from_millis(804))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_latency_samples_are_spaced_out() {
+        let client = Arc::new(FakeClient::new(200).with_confirmed([99, 150]));
+        let logic = test_logic(client.clone());
+        logic.state().set_last_processed_slot(100);
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 1);
+        assert_eq!(client.get_block_time_calls(), 1);
+
+        // A new tip slot within the sample period is cached without one.
+        logic.state().set_last_processed_slot(150);
+        assert_eq!(logic.query_slot_range(140, 150).await.unwrap(), 1);
+        assert_eq!(client.get_block_time_calls(), 1);
+
+        let client = Arc::new(FakeClient::new(200).with_confirmed([99]));
+        let logic = test_logic(client.clone()).with_confirmation_latency_sample(Duration::ZERO);
+        logic.state().set_last_processed_slot(100);
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 1);
+        assert_eq!(client.get_block_time_calls(), 0);
+        assert_eq!(logic.state().confirmation_latency(), None);
+    }
+
+    #[tokio::test]
+    async fn test_newly_confirmed_slots_are_sent_to_the_webhook() {
+        let client = Arc::new(FakeClient::new(100).with_confirmed([97, 99]));
+        let (webhook, mut events) = WebhookSender::channel(16);
+        let logic = test_logic(client).with_webhook(webhook);
+        logic.state().set_last_processed_slot(100);
+
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 2);
+        assert_eq!(logic.query_slot_range(90, 100).await.unwrap(), 0);
+
+        // The newest slot sets the time reference the other is estimated
+        // from, two nominal slots (800ms) earlier.
+        let block_time = FakeClient::block_time(99);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SlotConfirmed {
+                slot: 97,
+                block_time: Some(block_time - 1),
+            }
+        );
+        assert_eq!(
+            events.try_recv().unwrap(),
+            SlotConfirmed {
+                slot: 99,
+                block_time: Some(block_time),
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_sync_mode_from_str() {
+        assert_eq!("full".parse::<SyncMode>(), Ok(SyncMode::Full));
+        assert_eq!("Tip-Only".parse::<SyncMode>(), Ok(SyncMode::TipOnly));
+        assert!("tip_only".parse::<SyncMode>().is_err());
+        assert_eq!(
+            serde_json::to_value(SyncMode::TipOnly).unwrap(),
+            serde_json::json!("tip-only")
+        );
+    }
+}
@@ -1,65 +1,383 @@
 use solana_block_monitor::{
-    cache::BlockCache, config::Config, logic::SyndicaAppLogic, metrics::TracingMetrics,
-    server::start_server, state::AppState, synchronizer::Synchronizer,
-    syndica_client::SyndicaClient,
+    cache::BlockCache,
+    cli::{self, Command},
+    config::Config,
+    failover::FailoverClient,
+    heartbeat::Heartbeat,
+    logic::{SyncMode, SyndicaAppLogic},
+    metrics::{
+        CompositeMetrics, Metrics, MetricsBackend, OpenTelemetryMetrics, OtlpExporter,
+        OtlpSpanLayer, PrometheusMetrics, RunTotals, StatsdMetrics, TracingMetrics,
+    },
+    pruner::CachePruner,
+    reload::ConfigReloader,
+    server::start_server,
+    state::AppState,
+    synchronizer::Synchronizer,
+    syndica_client::{SolanaRpc, SyndicaClient, TlsOptions},
+    types::BoxError,
+    webhook::{WebhookDispatcher, WebhookSender},
 };
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{info, warn};
+use tracing_subscriber::{Layer, layer::SubscriberExt, reload, util::SubscriberInitExt};
+
+/// How long the synchronizer gets on shutdown to finish the intervals
+/// its workers are processing.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let config = Config::load().await?;
+async fn main() -> Result<(), BoxError> {
+    let args = match cli::parse_args(std::env::args().skip(1))? {
+        Command::Run(args) => args,
+        Command::Help => {
+            print!("{}", cli::help());
+            return Ok(());
+        }
+    };
+    let config =
+        match Config::load_with_overrides(args.env_file.as_deref(), args.overrides.clone()).await {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+        };
+    if let Some(format) = args.print_config {
+        for warning in &config.warnings {
+            eprintln!("warning: {}", warning);
+        }
+        print!("{}", config.print(format));
+        return Ok(());
+    }
+
+    let otlp_enabled = config
+        .metrics_backends
+        .contains(&MetricsBackend::OpenTelemetry);
+    let span_layer = (otlp_enabled && config.otel_traces_enabled).then(OtlpSpanLayer::new);
 
-    tracing_subscriber::fmt()
-        .with_writer(std::io::stdout)
-        .with_max_level(config.get_tracing_level())
+    let (log_filter, log_filter_handle) = reload::Layer::new(config.tracing_filter());
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stdout)
+                .with_filter(log_filter),
+        )
+        .with(span_layer.clone())
         .init();
 
-    info!("Loaded configuration from .env file:");
-    info!("  Solana RPC URL: {}", config.solana_rpc_url);
-    info!("  Server Port: {}", config.server_port);
-    info!("  Log Level: {}", config.log_level);
-    info!("  Monitor Interval: {}ms", config.monitor_interval_ms);
-
-    let cache = Arc::new(BlockCache::new(config.monitoring_depth));
-    let client = Arc::new(SyndicaClient::new(
-        config.solana_rpc_url.clone(),
-        config.solana_rpc_key.clone(),
-    ));
-    let metrics = Arc::new(TracingMetrics::new());
-    let state = Arc::new(AppState::new(
-        cache.clone(),
-        client.clone(),
-        metrics.clone(),
-    ));
-    let logic: Arc<SyndicaAppLogic> = Arc::new(SyndicaAppLogic::new(state));
-
-    let mut synchronizer = Synchronizer::new(
-        logic.clone(),
-        config.monitor_interval_ms,
-        config.monitoring_depth,
+    match (&config.env_file, &config.env_overlay) {
+        (Some(path), Some(overlay)) => info!(
+            env_file = %path.display(),
+            env_overlay = %overlay.display(),
+            "Loaded env file, then merged the profile overlay over it"
+        ),
+        (Some(path), None) => info!(env_file = %path.display(), "Loaded env file"),
+        (None, Some(overlay)) => {
+            info!(env_overlay = %overlay.display(), "Loaded the profile overlay only")
+        }
+        (None, None) => info!("No env file found, reading the environment only"),
+    }
+    info!("{}", config.summary());
+    for warning in &config.warnings {
+        warn!("{}", warning);
+    }
+
+    let mut cache = BlockCache::new(config.cache_capacity);
+    if let Some(max_memory_bytes) = config.cache_max_memory_bytes {
+        cache = cache.with_max_memory_bytes(max_memory_bytes);
+    }
+    info!(
+        capacity = cache.capacity(),
+        estimated_memory_bytes = cache.capacity_memory_bytes(),
+        "Block cache sized"
+    );
+    let cache = Arc::new(cache);
+    let tls = TlsOptions {
+        ca_cert_path: config.rpc_ca_cert.clone(),
+        insecure: config.rpc_insecure,
+    };
+    let mut endpoints = Vec::new();
+    for endpoint in &config.rpc_endpoints {
+        let endpoint: Arc<dyn SolanaRpc> = Arc::new(
+            SyndicaClient::new_with_tls(
+                endpoint.url.clone(),
+                endpoint.key.clone().unwrap_or_default(),
+                &tls,
+            )?
+            .with_max_concurrency(config.rpc_max_concurrency),
+        );
+        endpoints.push(endpoint);
+    }
+    let client: Arc<dyn SolanaRpc> = if endpoints.len() == 1 {
+        endpoints.remove(0)
+    } else {
+        info!(
+            endpoints = endpoints.len(),
+            "Failing over between RPC endpoints"
+        );
+        Arc::new(FailoverClient::new(endpoints))
+    };
+    // Stops the metrics flush tasks once the synchronizer has drained, so
+    // their final flush includes everything it recorded.
+    let (metrics_shutdown, metrics_shutdown_receiver) = watch::channel(false);
+    let mut metrics_tasks = Vec::new();
+    let mut otlp_exporter = None;
+    let mut backends: Vec<Arc<dyn Metrics + Send + Sync>> = Vec::new();
+    for backend in &config.metrics_backends {
+        let metrics: Arc<dyn Metrics + Send + Sync> = match backend {
+            MetricsBackend::Tracing => Arc::new(
+                TracingMetrics::with_latency_buckets(&config.latency_buckets_ms)
+                    .with_sync_lag_warn_threshold(config.sync_lag_warn_slots)
+                    .with_queue_warn_thresholds(
+                        config.queue_depth_warn,
+                        Duration::from_millis(config.interval_age_warn_ms),
+                    )
+                    .with_slow_thresholds(config.slow_thresholds())
+                    .with_cache_sample_rate(config.metrics_sample_rate),
+            ),
+            MetricsBackend::Prometheus => Arc::new(PrometheusMetrics::with_latency_buckets(
+                &config.latency_buckets_ms,
+            )),
+            MetricsBackend::Statsd => {
+                let statsd = Arc::new(
+                    StatsdMetrics::new(&config.statsd_addr)?
+                        .with_prefix(config.statsd_prefix.as_deref().unwrap_or_default())
+                        .with_tags(&config.statsd_tags),
+                );
+                let flush_statsd = statsd.clone();
+                let period = Duration::from_millis(config.statsd_flush_interval_ms.max(1));
+                let mut shutdown = metrics_shutdown_receiver.clone();
+                metrics_tasks.push(tokio::spawn(async move {
+                    let mut flush_timer = tokio::time::interval(period);
+                    loop {
+                        tokio::select! {
+                            _ = flush_timer.tick() => flush_statsd.flush_buffer(),
+                            _ = shutdown.wait_for(|stop| *stop) => break,
+                        }
+                    }
+                    flush_statsd.flush_buffer();
+                }));
+                statsd
+            }
+            MetricsBackend::OpenTelemetry => {
+                let otel = Arc::new(OpenTelemetryMetrics::with_latency_buckets(
+                    &config.latency_buckets_ms,
+                ));
+                let mut exporter = OtlpExporter::new(&config.otlp_endpoint)
+                    .with_headers(config.otlp_headers.clone())
+                    .with_service_name(&config.otel_service_name)
+                    .with_metrics(otel.clone());
+                if let Some(span_layer) = span_layer.clone() {
+                    exporter = exporter.with_spans(span_layer);
+                }
+                otlp_exporter = Some(Arc::new(exporter));
+                otel
+            }
+        };
+        backends.push(metrics);
+    }
+    let run_totals = Arc::new(RunTotals::new());
+    backends.push(run_totals.clone());
+    let metrics: Arc<dyn Metrics + Send + Sync> = Arc::new(CompositeMetrics::new(backends));
+
+    if let Some(exporter) = otlp_exporter {
+        let period = Duration::from_millis(config.otlp_export_interval_ms.max(1));
+        let mut shutdown = metrics_shutdown_receiver.clone();
+        metrics_tasks.push(tokio::spawn(async move {
+            let mut export_timer = tokio::time::interval(period);
+            export_timer.tick().await;
+            loop {
+                tokio::select! {
+                    _ = export_timer.tick() => {}
+                    _ = shutdown.wait_for(|stop| *stop) => break,
+                }
+                if let Err(e) = exporter.export().await {
+                    tracing::warn!(error = %e, "OTLP export failed");
+                }
+            }
+            exporter.shutdown().await;
+        }));
+    }
+
+    if config.metrics_summary_interval_secs > 0 {
+        let summary_metrics = metrics.clone();
+        let period = Duration::from_secs(config.metrics_summary_interval_secs);
+        let mut shutdown = metrics_shutdown_receiver.clone();
+        metrics_tasks.push(tokio::spawn(async move {
+            let mut summary_timer = tokio::time::interval(period);
+            summary_timer.tick().await;
+            loop {
+                tokio::select! {
+                    _ = summary_timer.tick() => summary_metrics.flush(),
+                    // The run summary logged on exit covers the last period.
+                    _ = shutdown.wait_for(|stop| *stop) => break,
+                }
+            }
+        }));
+    }
+    let state = Arc::new(
+        AppState::new(cache.clone(), client.clone(), metrics.clone())
+            .with_slot_broadcast_buffer(config.slot_broadcast_buffer),
     );
+    let mut logic = SyndicaAppLogic::new(state.clone())
+        .with_confirmation_strategy(config.confirmation_strategy)
+        .with_monitoring_depth(config.monitoring_depth)
+        .with_monitor_interval_ms(config.monitor_interval_ms)
+        .with_sync_mode(if config.disable_history {
+            SyncMode::TipOnly
+        } else {
+            config.sync_mode
+        })
+        .with_serve_stale_on_error(config.serve_stale_on_error)
+        .with_max_staleness(Duration::from_secs(config.health_max_staleness_secs))
+        .with_max_ready_lag(config.max_ready_lag)
+        .with_future_slot_tolerance(config.future_slot_tolerance)
+        .with_max_range_slots(config.max_range_slots)
+        .with_confirmation_sla(Duration::from_millis(config.confirmation_sla_ms))
+        .with_confirmation_latency_sample(Duration::from_millis(
+            config.confirmation_latency_sample_ms,
+        ));
+    if let Some(url) = &config.webhook_url {
+        let (webhook, receiver) = WebhookSender::channel(config.webhook_buffer);
+        WebhookDispatcher::new(url.clone(), receiver)?
+            .with_max_attempts(config.webhook_max_attempts)
+            .spawn();
+        logic = logic.with_webhook(webhook);
+    }
+    let logic = Arc::new(logic);
+
+    let tip = logic
+        .startup_health_check(
+            config.startup_healthcheck_retries,
+            Duration::from_millis(config.startup_healthcheck_delay_ms),
+        )
+        .await?;
+    let start_from_slot = match config.start_from_slot {
+        Some(slot) => Some(logic.resolve_start_slot(slot, tip).await?),
+        None => None,
+    };
 
-    let sync_handle = tokio::spawn(async move {
+    let heartbeat_handle = (config.heartbeat_interval_secs > 0).then(|| {
+        Heartbeat::spawn(
+            logic.clone(),
+            Duration::from_secs(config.heartbeat_interval_secs),
+        )
+    });
+
+    let pruner_handle = (config.cache_prune_interval_secs > 0).then(|| {
+        let mut pruner = CachePruner::new(logic.clone());
+        if let Some(slot) = start_from_slot {
+            pruner = pruner.with_keep_from(slot);
+        }
+        pruner.spawn(Duration::from_secs(config.cache_prune_interval_secs))
+    });
+
+    #[cfg(unix)]
+    ConfigReloader::new(
+        config.clone(),
+        logic.clone(),
+        // Reload the file found at startup rather than searching again.
+        config
+            .env_file
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned()),
+        args.overrides,
+    )
+    .with_filter_reload(move |filter| Ok(log_filter_handle.reload(filter)?))
+    .spawn_on_sighup()?;
+
+    let (shutdown, shutdown_receiver) = watch::channel(false);
+    let mut synchronizer = Synchronizer::new(logic.clone())
+        .with_initial_backfill_depth(config.initial_backfill_depth)
+        .with_slot_confirmation_offset(config.slot_confirmation_offset)
+        .with_batch_intervals(config.sync_batch_intervals)
+        .with_interval_size(config.sync_interval_size)
+        .with_max_attempts(config.sync_max_attempts)
+        .with_backpressure(config.queue_high_water, config.queue_low_water)
+        .with_history_disabled(config.disable_history)
+        .with_shutdown(shutdown_receiver);
+    if let Some(slot) = start_from_slot {
+        synchronizer = synchronizer.with_start_from_slot(slot);
+    }
+    match &config.rpc_ws_url {
+        Some(ws_url) => {
+            let key = config.rpc_endpoints[0].key.as_deref().unwrap_or_default();
+            synchronizer =
+                synchronizer.with_slot_feed(SyndicaClient::subscribe_slots(ws_url.clone(), key));
+        }
+        None => info!("RPC_WS_URL is not set, polling getSlot for new slots"),
+    }
+
+    let mut sync_handle = tokio::spawn(async move {
         synchronizer.run().await;
     });
 
+    let metrics_dump_path = config.metrics_dump_path.clone();
     info!("Starting server on port {}", config.server_port);
     let server_handle = tokio::spawn(async move {
-        if let Err(e) = start_server(config.server_port, logic).await {
+        if let Err(e) = start_server(
+            config.server_port,
+            logic,
+            config.admin_token.clone(),
+            config.json_pretty,
+            config.redacted(),
+        )
+        .await
+        {
             tracing::error!("Server error: {}", e);
         }
     });
 
-    tokio::select! {
-        _ = sync_handle => {
+    let shutdown_requested = tokio::select! {
+        _ = &mut sync_handle => {
             tracing::error!("Synchronizer task ended unexpectedly");
+            false
         }
         _ = server_handle => {
             tracing::error!("Server task ended unexpectedly");
+            false
         }
         _ = tokio::signal::ctrl_c() => {
             info!("Received shutdown signal");
+            true
+        }
+    };
+
+    if shutdown_requested {
+        let _ = shutdown.send(true);
+        if tokio::time::timeout(SHUTDOWN_DRAIN_TIMEOUT, sync_handle)
+            .await
+            .is_err()
+        {
+            warn!(
+                timeout_secs = SHUTDOWN_DRAIN_TIMEOUT.as_secs(),
+                "Synchronizer did not stop in time, abandoning in-flight intervals"
+            );
+        }
+    }
+
+    if let Some(heartbeat_handle) = heartbeat_handle {
+        heartbeat_handle.abort();
+    }
+    if let Some(pruner_handle) = pruner_handle {
+        pruner_handle.abort();
+    }
+    let _ = metrics_shutdown.send(true);
+    for task in metrics_tasks {
+        let _ = task.await;
+    }
+
+    let summary = run_totals.summary(state.rpc_call_counts());
+    summary.log();
+    if let Some(path) = metrics_dump_path {
+        match summary.write(&path).await {
+            Ok(()) => info!(path = %path.display(), "Wrote run summary"),
+            Err(e) => {
+                tracing::error!(path = %path.display(), error = %e, "Cannot write run summary")
+            }
         }
     }
 
@@ -0,0 +1,440 @@
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use tracing::error;
+
+use super::{
+    CacheHitRatios, IntervalOutcome, LatencyReport, Metrics, SlotThroughput, SlowThresholds,
+};
+use crate::syndica_client::RpcErrorKind;
+
+/// Metrics backend that forwards every call to several backends.
+///
+/// Each child is called in order and in isolation: a panic in one child is
+/// caught and logged, and the remaining children still record. Queries
+/// such as `render_prometheus` return the first child's `Some` answer.
+///
+/// The `record_*` methods are forwarded as they are rather than through the
+/// generic calls, so a child that overrides one still sees it.
+pub struct CompositeMetrics(Vec<Arc<dyn Metrics + Send + Sync>>);
+
+impl CompositeMetrics {
+    pub fn new(children: Vec<Arc<dyn Metrics + Send + Sync>>) -> Self {
+        Self(children)
+    }
+
+    fn each(&self, method: &str, record: impl Fn(&(dyn Metrics + Send + Sync))) {
+        for (index, child) in self.0.iter().enumerate() {
+            if catch_unwind(AssertUnwindSafe(|| record(child.as_ref()))).is_err() {
+                error!(
+                    target: "metrics::composite",
+                    method, index, "Metrics backend panicked"
+                );
+            }
+        }
+    }
+
+    fn first<T>(&self, query: impl Fn(&(dyn Metrics + Send + Sync)) -> Option<T>) -> Option<T> {
+        self.0.iter().find_map(|child| {
+            catch_unwind(AssertUnwindSafe(|| query(child.as_ref())))
+                .ok()
+                .flatten()
+        })
+    }
+}
+
+impl Metrics for CompositeMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        self.each("record_duration", |m| {
+            m.record_duration(op, labels, elapsed)
+        });
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        self.each("increment", |m| m.increment(counter, labels, n));
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.each("set_gauge", |m| m.set_gauge(gauge, labels, value));
+    }
+
+    fn record_latest_slot(&self, slot: u64) {
+        self.each("record_latest_slot", |m| m.record_latest_slot(slot));
+    }
+
+    fn record_get_blocks_elapsed(&self, elapsed: Duration) {
+        self.each("record_get_blocks_elapsed", |m| {
+            m.record_get_blocks_elapsed(elapsed)
+        });
+    }
+
+    fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration) {
+        self.each("record_is_slot_confirmed_elapsed", |m| {
+            m.record_is_slot_confirmed_elapsed(elapsed)
+        });
+    }
+
+    fn record_cache_hit(&self, hit: bool) {
+        self.each("record_cache_hit", |m| m.record_cache_hit(hit));
+    }
+
+    fn record_cache_lookups(&self, hits: usize, misses: usize) {
+        self.each("record_cache_lookups", |m| {
+            m.record_cache_lookups(hits, misses)
+        });
+    }
+
+    fn record_sync_lag(&self, slots: u64) {
+        self.each("record_sync_lag", |m| m.record_sync_lag(slots));
+    }
+
+    fn record_queue_depth(&self, depth: usize) {
+        self.each("record_queue_depth", |m| m.record_queue_depth(depth));
+    }
+
+    fn record_interval_age(&self, age: Duration) {
+        self.each("record_interval_age", |m| m.record_interval_age(age));
+    }
+
+    fn record_rpc_error(&self, method: &str, kind: RpcErrorKind) {
+        self.each("record_rpc_error", |m| m.record_rpc_error(method, kind));
+    }
+
+    fn record_interval_processed(
+        &self,
+        worker_id: usize,
+        size: u64,
+        elapsed: Duration,
+        confirmed_count: usize,
+        sub_intervals: usize,
+        outcome: IntervalOutcome,
+    ) {
+        self.each("record_interval_processed", |m| {
+            m.record_interval_processed(
+                worker_id,
+                size,
+                elapsed,
+                confirmed_count,
+                sub_intervals,
+                outcome,
+            )
+        });
+    }
+
+    fn record_worker_utilization(&self, worker_id: usize, busy_ratio: f64) {
+        self.each("record_worker_utilization", |m| {
+            m.record_worker_utilization(worker_id, busy_ratio)
+        });
+    }
+
+    fn record_http_request(&self, route: &str, status: u16, elapsed: Duration) {
+        self.each("record_http_request", |m| {
+            m.record_http_request(route, status, elapsed)
+        });
+    }
+
+    fn record_http_in_flight(&self, route: &str, in_flight: usize) {
+        self.each("record_http_in_flight", |m| {
+            m.record_http_in_flight(route, in_flight)
+        });
+    }
+
+    fn record_slots_inserted(&self, count: usize) {
+        self.each("record_slots_inserted", |m| m.record_slots_inserted(count));
+    }
+
+    fn record_last_success(&self, activity: &str, at: SystemTime) {
+        self.each("record_last_success", |m| {
+            m.record_last_success(activity, at)
+        });
+    }
+
+    fn record_backfill_progress(&self, completed: u64, total: u64) {
+        self.each("record_backfill_progress", |m| {
+            m.record_backfill_progress(completed, total)
+        });
+    }
+
+    fn record_confirmation_latency(&self, latency: Duration) {
+        self.each("record_confirmation_latency", |m| {
+            m.record_confirmation_latency(latency)
+        });
+    }
+
+    fn record_time_to_cache(&self, elapsed: Duration) {
+        self.each("record_time_to_cache", |m| m.record_time_to_cache(elapsed));
+    }
+
+    fn record_confirmation_sla_violation(&self) {
+        self.each("record_confirmation_sla_violation", |m| {
+            m.record_confirmation_sla_violation()
+        });
+    }
+
+    fn record_interval_dead_lettered(&self) {
+        self.each("record_interval_dead_lettered", |m| {
+            m.record_interval_dead_lettered()
+        });
+    }
+
+    fn latency_percentiles(&self) -> Option<LatencyReport> {
+        self.first(|m| m.latency_percentiles())
+    }
+
+    fn cache_hit_ratios(&self) -> Option<CacheHitRatios> {
+        self.first(|m| m.cache_hit_ratios())
+    }
+
+    fn slot_throughput(&self) -> Option<SlotThroughput> {
+        self.first(|m| m.slot_throughput())
+    }
+
+    fn render_prometheus(&self) -> Option<String> {
+        self.first(|m| m.render_prometheus())
+    }
+
+    fn flush(&self) {
+        self.each("flush", |m| m.flush());
+    }
+
+    fn set_slow_thresholds(&self, thresholds: SlowThresholds) {
+        self.each("set_slow_thresholds", |m| {
+            m.set_slow_thresholds(thresholds.clone())
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::PrometheusMetrics;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingMetrics {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingMetrics {
+        fn push(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    impl Metrics for RecordingMetrics {
+        fn record_duration(
+            &self,
+            op: &'static str,
+            labels: &[(&'static str, &str)],
+            elapsed: Duration,
+        ) {
+            self.push(format!("duration({}, {:?}, {:?})", op, labels, elapsed));
+        }
+        fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+            self.push(format!("increment({}, {:?}, {})", counter, labels, n));
+        }
+        fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+            self.push(format!("gauge({}, {:?}, {})", gauge, labels, value));
+        }
+        fn record_latest_slot(&self, slot: u64) {
+            self.push(format!("latest_slot({})", slot));
+        }
+        fn record_get_blocks_elapsed(&self, elapsed: Duration) {
+            self.push(format!("get_blocks_elapsed({:?})", elapsed));
+        }
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration) {
+            self.push(format!("is_slot_confirmed_elapsed({:?})", elapsed));
+        }
+        fn record_cache_hit(&self, hit: bool) {
+            self.push(format!("cache_hit({})", hit));
+        }
+        fn record_cache_lookups(&self, hits: usize, misses: usize) {
+            self.push(format!("cache_lookups({}, {})", hits, misses));
+        }
+        fn record_sync_lag(&self, slots: u64) {
+            self.push(format!("sync_lag({})", slots));
+        }
+        fn record_queue_depth(&self, depth: usize) {
+            self.push(format!("queue_depth({})", depth));
+        }
+        fn record_interval_age(&self, age: Duration) {
+            self.push(format!("interval_age({:?})", age));
+        }
+        fn record_rpc_error(&self, method: &str, kind: RpcErrorKind) {
+            self.push(format!("rpc_error({}, {})", method, kind.as_str()));
+        }
+        fn record_interval_processed(
+            &self,
+            worker_id: usize,
+            size: u64,
+            elapsed: Duration,
+            confirmed_count: usize,
+            sub_intervals: usize,
+            outcome: IntervalOutcome,
+        ) {
+            self.push(format!(
+                "interval_processed({}, {}, {:?}, {}, {}, {})",
+                worker_id,
+                size,
+                elapsed,
+                confirmed_count,
+                sub_intervals,
+                outcome.as_str()
+            ));
+        }
+        fn record_worker_utilization(&self, worker_id: usize, busy_ratio: f64) {
+            self.push(format!("worker_utilization({}, {})", worker_id, busy_ratio));
+        }
+        fn record_http_request(&self, route: &str, status: u16, elapsed: Duration) {
+            self.push(format!(
+                "http_request({}, {}, {:?})",
+                route, status, elapsed
+            ));
+        }
+        fn record_http_in_flight(&self, route: &str, in_flight: usize) {
+            self.push(format!("http_in_flight({}, {})", route, in_flight));
+        }
+        fn record_slots_inserted(&self, count: usize) {
+            self.push(format!("slots_inserted({})", count));
+        }
+        fn record_last_success(&self, activity: &str, at: SystemTime) {
+            self.push(format!("last_success({}, {:?})", activity, at));
+        }
+        fn record_backfill_progress(&self, completed: u64, total: u64) {
+            self.push(format!("backfill_progress({}, {})", completed, total));
+        }
+        fn record_confirmation_latency(&self, latency: Duration) {
+            self.push(format!("confirmation_latency({:?})", latency));
+        }
+        fn record_time_to_cache(&self, elapsed: Duration) {
+            self.push(format!("time_to_cache({:?})", elapsed));
+        }
+        fn record_confirmation_sla_violation(&self) {
+            self.push("confirmation_sla_violation".to_string());
+        }
+        fn record_interval_dead_lettered(&self) {
+            self.push("interval_dead_lettered".to_string());
+        }
+        fn flush(&self) {
+            self.push("flush".to_string());
+        }
+    }
+
+    struct PanickingMetrics;
+
+    impl Metrics for PanickingMetrics {
+        fn record_latest_slot(&self, _slot: u64) {
+            panic!("record_latest_slot");
+        }
+        fn record_duration(
+            &self,
+            _op: &'static str,
+            _labels: &[(&'static str, &str)],
+            _elapsed: Duration,
+        ) {
+        }
+        fn increment(&self, _counter: &'static str, _labels: &[(&'static str, &str)], _n: u64) {
+            panic!("increment");
+        }
+        fn set_gauge(&self, _gauge: &'static str, _labels: &[(&'static str, &str)], _value: f64) {}
+        fn render_prometheus(&self) -> Option<String> {
+            panic!("render_prometheus");
+        }
+    }
+
+    #[test]
+    fn test_forwards_every_call_to_all_children() {
+        let first = Arc::new(RecordingMetrics::default());
+        let second = Arc::new(RecordingMetrics::default());
+        let composite = CompositeMetrics::new(vec![first.clone(), second.clone()]);
+
+        composite.record_latest_slot(7);
+        composite.record_get_blocks_elapsed(Duration::from_millis(3));
+        composite.record_is_slot_confirmed_elapsed(Duration::from_millis(4));
+        composite.record_cache_hit(true);
+        composite.record_cache_lookups(2, 1);
+        composite.record_sync_lag(5);
+        composite.record_queue_depth(6);
+        composite.record_interval_age(Duration::from_secs(1));
+        composite.record_rpc_error("getSlot", RpcErrorKind::Timeout);
+        composite.record_interval_processed(
+            1,
+            100,
+            Duration::from_millis(20),
+            40,
+            2,
+            IntervalOutcome::Success,
+        );
+        composite.record_worker_utilization(1, 0.5);
+        composite.record_http_request("/stats", 200, Duration::from_millis(2));
+        composite.record_http_in_flight("/stats", 0);
+        composite.record_slots_inserted(25);
+        composite.record_last_success("get_slot", SystemTime::UNIX_EPOCH);
+        composite.record_backfill_progress(400, 1_000);
+        composite.record_confirmation_latency(Duration::from_millis(900));
+        composite.record_time_to_cache(Duration::from_millis(700));
+        composite.record_confirmation_sla_violation();
+        composite.record_interval_dead_lettered();
+        composite.record_duration("backfill", &[("queue", "a")], Duration::from_millis(1));
+        composite.increment("backfill_jobs", &[("queue", "a")], 2);
+        composite.set_gauge("backfill_depth", &[], 3.0);
+        composite.flush();
+
+        assert_eq!(first.calls().len(), 24);
+        assert_eq!(
+            first.calls()[20..22],
+            [
+                "duration(backfill, [(\"queue\", \"a\")], 1ms)",
+                "increment(backfill_jobs, [(\"queue\", \"a\")], 2)",
+            ]
+        );
+        // Forwarded as is, so a child's own override runs.
+        assert_eq!(
+            first.calls()[15..20],
+            [
+                "backfill_progress(400, 1000)",
+                "confirmation_latency(900ms)",
+                "time_to_cache(700ms)",
+                "confirmation_sla_violation",
+                "interval_dead_lettered",
+            ]
+        );
+        assert_eq!(first.calls(), second.calls());
+        assert_eq!(composite.render_prometheus(), None);
+    }
+
+    #[test]
+    fn test_panicking_child_does_not_block_others() {
+        let recorder = Arc::new(RecordingMetrics::default());
+        let prometheus = Arc::new(PrometheusMetrics::new());
+        let composite = CompositeMetrics::new(vec![
+            Arc::new(PanickingMetrics),
+            recorder.clone(),
+            prometheus.clone(),
+        ]);
+
+        composite.record_latest_slot(42);
+        composite.record_rpc_error("getSlot", RpcErrorKind::Timeout);
+
+        assert_eq!(
+            recorder.calls(),
+            vec!["latest_slot(42)", "rpc_error(getSlot, timeout)"]
+        );
+        assert!(
+            composite
+                .render_prometheus()
+                .unwrap()
+                .contains("solana_block_monitor_latest_slot 42")
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Default bucket upper bounds, in milliseconds.
+pub const DEFAULT_LATENCY_BUCKETS_MS: &[u64] =
+    &[1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Percentiles derived from a latency histogram, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Aggregate view of the latencies recorded over a flush period, in milliseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Latency percentiles for every operation tracked by the metrics layer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LatencyReport {
+    pub get_blocks: LatencyPercentiles,
+    pub is_slot_confirmed: LatencyPercentiles,
+}
+
+/// A fixed-bucket latency histogram backed by atomic counters.
+///
+/// Recording is a handful of relaxed atomic operations, so a single histogram
+/// can be shared by every worker without a lock. Percentiles resolve to the
+/// upper bound of the bucket holding the requested rank (capped by the
+/// largest observed value), so they are accurate to the bucket granularity.
+pub struct LatencyHistogram {
+    bounds_us: Vec<u64>,
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    sum_us: AtomicU64,
+    max_us: AtomicU64,
+}
+
+impl LatencyHistogram {
+    pub fn new(bounds_ms: &[u64]) -> Self {
+        let mut bounds_us: Vec<u64> = bounds_ms.iter().map(|ms| ms * 1000).collect();
+        bounds_us.sort_unstable();
+        bounds_us.dedup();
+
+        // One extra bucket collects everything above the last bound.
+        let buckets = (0..=bounds_us.len()).map(|_| AtomicU64::new(0)).collect();
+
+        Self {
+            bounds_us,
+            buckets,
+            count: AtomicU64::new(0),
+            sum_us: AtomicU64::new(0),
+            max_us: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros().min(u64::MAX as u128) as u64;
+        let index = self.bounds_us.partition_point(|&bound| bound < micros);
+
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(micros, Ordering::Relaxed);
+        self.max_us.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> Duration {
+        Duration::from_micros(self.sum_us.load(Ordering::Relaxed))
+    }
+
+    /// Bucket upper bounds, excluding the implicit overflow bucket.
+    pub fn bounds(&self) -> Vec<Duration> {
+        self.bounds_us
+            .iter()
+            .map(|bound| Duration::from_micros(*bound))
+            .collect()
+    }
+
+    /// Per-bucket (non-cumulative) counts; the last entry is the overflow bucket.
+    pub fn bucket_counts(&self) -> Vec<u64> {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    /// Copies the current state of the histogram.
+    pub fn snapshot(&self) -> HistogramSnapshot {
+        HistogramSnapshot {
+            bounds_us: self.bounds_us.clone(),
+            counts: self.bucket_counts(),
+            sum_us: self.sum_us.load(Ordering::Relaxed),
+            max_us: self.max_us.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns the current state and resets the histogram, so the next
+    /// snapshot only covers values recorded after this call.
+    pub fn take(&self) -> HistogramSnapshot {
+        let counts = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.swap(0, Ordering::Relaxed))
+            .collect();
+        self.count.store(0, Ordering::Relaxed);
+
+        HistogramSnapshot {
+            bounds_us: self.bounds_us.clone(),
+            counts,
+            sum_us: self.sum_us.swap(0, Ordering::Relaxed),
+            max_us: self.max_us.swap(0, Ordering::Relaxed),
+        }
+    }
+
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        self.snapshot().percentile(quantile)
+    }
+
+    pub fn percentiles(&self) -> LatencyPercentiles {
+        let snapshot = self.snapshot();
+        LatencyPercentiles {
+            count: snapshot.count(),
+            p50_ms: as_ms(snapshot.percentile(0.50)),
+            p90_ms: as_ms(snapshot.percentile(0.90)),
+            p99_ms: as_ms(snapshot.percentile(0.99)),
+        }
+    }
+}
+
+/// A point-in-time copy of a `LatencyHistogram`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramSnapshot {
+    bounds_us: Vec<u64>,
+    counts: Vec<u64>,
+    sum_us: u64,
+    max_us: u64,
+}
+
+impl HistogramSnapshot {
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        let total = self.count();
+        if total == 0 {
+            return Duration::ZERO;
+        }
+
+        let rank = ((quantile * total as f64).ceil() as u64).clamp(1, total);
+        let mut cumulative = 0;
+        for (index, count) in self.counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= rank {
+                let upper_us = self.bounds_us.get(index).copied().unwrap_or(self.max_us);
+                return Duration::from_micros(upper_us.min(self.max_us));
+            }
+        }
+
+        Duration::from_micros(self.max_us)
+    }
+
+    pub fn summary(&self) -> LatencySummary {
+        let count = self.count();
+        let mean_ms = if count == 0 {
+            0.0
+        } else {
+            self.sum_us as f64 / count as f64 / 1000.0
+        };
+
+        LatencySummary {
+            count,
+            mean_ms,
+            p50_ms: as_ms(self.percentile(0.50)),
+            p95_ms: as_ms(self.percentile(0.95)),
+            p99_ms: as_ms(self.percentile(0.99)),
+            max_ms: self.max_us as f64 / 1000.0,
+        }
+    }
+}
+
+fn as_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self::new(DEFAULT_LATENCY_BUCKETS_MS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_histogram_percentiles() {
+        let histogram = LatencyHistogram::default();
+
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.percentile(0.5), Duration::ZERO);
+        assert_eq!(histogram.percentiles(), LatencyPercentiles::default());
+    }
+
+    #[test]
+    fn test_percentiles_for_latency_spread() {
+        let histogram = LatencyHistogram::default();
+
+        for ms in 1..=100 {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        let percentiles = histogram.percentiles();
+        assert_eq!(percentiles.count, 100);
+        assert!((25.0..=50.0).contains(&percentiles.p50_ms));
+        assert!((50.0..=100.0).contains(&percentiles.p90_ms));
+        assert!((90.0..=100.0).contains(&percentiles.p99_ms));
+    }
+
+    #[test]
+    fn test_summary_for_known_durations() {
+        let histogram = LatencyHistogram::new(&[10, 20, 50, 100]);
+
+        for ms in [5, 5, 15, 15, 15, 40, 40, 40, 80, 90] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        let summary = histogram.snapshot().summary();
+        assert_eq!(summary.count, 10);
+        assert_eq!(summary.mean_ms, 34.5);
+        assert_eq!(summary.p50_ms, 20.0);
+        assert_eq!(summary.p95_ms, 90.0);
+        assert_eq!(summary.p99_ms, 90.0);
+        assert_eq!(summary.max_ms, 90.0);
+    }
+
+    #[test]
+    fn test_take_resets_window() {
+        let histogram = LatencyHistogram::new(&[10]);
+        histogram.record(Duration::from_millis(5));
+        histogram.record(Duration::from_millis(15));
+
+        let first = histogram.take();
+        assert_eq!(first.count(), 2);
+        assert_eq!(first.summary().max_ms, 15.0);
+
+        histogram.record(Duration::from_millis(1));
+        let second = histogram.take();
+        assert_eq!(second.count(), 1);
+        assert_eq!(second.summary().max_ms, 1.0);
+        assert_eq!(histogram.count(), 0);
+    }
+
+    #[test]
+    fn test_overflow_bucket_uses_max() {
+        let histogram = LatencyHistogram::new(&[10, 20]);
+
+        histogram.record(Duration::from_millis(5));
+        histogram.record(Duration::from_millis(300));
+
+        assert_eq!(histogram.percentile(0.5), Duration::from_millis(10));
+        assert_eq!(histogram.percentile(0.99), Duration::from_millis(300));
+    }
+}
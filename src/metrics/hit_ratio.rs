@@ -0,0 +1,180 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const BUCKET_WIDTH: Duration = Duration::from_secs(60);
+/// One bucket per minute for the last hour.
+const BUCKET_COUNT: u64 = 60;
+
+/// Cache hit ratios over the trailing 1, 5 and 60 minutes. A ratio is
+/// `None` when there were no lookups in that window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CacheHitRatios {
+    pub ratio_1m: Option<f64>,
+    pub ratio_5m: Option<f64>,
+    pub ratio_60m: Option<f64>,
+}
+
+impl CacheHitRatios {
+    /// Window label and ratio pairs, shortest window first.
+    pub fn windows(&self) -> [(&'static str, Option<f64>); 3] {
+        [
+            ("1m", self.ratio_1m),
+            ("5m", self.ratio_5m),
+            ("60m", self.ratio_60m),
+        ]
+    }
+}
+
+struct MinuteBucket {
+    /// Minute (since the window was created) plus one; zero means unused.
+    minute: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// Ring buffer of per-minute cache hit/miss counts covering the last hour.
+///
+/// Recording is a few relaxed atomic operations. A bucket is reused when
+/// its minute comes around again; the thread that claims it resets the
+/// counts, so lookups racing with that reset in the first instant of a new
+/// minute may be lost. That is acceptable for a ratio.
+pub struct CacheHitWindow {
+    started: Instant,
+    buckets: Vec<MinuteBucket>,
+}
+
+impl CacheHitWindow {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            buckets: (0..BUCKET_COUNT)
+                .map(|_| MinuteBucket {
+                    minute: AtomicU64::new(0),
+                    hits: AtomicU64::new(0),
+                    misses: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn record(&self, hits: usize, misses: usize) {
+        self.record_at(hits, misses, Instant::now());
+    }
+
+    pub fn ratios(&self) -> CacheHitRatios {
+        self.ratios_at(Instant::now())
+    }
+
+    fn minute_at(&self, now: Instant) -> u64 {
+        (now.saturating_duration_since(self.started).as_secs() / BUCKET_WIDTH.as_secs()) + 1
+    }
+
+    fn record_at(&self, hits: usize, misses: usize, now: Instant) {
+        let minute = self.minute_at(now);
+        let bucket = &self.buckets[(minute % BUCKET_COUNT) as usize];
+
+        let current = bucket.minute.load(Ordering::Acquire);
+        if current < minute
+            && bucket
+                .minute
+                .compare_exchange(current, minute, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            bucket.hits.store(0, Ordering::Relaxed);
+            bucket.misses.store(0, Ordering::Relaxed);
+        }
+
+        bucket.hits.fetch_add(hits as u64, Ordering::Relaxed);
+        bucket.misses.fetch_add(misses as u64, Ordering::Relaxed);
+    }
+
+    fn ratios_at(&self, now: Instant) -> CacheHitRatios {
+        let minute = self.minute_at(now);
+        let ratio = |minutes: u64| {
+            let (hits, misses) = self
+                .buckets
+                .iter()
+                .filter(|bucket| {
+                    let bucket_minute = bucket.minute.load(Ordering::Acquire);
+                    bucket_minute != 0
+                        && bucket_minute + minutes > minute
+                        && bucket_minute <= minute
+                })
+                .fold((0, 0), |(hits, misses), bucket| {
+                    (
+                        hits + bucket.hits.load(Ordering::Relaxed),
+                        misses + bucket.misses.load(Ordering::Relaxed),
+                    )
+                });
+            let total = hits + misses;
+            (total > 0).then(|| hits as f64 / total as f64)
+        };
+
+        CacheHitRatios {
+            ratio_1m: ratio(1),
+            ratio_5m: ratio(5),
+            ratio_60m: ratio(BUCKET_COUNT),
+        }
+    }
+}
+
+impl Default for CacheHitWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes(n: u64) -> Duration {
+        Duration::from_secs(n * 60)
+    }
+
+    #[test]
+    fn test_ratios_per_window() {
+        let window = CacheHitWindow::new();
+        let start = window.started;
+
+        window.record_at(0, 4, start);
+        window.record_at(3, 1, start + minutes(3));
+        window.record_at(1, 0, start + minutes(5) + Duration::from_secs(30));
+
+        let ratios = window.ratios_at(start + minutes(5) + Duration::from_secs(59));
+        assert_eq!(ratios.ratio_1m, Some(1.0));
+        assert_eq!(ratios.ratio_5m, Some(0.8));
+        assert_eq!(ratios.ratio_60m, Some(4.0 / 9.0));
+
+        assert_eq!(CacheHitWindow::new().ratios(), CacheHitRatios::default());
+    }
+
+    #[test]
+    fn test_old_buckets_roll_off() {
+        let window = CacheHitWindow::new();
+        let start = window.started;
+
+        window.record_at(0, 10, start);
+        window.record_at(5, 0, start + minutes(30));
+
+        let ratios = window.ratios_at(start + minutes(59));
+        assert_eq!(ratios.ratio_1m, None);
+        assert_eq!(ratios.ratio_60m, Some(5.0 / 15.0));
+
+        // Minute 0 has left the hour window, minute 30 has not.
+        let ratios = window.ratios_at(start + minutes(60));
+        assert_eq!(ratios.ratio_60m, Some(1.0));
+
+        // Minute 60 reuses minute 0's bucket and must not inherit its misses.
+        window.record_at(2, 0, start + minutes(60));
+        let ratios = window.ratios_at(start + minutes(60));
+        assert_eq!(ratios.ratio_1m, Some(1.0));
+        assert_eq!(ratios.ratio_60m, Some(1.0));
+
+        let ratios = window.ratios_at(start + minutes(95));
+        assert_eq!(ratios.ratio_60m, Some(1.0));
+        assert_eq!(ratios.ratio_5m, None);
+        assert_eq!(window.ratios_at(start + minutes(200)).ratio_60m, None);
+    }
+}
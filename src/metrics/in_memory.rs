@@ -0,0 +1,311 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use super::{Metrics, names};
+
+/// One `record_duration`, `increment` or `set_gauge` call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement<T> {
+    pub name: &'static str,
+    pub labels: Vec<(&'static str, String)>,
+    pub value: T,
+}
+
+impl<T> Measurement<T> {
+    fn new(name: &'static str, labels: &[(&'static str, &str)], value: T) -> Self {
+        Self {
+            name,
+            labels: labels
+                .iter()
+                .map(|(key, value)| (*key, value.to_string()))
+                .collect(),
+            value,
+        }
+    }
+
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Everything an `InMemoryMetrics` has recorded, in call order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedMetrics {
+    pub durations: Vec<Measurement<Duration>>,
+    pub counters: Vec<Measurement<u64>>,
+    pub gauges: Vec<Measurement<f64>>,
+    pub flushes: usize,
+}
+
+impl RecordedMetrics {
+    pub fn durations_named(&self, name: &str) -> Vec<&Measurement<Duration>> {
+        self.durations.iter().filter(|m| m.name == name).collect()
+    }
+
+    pub fn counters_named(&self, name: &str) -> Vec<&Measurement<u64>> {
+        self.counters.iter().filter(|m| m.name == name).collect()
+    }
+
+    pub fn gauges_named(&self, name: &str) -> Vec<&Measurement<f64>> {
+        self.gauges.iter().filter(|m| m.name == name).collect()
+    }
+
+    /// Sum of every increment of `name` whose labels include all of `labels`.
+    pub fn counter_total(&self, name: &str, labels: &[(&str, &str)]) -> u64 {
+        self.counters
+            .iter()
+            .filter(|m| m.name == name)
+            .filter(|m| {
+                labels
+                    .iter()
+                    .all(|(key, value)| m.label(key) == Some(*value))
+            })
+            .map(|m| m.value)
+            .sum()
+    }
+}
+
+/// Metrics backend that keeps every call in memory so tests can inspect
+/// what was recorded.
+///
+/// Only the generic calls are recorded, so the `record_*` shims show up
+/// under the names in `names`. Nothing is ever discarded, so memory grows
+/// with every call; use it in tests and short-lived tools rather than in a
+/// long-running service.
+#[derive(Default)]
+pub struct InMemoryMetrics {
+    recorded: Mutex<RecordedMetrics>,
+}
+
+impl InMemoryMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn recorded(&self) -> MutexGuard<'_, RecordedMetrics> {
+        self.recorded.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A copy of everything recorded so far.
+    pub fn snapshot(&self) -> RecordedMetrics {
+        self.recorded().clone()
+    }
+
+    /// Forgets everything recorded so far.
+    pub fn reset(&self) {
+        *self.recorded() = RecordedMetrics::default();
+    }
+
+    pub fn latest_slot(&self) -> Option<u64> {
+        self.recorded()
+            .gauges_named(names::LATEST_SLOT)
+            .last()
+            .map(|gauge| gauge.value as u64)
+    }
+
+    /// Cache hits over all lookups, or `None` before the first lookup.
+    pub fn hit_ratio(&self) -> Option<f64> {
+        let recorded = self.recorded();
+        let hits = recorded.counter_total(names::CACHE_LOOKUPS, &[("result", "hit")]);
+        let total = recorded.counter_total(names::CACHE_LOOKUPS, &[]);
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+
+    /// Failed RPC calls as `(method, kind)` pairs, one per failure.
+    pub fn rpc_errors(&self) -> Vec<(String, String)> {
+        self.recorded()
+            .counters_named(names::RPC_ERRORS)
+            .into_iter()
+            .flat_map(|error| {
+                let failure = (
+                    error.label("method").unwrap_or_default().to_string(),
+                    error.label("kind").unwrap_or_default().to_string(),
+                );
+                std::iter::repeat_n(failure, error.value as usize)
+            })
+            .collect()
+    }
+
+    /// RPC calls visible through the `Metrics` trait: successful `getSlot`
+    /// calls (as latest slots), timed `get_blocks` calls, and failed calls
+    /// of other methods. Successful `getBlockTime` calls are not reported
+    /// to metrics and so are not counted.
+    pub fn total_rpc_calls(&self) -> usize {
+        let untimed_errors = self
+            .rpc_errors()
+            .iter()
+            .filter(|(method, _)| method != "getBlocks")
+            .count();
+        let recorded = self.recorded();
+        recorded.gauges_named(names::LATEST_SLOT).len()
+            + recorded.durations_named(names::GET_BLOCKS).len()
+            + untimed_errors
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        self.recorded()
+            .durations
+            .push(Measurement::new(op, labels, elapsed));
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        self.recorded()
+            .counters
+            .push(Measurement::new(counter, labels, n));
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.recorded()
+            .gauges
+            .push(Measurement::new(gauge, labels, value));
+    }
+
+    fn flush(&self) {
+        self.recorded().flushes += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::IntervalOutcome;
+    use crate::syndica_client::RpcErrorKind;
+
+    #[test]
+    fn test_records_calls_in_order() {
+        let metrics = InMemoryMetrics::new();
+        assert_eq!(metrics.hit_ratio(), None);
+        assert_eq!(metrics.latest_slot(), None);
+
+        metrics.record_latest_slot(10);
+        metrics.record_latest_slot(12);
+        metrics.record_get_blocks_elapsed(Duration::from_millis(5));
+        metrics.record_cache_hit(true);
+        metrics.record_cache_lookups(2, 1);
+        metrics.record_rpc_error("getBlocks", RpcErrorKind::Timeout);
+        metrics.record_rpc_error("getSlot", RpcErrorKind::RateLimited);
+        metrics.flush();
+
+        assert_eq!(metrics.latest_slot(), Some(12));
+        assert_eq!(metrics.hit_ratio(), Some(0.75));
+        assert_eq!(metrics.total_rpc_calls(), 4);
+        let recorded = metrics.snapshot();
+        assert_eq!(
+            recorded
+                .gauges_named(names::LATEST_SLOT)
+                .iter()
+                .map(|gauge| gauge.value)
+                .collect::<Vec<_>>(),
+            vec![10.0, 12.0]
+        );
+        assert_eq!(recorded.flushes, 1);
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot(), RecordedMetrics::default());
+    }
+
+    #[test]
+    fn test_shims_forward_to_generic_calls() {
+        let metrics = InMemoryMetrics::new();
+
+        metrics.record_is_slot_confirmed_elapsed(Duration::from_millis(3));
+        metrics.record_cache_lookups(0, 2);
+        metrics.record_sync_lag(40);
+        metrics.record_queue_depth(6);
+        metrics.record_interval_age(Duration::from_millis(1500));
+        metrics.record_interval_processed(
+            2,
+            100,
+            Duration::from_millis(20),
+            40,
+            3,
+            IntervalOutcome::Success,
+        );
+        metrics.record_interval_processed(1, 50, Duration::ZERO, 0, 0, IntervalOutcome::Failure);
+        metrics.record_worker_utilization(2, 0.5);
+        metrics.record_http_request("/stats", 404, Duration::from_millis(1));
+        metrics.record_http_in_flight("/stats", 3);
+        metrics.record_slots_inserted(25);
+
+        let recorded = metrics.snapshot();
+        let durations: Vec<_> = recorded
+            .durations
+            .iter()
+            .map(|m| (m.name, m.labels.clone(), m.value))
+            .collect();
+        assert_eq!(
+            durations,
+            vec![
+                (names::IS_SLOT_CONFIRMED, vec![], Duration::from_millis(3)),
+                (
+                    names::PROCESS_INTERVAL,
+                    vec![
+                        ("worker", "2".to_string()),
+                        ("outcome", "success".to_string())
+                    ],
+                    Duration::from_millis(20),
+                ),
+                (
+                    names::PROCESS_INTERVAL,
+                    vec![
+                        ("worker", "1".to_string()),
+                        ("outcome", "failure".to_string())
+                    ],
+                    Duration::ZERO,
+                ),
+                (
+                    names::HTTP_REQUEST,
+                    vec![
+                        ("route", "/stats".to_string()),
+                        ("status", "404".to_string()),
+                        ("status_class", "4xx".to_string()),
+                    ],
+                    Duration::from_millis(1),
+                ),
+            ]
+        );
+
+        // Zero counts and failed intervals add nothing.
+        assert_eq!(
+            recorded.counter_total(names::CACHE_LOOKUPS, &[("result", "hit")]),
+            0
+        );
+        assert_eq!(recorded.counters_named(names::CACHE_LOOKUPS).len(), 1);
+        assert_eq!(recorded.counter_total(names::INTERVAL_SLOTS, &[]), 100);
+        assert_eq!(
+            recorded.counter_total(names::INTERVAL_CONFIRMED_SLOTS, &[("worker", "2")]),
+            40
+        );
+        assert_eq!(
+            recorded.counter_total(names::INTERVAL_SUB_INTERVALS, &[]),
+            3
+        );
+        assert_eq!(recorded.counter_total(names::SLOTS_INSERTED, &[]), 25);
+
+        let gauges: Vec<_> = recorded
+            .gauges
+            .iter()
+            .map(|m| (m.name, m.label("worker").or(m.label("route")), m.value))
+            .collect();
+        assert_eq!(
+            gauges,
+            vec![
+                (names::SYNC_LAG, None, 40.0),
+                (names::QUEUE_DEPTH, None, 6.0),
+                (names::INTERVAL_AGE_SECONDS, None, 1.5),
+                (names::WORKER_UTILIZATION, Some("2"), 0.5),
+                (names::HTTP_IN_FLIGHT, Some("/stats"), 3.0),
+            ]
+        );
+    }
+}
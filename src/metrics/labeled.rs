@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tracing::warn;
+
+use super::LatencyHistogram;
+
+/// Label sets a single metric name may grow to before new ones are dropped.
+pub const DEFAULT_MAX_SERIES_PER_METRIC: usize = 100;
+
+/// Owned labels of one series, sorted by key so that the same labels passed
+/// in a different order land in the same series.
+pub(crate) type LabelSet = Vec<(&'static str, String)>;
+
+type Families<T> = Mutex<BTreeMap<&'static str, BTreeMap<LabelSet, T>>>;
+
+fn label_set(labels: &[(&'static str, &str)]) -> LabelSet {
+    let mut set: LabelSet = labels
+        .iter()
+        .map(|(key, value)| (*key, value.to_string()))
+        .collect();
+    set.sort();
+    set
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Counters, gauges and duration histograms recorded through the generic
+/// `Metrics` calls under names a backend has no dedicated series for.
+///
+/// Each metric name keeps at most `max_series` label sets. Measurements
+/// for a new label set beyond that are dropped and counted per name, so a
+/// label fed from unbounded input (a raw path, a slot number) cannot grow
+/// memory or the scrape without bound.
+pub(crate) struct LabeledSeries {
+    bounds_ms: Vec<u64>,
+    max_series: usize,
+    counters: Families<u64>,
+    gauges: Families<f64>,
+    durations: Families<Arc<LatencyHistogram>>,
+    dropped: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl LabeledSeries {
+    pub(crate) fn new(bounds_ms: &[u64]) -> Self {
+        Self {
+            bounds_ms: bounds_ms.to_vec(),
+            max_series: DEFAULT_MAX_SERIES_PER_METRIC,
+            counters: Mutex::default(),
+            gauges: Mutex::default(),
+            durations: Mutex::default(),
+            dropped: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn with_max_series(mut self, max_series: usize) -> Self {
+        self.max_series = max_series;
+        self
+    }
+
+    fn update<T>(
+        &self,
+        families: &Families<T>,
+        name: &'static str,
+        labels: &[(&'static str, &str)],
+        init: impl FnOnce() -> T,
+        update: impl FnOnce(&mut T),
+    ) {
+        let key = label_set(labels);
+        let mut families = lock(families);
+        let family = families.entry(name).or_default();
+        if !family.contains_key(&key) && family.len() >= self.max_series {
+            drop(families);
+            self.record_dropped(name);
+            return;
+        }
+        update(family.entry(key).or_insert_with(init));
+    }
+
+    fn record_dropped(&self, name: &'static str) {
+        let mut dropped = lock(&self.dropped);
+        let count = dropped.entry(name).or_default();
+        if *count == 0 {
+            warn!(
+                target: "metrics::labels",
+                metric = name,
+                max_series = self.max_series,
+                "Metric reached its series limit; new label sets are dropped"
+            );
+        }
+        *count += 1;
+    }
+
+    pub(crate) fn increment(&self, name: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        self.update(&self.counters, name, labels, || 0, |count| *count += n);
+    }
+
+    pub(crate) fn set_gauge(
+        &self,
+        name: &'static str,
+        labels: &[(&'static str, &str)],
+        value: f64,
+    ) {
+        self.update(&self.gauges, name, labels, || 0.0, |gauge| *gauge = value);
+    }
+
+    pub(crate) fn record_duration(
+        &self,
+        name: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        self.update(
+            &self.durations,
+            name,
+            labels,
+            || Arc::new(LatencyHistogram::new(&self.bounds_ms)),
+            |histogram| histogram.record(elapsed),
+        );
+    }
+
+    pub(crate) fn counters(&self) -> BTreeMap<&'static str, BTreeMap<LabelSet, u64>> {
+        lock(&self.counters).clone()
+    }
+
+    pub(crate) fn gauges(&self) -> BTreeMap<&'static str, BTreeMap<LabelSet, f64>> {
+        lock(&self.gauges).clone()
+    }
+
+    pub(crate) fn durations(
+        &self,
+    ) -> BTreeMap<&'static str, BTreeMap<LabelSet, Arc<LatencyHistogram>>> {
+        lock(&self.durations).clone()
+    }
+
+    /// Measurements dropped by the series limit, per metric name.
+    pub(crate) fn dropped(&self) -> BTreeMap<&'static str, u64> {
+        lock(&self.dropped).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_order_does_not_split_series() {
+        let series = LabeledSeries::new(&[10]);
+
+        series.increment("jobs", &[("queue", "a"), ("kind", "x")], 2);
+        series.increment("jobs", &[("kind", "x"), ("queue", "a")], 3);
+
+        let counters = series.counters();
+        assert_eq!(counters["jobs"].len(), 1);
+        assert_eq!(
+            counters["jobs"][&vec![("kind", "x".to_string()), ("queue", "a".to_string())]],
+            5
+        );
+    }
+
+    #[test]
+    fn test_series_limit_drops_new_label_sets() {
+        let series = LabeledSeries::new(&[10]).with_max_series(2);
+
+        for path in ["/a", "/b", "/c", "/d"] {
+            series.record_duration("request", &[("path", path)], Duration::from_millis(1));
+        }
+        series.record_duration("request", &[("path", "/a")], Duration::from_millis(1));
+        series.set_gauge("depth", &[("queue", "a")], 1.0);
+
+        let durations = series.durations();
+        assert_eq!(durations["request"].len(), 2);
+        assert_eq!(
+            durations["request"][&vec![("path", "/a".to_string())]].count(),
+            2
+        );
+        assert_eq!(series.dropped(), BTreeMap::from([("request", 2)]));
+        assert_eq!(series.gauges()["depth"].len(), 1);
+    }
+}
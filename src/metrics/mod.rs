@@ -0,0 +1,1157 @@
+mod composite;
+mod histogram;
+mod hit_ratio;
+mod in_memory;
+mod labeled;
+mod opentelemetry;
+mod prometheus;
+mod run_summary;
+mod sampler;
+mod sharded;
+mod statsd;
+mod throughput;
+
+pub use composite::CompositeMetrics;
+pub use histogram::{
+    DEFAULT_LATENCY_BUCKETS_MS, HistogramSnapshot, LatencyHistogram, LatencyPercentiles,
+    LatencyReport, LatencySummary,
+};
+pub use hit_ratio::{CacheHitRatios, CacheHitWindow};
+pub use in_memory::{InMemoryMetrics, Measurement, RecordedMetrics};
+pub use opentelemetry::{
+    DEFAULT_OTLP_ENDPOINT, DEFAULT_SERVICE_NAME, OpenTelemetryMetrics, OtlpExporter, OtlpSpanLayer,
+};
+pub use prometheus::PrometheusMetrics;
+pub use run_summary::{RunSummary, RunTotals};
+pub use sharded::ShardedCounter;
+pub use statsd::{DEFAULT_MAX_DATAGRAM_BYTES, DEFAULT_STATSD_ADDR, StatsdMetrics};
+pub use throughput::{SlotThroughput, SlotThroughputWindow};
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, trace, warn};
+
+use crate::syndica_client::RpcErrorKind;
+pub use labeled::DEFAULT_MAX_SERIES_PER_METRIC;
+pub(crate) use labeled::{LabelSet, LabeledSeries};
+use sampler::LogSampler;
+
+pub const DEFAULT_SLOW_OPERATION_THRESHOLD_MS: u64 = 1000;
+
+/// Roughly one minute of slots at the target 400ms slot time.
+pub const DEFAULT_SYNC_LAG_WARN_SLOTS: u64 = 150;
+pub const DEFAULT_QUEUE_DEPTH_WARN: usize = 1000;
+pub const DEFAULT_INTERVAL_AGE_WARN_MS: u64 = 30_000;
+
+/// Durations above which an operation is logged as slow: one threshold for
+/// every operation, overridable per operation name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowThresholds {
+    default: Duration,
+    per_operation: BTreeMap<String, Duration>,
+}
+
+impl SlowThresholds {
+    pub fn new(default: Duration) -> Self {
+        Self {
+            default,
+            per_operation: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_override(mut self, operation: &str, threshold: Duration) -> Self {
+        self.per_operation.insert(operation.to_string(), threshold);
+        self
+    }
+
+    pub fn threshold(&self, operation: &str) -> Duration {
+        self.per_operation
+            .get(operation)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// `"slow"` above the operation's threshold, `"moderate"` above half of
+    /// it and `"fast"` otherwise.
+    pub fn performance(&self, operation: &str, elapsed: Duration) -> &'static str {
+        let threshold = self.threshold(operation);
+        if elapsed > threshold {
+            "slow"
+        } else if elapsed > threshold / 2 {
+            "moderate"
+        } else {
+            "fast"
+        }
+    }
+}
+
+impl Default for SlowThresholds {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(DEFAULT_SLOW_OPERATION_THRESHOLD_MS))
+    }
+}
+
+/// Names the forwarding `Metrics` methods record under.
+///
+/// Backends match on these to keep their dedicated series and fall back to
+/// generic handling for any other name.
+pub mod names {
+    pub const LATEST_SLOT: &str = "latest_slot";
+    pub const SLOT_REGRESSIONS: &str = "slot_regressions";
+    pub const GET_BLOCKS: &str = "get_blocks";
+    pub const IS_SLOT_CONFIRMED: &str = "is_slot_confirmed";
+    pub const PROCESS_INTERVAL: &str = "process_interval";
+    pub const HTTP_REQUEST: &str = "http_request";
+    pub const CACHE_LOOKUPS: &str = "cache_lookups";
+    pub const RPC_ERRORS: &str = "rpc_errors";
+    pub const INTERVAL_SLOTS: &str = "interval_slots";
+    pub const INTERVAL_CONFIRMED_SLOTS: &str = "interval_confirmed_slots";
+    pub const INTERVAL_SUB_INTERVALS: &str = "interval_sub_intervals";
+    pub const SLOTS_INSERTED: &str = "slots_inserted";
+    pub const SYNC_LAG: &str = "sync_lag";
+    pub const QUEUE_DEPTH: &str = "queue_depth";
+    pub const INTERVAL_AGE_SECONDS: &str = "interval_age_seconds";
+    pub const WORKER_UTILIZATION: &str = "worker_utilization";
+    pub const HTTP_IN_FLIGHT: &str = "http_in_flight";
+    pub const LAST_SUCCESS: &str = "last_success";
+    pub const BACKFILL_COMPLETED_SLOTS: &str = "backfill_completed_slots";
+    pub const BACKFILL_TOTAL_SLOTS: &str = "backfill_total_slots";
+    pub const CONFIRMATION_LATENCY: &str = "confirmation_latency";
+    pub const TIME_TO_CACHE: &str = "time_to_cache";
+    pub const CONFIRMATION_SLA_VIOLATIONS: &str = "confirmation_sla_violations";
+    pub const DEAD_LETTERED_INTERVALS: &str = "dead_lettered_intervals";
+}
+
+/// Recording side of the metrics layer.
+///
+/// Backends implement the three generic calls. Labels are `(key, value)`
+/// pairs naming what a measurement belongs to, such as a route or a worker;
+/// values should come from a small fixed set, never from raw request input.
+/// The `record_*` methods are shims that forward to the generic calls under
+/// the names in [`names`] and exist so call sites can stay short.
+pub trait Metrics {
+    /// Duration of one `op`, such as a single `get_blocks` call.
+    fn record_duration(&self, op: &'static str, labels: &[(&'static str, &str)], elapsed: Duration);
+    /// Adds `n` to a monotonic counter.
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64);
+    /// Sets a gauge to its current value.
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64);
+
+    fn record_latest_slot(&self, slot: u64) {
+        self.set_gauge(names::LATEST_SLOT, &[], slot as f64);
+    }
+
+    fn record_get_blocks_elapsed(&self, elapsed: Duration) {
+        self.record_duration(names::GET_BLOCKS, &[], elapsed);
+    }
+
+    fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration) {
+        self.record_duration(names::IS_SLOT_CONFIRMED, &[], elapsed);
+    }
+
+    fn record_cache_hit(&self, hit: bool) {
+        self.record_cache_lookups(hit as usize, !hit as usize);
+    }
+
+    /// Counted under `cache_lookups` with a `result` label of `hit` or
+    /// `miss`; zero counts are skipped.
+    fn record_cache_lookups(&self, hits: usize, misses: usize) {
+        for (result, count) in [("hit", hits), ("miss", misses)] {
+            if count > 0 {
+                self.increment(names::CACHE_LOOKUPS, &[("result", result)], count as u64);
+            }
+        }
+    }
+
+    fn record_sync_lag(&self, slots: u64) {
+        self.set_gauge(names::SYNC_LAG, &[], slots as f64);
+    }
+
+    fn record_queue_depth(&self, depth: usize) {
+        self.set_gauge(names::QUEUE_DEPTH, &[], depth as f64);
+    }
+
+    fn record_interval_age(&self, age: Duration) {
+        self.set_gauge(names::INTERVAL_AGE_SECONDS, &[], age.as_secs_f64());
+    }
+
+    fn record_rpc_error(&self, method: &str, kind: RpcErrorKind) {
+        self.increment(
+            names::RPC_ERRORS,
+            &[("method", method), ("kind", kind.as_str())],
+            1,
+        );
+    }
+
+    /// Records the pass duration under `process_interval` labelled with
+    /// `worker` and `outcome`. Successful passes also add their slot,
+    /// confirmed slot and sub-interval counts under the same labels.
+    fn record_interval_processed(
+        &self,
+        worker_id: usize,
+        size: u64,
+        elapsed: Duration,
+        confirmed_count: usize,
+        sub_intervals: usize,
+        outcome: IntervalOutcome,
+    ) {
+        let worker = worker_id.to_string();
+        let labels = [("worker", worker.as_str()), ("outcome", outcome.as_str())];
+        self.record_duration(names::PROCESS_INTERVAL, &labels, elapsed);
+        if outcome == IntervalOutcome::Success {
+            self.increment(names::INTERVAL_SLOTS, &labels, size);
+            self.increment(
+                names::INTERVAL_CONFIRMED_SLOTS,
+                &labels,
+                confirmed_count as u64,
+            );
+            self.increment(names::INTERVAL_SUB_INTERVALS, &labels, sub_intervals as u64);
+        }
+    }
+
+    /// Share of the last report period `worker_id` spent processing
+    /// intervals rather than sleeping, between 0.0 and 1.0.
+    fn record_worker_utilization(&self, worker_id: usize, busy_ratio: f64) {
+        let worker = worker_id.to_string();
+        self.set_gauge(
+            names::WORKER_UTILIZATION,
+            &[("worker", worker.as_str())],
+            busy_ratio,
+        );
+    }
+
+    /// One finished HTTP request. `route` is the route template, such as
+    /// `/isSlotConfirmed/{slot}`, never the raw path.
+    fn record_http_request(&self, route: &str, status: u16, elapsed: Duration) {
+        let status_code = status.to_string();
+        self.record_duration(
+            names::HTTP_REQUEST,
+            &[
+                ("route", route),
+                ("status", status_code.as_str()),
+                ("status_class", status_class(status)),
+            ],
+            elapsed,
+        );
+    }
+
+    /// Requests currently being handled for `route`.
+    fn record_http_in_flight(&self, route: &str, in_flight: usize) {
+        self.set_gauge(names::HTTP_IN_FLIGHT, &[("route", route)], in_flight as f64);
+    }
+
+    /// Slots newly added to the cache by one synchronizer range query.
+    fn record_slots_inserted(&self, count: usize) {
+        self.increment(names::SLOTS_INSERTED, &[], count as u64);
+    }
+
+    /// `activity` (e.g. `get_slot`) last succeeded at `at`, recorded as a
+    /// Unix timestamp in seconds.
+    fn record_last_success(&self, activity: &str, at: SystemTime) {
+        let timestamp = at.duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.set_gauge(
+            names::LAST_SUCCESS,
+            &[("activity", activity)],
+            timestamp.as_secs_f64(),
+        );
+    }
+
+    /// Slots of the monitoring window backfilled so far, out of `total`.
+    /// Reported periodically until the backfill completes.
+    fn record_backfill_progress(&self, completed: u64, total: u64) {
+        self.set_gauge(names::BACKFILL_COMPLETED_SLOTS, &[], completed as f64);
+        self.set_gauge(names::BACKFILL_TOTAL_SLOTS, &[], total as f64);
+    }
+
+    /// Time from a slot's block time to the monitor caching it as confirmed.
+    fn record_confirmation_latency(&self, latency: Duration) {
+        self.record_duration(names::CONFIRMATION_LATENCY, &[], latency);
+    }
+
+    /// Time from the slot updater queueing a new slot to caching it.
+    fn record_time_to_cache(&self, elapsed: Duration) {
+        self.record_duration(names::TIME_TO_CACHE, &[], elapsed);
+    }
+
+    /// A slot took longer than `CONFIRMATION_SLA_MS` to be cached.
+    fn record_confirmation_sla_violation(&self) {
+        self.increment(names::CONFIRMATION_SLA_VIOLATIONS, &[], 1);
+    }
+
+    /// An interval was given up on after `SYNC_MAX_ATTEMPTS` failures.
+    fn record_interval_dead_lettered(&self) {
+        self.increment(names::DEAD_LETTERED_INTERVALS, &[], 1);
+    }
+
+    /// Latency percentiles per operation, for backends that aggregate them.
+    fn latency_percentiles(&self) -> Option<LatencyReport> {
+        None
+    }
+
+    /// Rolling cache hit ratios, for backends that track them.
+    fn cache_hit_ratios(&self) -> Option<CacheHitRatios> {
+        None
+    }
+
+    /// Rolling rate of newly cached slots, for backends that track it.
+    fn slot_throughput(&self) -> Option<SlotThroughput> {
+        None
+    }
+
+    /// Metrics in the Prometheus text exposition format, for backends that
+    /// keep scrapeable state.
+    fn render_prometheus(&self) -> Option<String> {
+        None
+    }
+
+    /// Emits and resets any per-period aggregates. Called periodically by
+    /// the summary task spawned in main.
+    fn flush(&self) {}
+
+    /// Replaces the slow-operation thresholds at runtime, for backends that
+    /// log slow operations. Called when the configuration is reloaded.
+    fn set_slow_thresholds(&self, _thresholds: SlowThresholds) {}
+}
+
+/// Result of one synchronizer worker pass over an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalOutcome {
+    Success,
+    Failure,
+}
+
+impl IntervalOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IntervalOutcome::Success => "success",
+            IntervalOutcome::Failure => "failure",
+        }
+    }
+
+    /// Parses an `outcome` label written by `record_interval_processed`.
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "success" => Some(IntervalOutcome::Success),
+            "failure" => Some(IntervalOutcome::Failure),
+            _ => None,
+        }
+    }
+}
+
+/// Share of a backfill that is complete; an empty backfill is complete.
+pub(crate) fn backfill_fraction(completed: u64, total: u64) -> f64 {
+    if total == 0 {
+        1.0
+    } else {
+        completed as f64 / total as f64
+    }
+}
+
+/// Value of the label `key`, if the measurement carries it.
+pub fn label<'a>(labels: &[(&'static str, &'a str)], key: &str) -> Option<&'a str> {
+    labels
+        .iter()
+        .find(|(name, _)| *name == key)
+        .map(|(_, value)| *value)
+}
+
+/// Labels rendered as space-separated `key=value` pairs for a log field.
+pub(crate) struct DisplayLabels<'a>(pub(crate) &'a [(&'static str, &'a str)]);
+
+impl std::fmt::Display for DisplayLabels<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, (key, value)) in self.0.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Cumulative totals over processed synchronizer intervals. Slot, confirmed
+/// and sub-interval totals only count successful passes.
+#[derive(Default)]
+pub(crate) struct IntervalCounters {
+    pub(crate) succeeded: ShardedCounter,
+    pub(crate) failed: ShardedCounter,
+    pub(crate) slots: ShardedCounter,
+    pub(crate) confirmed_slots: ShardedCounter,
+    pub(crate) sub_intervals: ShardedCounter,
+}
+
+impl IntervalCounters {
+    /// Counts one `process_interval` pass by its `outcome` label, and by
+    /// worker in `workers` when it also carries a `worker` label.
+    pub(crate) fn record_pass(&self, workers: &WorkerStatsMap, labels: &[(&'static str, &str)]) {
+        let Some(outcome) = label(labels, "outcome").and_then(IntervalOutcome::from_label) else {
+            return;
+        };
+        match outcome {
+            IntervalOutcome::Success => self.succeeded.add(1),
+            IntervalOutcome::Failure => self.failed.add(1),
+        }
+        if let Some(worker_id) = label(labels, "worker").and_then(|worker| worker.parse().ok()) {
+            workers.record_outcome(worker_id, outcome);
+        }
+    }
+
+    /// The total a per-interval counter adds to, if `counter` is one.
+    pub(crate) fn total(&self, counter: &str) -> Option<&ShardedCounter> {
+        match counter {
+            names::INTERVAL_SLOTS => Some(&self.slots),
+            names::INTERVAL_CONFIRMED_SLOTS => Some(&self.confirmed_slots),
+            names::INTERVAL_SUB_INTERVALS => Some(&self.sub_intervals),
+            _ => None,
+        }
+    }
+}
+
+/// Failed RPC calls counted by method and error kind.
+///
+/// Failures are rare compared to the other metrics, so a lock is fine here.
+#[derive(Default)]
+pub(crate) struct RpcErrorCounts {
+    counts: Mutex<BTreeMap<(String, String), u64>>,
+}
+
+impl RpcErrorCounts {
+    /// Adds `n` failures under the `method` and `kind` labels.
+    pub(crate) fn increment(&self, labels: &[(&'static str, &str)], n: u64) {
+        let method = label(labels, "method").unwrap_or_default();
+        let kind = label(labels, "kind").unwrap_or(RpcErrorKind::Other.as_str());
+        let mut counts = self
+            .counts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        *counts
+            .entry((method.to_string(), kind.to_string()))
+            .or_default() += n;
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<(String, String, u64)> {
+        self.counts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|((method, kind), count)| (method.clone(), kind.clone(), *count))
+            .collect()
+    }
+}
+
+/// Label for the class of an HTTP status code, e.g. `4xx`.
+pub fn status_class(status: u16) -> &'static str {
+    match status / 100 {
+        1 => "1xx",
+        2 => "2xx",
+        3 => "3xx",
+        4 => "4xx",
+        5 => "5xx",
+        _ => "other",
+    }
+}
+
+/// HTTP request latencies by route template and status class, and the
+/// latest in-flight count by route.
+///
+/// Routes are templates from the router, so the number of series stays
+/// bounded by the number of routes.
+pub(crate) struct HttpRequestStats {
+    bounds_ms: Vec<u64>,
+    latencies: Mutex<BTreeMap<(String, String), Arc<LatencyHistogram>>>,
+    in_flight: Mutex<BTreeMap<String, usize>>,
+}
+
+impl HttpRequestStats {
+    pub(crate) fn new(bounds_ms: &[u64]) -> Self {
+        Self {
+            bounds_ms: bounds_ms.to_vec(),
+            latencies: Mutex::default(),
+            in_flight: Mutex::default(),
+        }
+    }
+
+    /// Records one request under its `route` and `status_class` labels.
+    pub(crate) fn record(&self, labels: &[(&'static str, &str)], elapsed: Duration) {
+        let route = label(labels, "route").unwrap_or_default();
+        let class = label(labels, "status_class").unwrap_or("other");
+        let histogram = self
+            .latencies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry((route.to_string(), class.to_string()))
+            .or_insert_with(|| Arc::new(LatencyHistogram::new(&self.bounds_ms)))
+            .clone();
+        histogram.record(elapsed);
+    }
+
+    pub(crate) fn set_in_flight(&self, route: &str, in_flight: usize) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(route.to_string(), in_flight);
+    }
+
+    pub(crate) fn latencies(&self) -> Vec<(String, String, Arc<LatencyHistogram>)> {
+        self.latencies
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|((route, class), histogram)| (route.clone(), class.clone(), histogram.clone()))
+            .collect()
+    }
+
+    pub(crate) fn in_flight(&self) -> Vec<(String, usize)> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(route, in_flight)| (route.clone(), *in_flight))
+            .collect()
+    }
+}
+
+/// Latest utilization and interval outcome counts per synchronizer worker.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct WorkerStats {
+    pub(crate) busy_ratio: Option<f64>,
+    pub(crate) succeeded: u64,
+    pub(crate) failed: u64,
+}
+
+/// Per-worker stats keyed by worker id. There are only a handful of
+/// workers and each records once per interval, so a lock is fine here.
+#[derive(Default)]
+pub(crate) struct WorkerStatsMap {
+    workers: Mutex<BTreeMap<usize, WorkerStats>>,
+}
+
+impl WorkerStatsMap {
+    fn update(&self, worker_id: usize, update: impl FnOnce(&mut WorkerStats)) {
+        let mut workers = self
+            .workers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        update(workers.entry(worker_id).or_default());
+    }
+
+    /// Sets the busy ratio of the worker named by the `worker` label.
+    pub(crate) fn record_utilization(&self, labels: &[(&'static str, &str)], busy_ratio: f64) {
+        if let Some(worker_id) = label(labels, "worker").and_then(|worker| worker.parse().ok()) {
+            self.update(worker_id, |stats| stats.busy_ratio = Some(busy_ratio));
+        }
+    }
+
+    pub(crate) fn record_outcome(&self, worker_id: usize, outcome: IntervalOutcome) {
+        self.update(worker_id, |stats| match outcome {
+            IntervalOutcome::Success => stats.succeeded += 1,
+            IntervalOutcome::Failure => stats.failed += 1,
+        });
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<(usize, WorkerStats)> {
+        self.workers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .map(|(worker_id, stats)| (*worker_id, *stats))
+            .collect()
+    }
+}
+
+/// Metrics implementation selected at startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MetricsBackend {
+    #[default]
+    Tracing,
+    Prometheus,
+    Statsd,
+    OpenTelemetry,
+}
+
+impl FromStr for MetricsBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "tracing" => Ok(MetricsBackend::Tracing),
+            "prometheus" => Ok(MetricsBackend::Prometheus),
+            "statsd" => Ok(MetricsBackend::Statsd),
+            "otlp" | "opentelemetry" => Ok(MetricsBackend::OpenTelemetry),
+            other => Err(format!("unknown metrics backend: {}", other)),
+        }
+    }
+}
+
+/// Metrics backend that writes structured log events.
+///
+/// Per-call durations are logged at trace level and aggregated into
+/// histograms. Cumulative histograms back `latency_percentiles`, while a
+/// second set covering only the current period is summarised and reset on
+/// every `flush`, together with the rolling cache hit ratios.
+pub struct TracingMetrics {
+    get_blocks_latency: LatencyHistogram,
+    is_slot_confirmed_latency: LatencyHistogram,
+    get_blocks_window: LatencyHistogram,
+    is_slot_confirmed_window: LatencyHistogram,
+    cache_hit_window: CacheHitWindow,
+    slot_throughput_window: SlotThroughputWindow,
+    sync_lag_warn_slots: u64,
+    queue_depth_warn: usize,
+    interval_age_warn: Duration,
+    slow_thresholds: Mutex<SlowThresholds>,
+    cache_log_sampler: LogSampler,
+}
+
+impl TracingMetrics {
+    pub fn new() -> Self {
+        Self::with_latency_buckets(DEFAULT_LATENCY_BUCKETS_MS)
+    }
+
+    pub fn with_latency_buckets(bounds_ms: &[u64]) -> Self {
+        Self {
+            get_blocks_latency: LatencyHistogram::new(bounds_ms),
+            is_slot_confirmed_latency: LatencyHistogram::new(bounds_ms),
+            get_blocks_window: LatencyHistogram::new(bounds_ms),
+            is_slot_confirmed_window: LatencyHistogram::new(bounds_ms),
+            cache_hit_window: CacheHitWindow::new(),
+            slot_throughput_window: SlotThroughputWindow::new(),
+            sync_lag_warn_slots: DEFAULT_SYNC_LAG_WARN_SLOTS,
+            queue_depth_warn: DEFAULT_QUEUE_DEPTH_WARN,
+            interval_age_warn: Duration::from_millis(DEFAULT_INTERVAL_AGE_WARN_MS),
+            slow_thresholds: Mutex::new(SlowThresholds::default()),
+            cache_log_sampler: LogSampler::new(1.0),
+        }
+    }
+
+    /// Share of cache lookups that get a debug log line, from 0.0 to 1.0.
+    /// Hit ratios are still computed from every lookup.
+    pub fn with_cache_sample_rate(mut self, rate: f64) -> Self {
+        self.cache_log_sampler = LogSampler::new(rate);
+        self
+    }
+
+    /// Operations slower than their threshold are logged as a warning.
+    pub fn with_slow_thresholds(mut self, thresholds: SlowThresholds) -> Self {
+        self.slow_thresholds = Mutex::new(thresholds);
+        self
+    }
+
+    /// Queue depth and oldest-interval age above which a warning is logged.
+    pub fn with_queue_warn_thresholds(mut self, depth: usize, age: Duration) -> Self {
+        self.queue_depth_warn = depth;
+        self.interval_age_warn = age;
+        self
+    }
+
+    /// Sync lag above this many slots is logged as a warning.
+    pub fn with_sync_lag_warn_threshold(mut self, slots: u64) -> Self {
+        self.sync_lag_warn_slots = slots;
+        self
+    }
+
+    /// Summarises the current period per operation and starts a new one.
+    pub fn take_summaries(&self) -> [(&'static str, LatencySummary); 2] {
+        [
+            ("get_blocks", self.get_blocks_window.take().summary()),
+            (
+                "is_slot_confirmed",
+                self.is_slot_confirmed_window.take().summary(),
+            ),
+        ]
+    }
+
+    pub fn percentiles(&self) -> LatencyReport {
+        LatencyReport {
+            get_blocks: self.get_blocks_latency.percentiles(),
+            is_slot_confirmed: self.is_slot_confirmed_latency.percentiles(),
+        }
+    }
+
+    fn get_timestamp_ms() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
+    }
+
+    fn log_performance(&self, operation: &str, labels: &[(&'static str, &str)], elapsed: Duration) {
+        let elapsed_ms = elapsed.as_millis() as u64;
+        let elapsed_micros = elapsed.as_micros() as u64;
+
+        let (log_level, threshold) = {
+            let thresholds = self
+                .slow_thresholds
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (
+                thresholds.performance(operation, elapsed),
+                thresholds.threshold(operation),
+            )
+        };
+
+        if log_level == "slow" {
+            warn!(
+                target: "metrics::performance::slow",
+                operation = operation,
+                labels = %DisplayLabels(labels),
+                elapsed_ms = elapsed_ms,
+                elapsed_micros = elapsed_micros,
+                threshold_ms = threshold.as_millis() as u64,
+                performance = log_level,
+                "Slow operation detected"
+            );
+        } else {
+            debug!(
+                target: "metrics::performance",
+                operation = operation,
+                labels = %DisplayLabels(labels),
+                elapsed_ms = elapsed_ms,
+                elapsed_micros = elapsed_micros,
+                performance = log_level,
+                "Operation completed"
+            );
+        }
+
+        trace!(
+            target: "metrics::timing",
+            operation = operation,
+            labels = %DisplayLabels(labels),
+            elapsed_ns = elapsed.as_nanos() as u64,
+            elapsed_micros = elapsed_micros,
+            elapsed_ms = elapsed_ms,
+            timestamp = Self::get_timestamp_ms(),
+            "Detailed timing information"
+        );
+    }
+}
+
+impl Default for TracingMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for TracingMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        match op {
+            names::GET_BLOCKS => {
+                self.get_blocks_latency.record(elapsed);
+                self.get_blocks_window.record(elapsed);
+            }
+            names::IS_SLOT_CONFIRMED => {
+                self.is_slot_confirmed_latency.record(elapsed);
+                self.is_slot_confirmed_window.record(elapsed);
+            }
+            names::PROCESS_INTERVAL
+                if label(labels, "outcome") == Some(IntervalOutcome::Failure.as_str()) =>
+            {
+                warn!(
+                    target: "metrics::sync",
+                    operation = op,
+                    labels = %DisplayLabels(labels),
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    metric_type = "interval_processed",
+                    "Interval processing failed"
+                );
+                return;
+            }
+            // Measured against the chain's clock rather than timing our own
+            // work, so slow-operation thresholds do not apply.
+            names::CONFIRMATION_LATENCY => {
+                debug!(
+                    target: "metrics::sync",
+                    latency_ms = elapsed.as_millis() as u64,
+                    metric_type = "confirmation_latency",
+                    "Slot confirmation latency"
+                );
+                return;
+            }
+            // Includes time spent queued, which the SLA counter covers.
+            names::TIME_TO_CACHE => {
+                debug!(
+                    target: "metrics::sync",
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    metric_type = "time_to_cache",
+                    "Slot time to cache"
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        self.log_performance(op, labels, elapsed);
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        match counter {
+            names::CACHE_LOOKUPS => {
+                match label(labels, "result") {
+                    Some("hit") => self.cache_hit_window.record(n as usize, 0),
+                    Some("miss") => self.cache_hit_window.record(0, n as usize),
+                    _ => {}
+                }
+                if self.cache_log_sampler.sample(n) {
+                    debug!(
+                        target: "metrics::cache",
+                        counter,
+                        labels = %DisplayLabels(labels),
+                        count = n,
+                        sample_rate = self.cache_log_sampler.rate(),
+                        timestamp = Self::get_timestamp_ms(),
+                        "Counter incremented"
+                    );
+                }
+                return;
+            }
+            names::SLOTS_INSERTED => self.slot_throughput_window.record(n as usize),
+            names::RPC_ERRORS => {
+                warn!(
+                    target: "metrics::rpc",
+                    counter,
+                    labels = %DisplayLabels(labels),
+                    count = n,
+                    metric_type = "rpc_error",
+                    "RPC call failed"
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        debug!(
+            target: "metrics::counter",
+            counter,
+            labels = %DisplayLabels(labels),
+            count = n,
+            timestamp = Self::get_timestamp_ms(),
+            "Counter incremented"
+        );
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let warning = match gauge {
+            names::SYNC_LAG => Some((
+                self.sync_lag_warn_slots as f64,
+                "Synchronizer is falling behind the chain tip",
+            )),
+            names::QUEUE_DEPTH => {
+                Some((self.queue_depth_warn as f64, "Interval queue is backing up"))
+            }
+            names::INTERVAL_AGE_SECONDS => Some((
+                self.interval_age_warn.as_secs_f64(),
+                "Oldest queued interval is waiting too long",
+            )),
+            _ => None,
+        };
+
+        match warning {
+            Some((threshold, message)) if value > threshold => warn!(
+                target: "metrics::gauge",
+                gauge,
+                labels = %DisplayLabels(labels),
+                value,
+                threshold,
+                "{}",
+                message
+            ),
+            _ => debug!(
+                target: "metrics::gauge",
+                gauge,
+                labels = %DisplayLabels(labels),
+                value,
+                timestamp = Self::get_timestamp_ms(),
+                "Gauge updated"
+            ),
+        }
+    }
+
+    fn record_backfill_progress(&self, completed: u64, total: u64) {
+        info!(
+            target: "metrics::backfill",
+            completed,
+            total,
+            fraction = backfill_fraction(completed, total),
+            "Backfill progress: {}/{} slots ({:.1}%)",
+            completed,
+            total,
+            backfill_fraction(completed, total) * 100.0
+        );
+    }
+
+    fn latency_percentiles(&self) -> Option<LatencyReport> {
+        Some(self.percentiles())
+    }
+
+    fn cache_hit_ratios(&self) -> Option<CacheHitRatios> {
+        Some(self.cache_hit_window.ratios())
+    }
+
+    fn slot_throughput(&self) -> Option<SlotThroughput> {
+        Some(self.slot_throughput_window.rates())
+    }
+
+    fn set_slow_thresholds(&self, thresholds: SlowThresholds) {
+        *self
+            .slow_thresholds
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = thresholds;
+    }
+
+    fn flush(&self) {
+        for (operation, summary) in self.take_summaries() {
+            info!(
+                target: "metrics::summary",
+                operation = operation,
+                count = summary.count,
+                mean_ms = summary.mean_ms,
+                p50_ms = summary.p50_ms,
+                p95_ms = summary.p95_ms,
+                p99_ms = summary.p99_ms,
+                max_ms = summary.max_ms,
+                "Latency summary"
+            );
+        }
+
+        let ratios = self.cache_hit_window.ratios();
+        info!(
+            target: "metrics::summary",
+            hit_ratio_1m = ratios.ratio_1m,
+            hit_ratio_5m = ratios.ratio_5m,
+            hit_ratio_60m = ratios.ratio_60m,
+            "Cache hit ratio summary"
+        );
+
+        let throughput = self.slot_throughput_window.rates();
+        info!(
+            target: "metrics::summary",
+            slots_per_sec_1m = throughput.slots_per_sec_1m,
+            slots_per_sec_5m = throughput.slots_per_sec_5m,
+            "Sync throughput summary"
+        );
+    }
+}
+
+#[derive(Default)]
+pub struct NoOpMetrics;
+
+impl Metrics for NoOpMetrics {
+    fn record_duration(
+        &self,
+        _op: &'static str,
+        _labels: &[(&'static str, &str)],
+        _elapsed: Duration,
+    ) {
+    }
+
+    fn increment(&self, _counter: &'static str, _labels: &[(&'static str, &str)], _n: u64) {}
+
+    fn set_gauge(&self, _gauge: &'static str, _labels: &[(&'static str, &str)], _value: f64) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_metrics_trait_implementation() {
+        let metrics = TracingMetrics::new();
+
+        metrics.record_latest_slot(12345);
+
+        metrics.record_get_blocks_elapsed(Duration::from_millis(100));
+
+        metrics.record_is_slot_confirmed_elapsed(Duration::from_millis(50));
+
+        metrics.record_cache_hit(true);
+        metrics.record_cache_hit(false);
+        metrics.record_cache_lookups(3, 1);
+
+        let metrics = metrics.with_sync_lag_warn_threshold(10);
+        metrics.record_sync_lag(5);
+        metrics.record_sync_lag(50);
+
+        let metrics = metrics.with_queue_warn_thresholds(10, Duration::from_secs(1));
+        metrics.record_queue_depth(5);
+        metrics.record_queue_depth(50);
+        metrics.record_interval_age(Duration::from_millis(10));
+        metrics.record_interval_age(Duration::from_secs(5));
+        metrics.record_interval_processed(
+            2,
+            100,
+            Duration::from_millis(40),
+            30,
+            4,
+            IntervalOutcome::Success,
+        );
+        metrics.record_interval_processed(3, 100, Duration::ZERO, 0, 0, IntervalOutcome::Failure);
+    }
+
+    #[test]
+    fn test_tracing_metrics_percentiles() {
+        let metrics = TracingMetrics::new();
+
+        for ms in [10, 20, 30, 40, 1000] {
+            metrics.record_get_blocks_elapsed(Duration::from_millis(ms));
+        }
+        metrics.record_is_slot_confirmed_elapsed(Duration::from_millis(1));
+
+        let report = metrics.latency_percentiles().unwrap();
+        assert_eq!(report.get_blocks.count, 5);
+        assert!(report.get_blocks.p50_ms <= 50.0);
+        assert_eq!(report.get_blocks.p99_ms, 1000.0);
+        assert_eq!(report.is_slot_confirmed.count, 1);
+        assert!(NoOpMetrics.latency_percentiles().is_none());
+    }
+
+    #[test]
+    fn test_tracing_metrics_summary_window() {
+        let metrics = TracingMetrics::with_latency_buckets(&[10, 100]);
+
+        metrics.record_get_blocks_elapsed(Duration::from_millis(5));
+        metrics.record_get_blocks_elapsed(Duration::from_millis(50));
+        metrics.record_is_slot_confirmed_elapsed(Duration::from_millis(1));
+
+        let [(operation, get_blocks), (_, is_slot_confirmed)] = metrics.take_summaries();
+        assert_eq!(operation, "get_blocks");
+        assert_eq!(get_blocks.count, 2);
+        assert_eq!(get_blocks.mean_ms, 27.5);
+        assert_eq!(get_blocks.p50_ms, 10.0);
+        assert_eq!(get_blocks.max_ms, 50.0);
+        assert_eq!(is_slot_confirmed.count, 1);
+
+        let [(_, get_blocks), _] = metrics.take_summaries();
+        assert_eq!(get_blocks.count, 0);
+        assert_eq!(metrics.percentiles().get_blocks.count, 2);
+
+        metrics.flush();
+    }
+
+    #[test]
+    fn test_noop_metrics() {
+        let metrics = NoOpMetrics;
+
+        metrics.record_latest_slot(12345);
+        metrics.record_get_blocks_elapsed(Duration::from_millis(100));
+        metrics.record_is_slot_confirmed_elapsed(Duration::from_millis(50));
+        metrics.record_cache_hit(true);
+        metrics.record_cache_hit(false);
+        metrics.record_cache_lookups(3, 1);
+    }
+
+    #[test]
+    fn test_generic_calls_reach_dedicated_series() {
+        let metrics = TracingMetrics::new();
+
+        metrics.record_duration(
+            names::GET_BLOCKS,
+            &[("method", "getBlocks")],
+            Duration::from_millis(10),
+        );
+        metrics.record_get_blocks_elapsed(Duration::from_millis(20));
+        metrics.increment(names::CACHE_LOOKUPS, &[("result", "hit")], 3);
+        metrics.record_cache_hit(false);
+        metrics.increment("custom_jobs", &[("queue", "backfill")], 1);
+        metrics.set_gauge("custom_depth", &[], 4.0);
+        metrics.record_duration(
+            "custom_op",
+            &[("route", "/health"), ("status_class", "2xx")],
+            Duration::from_millis(1),
+        );
+
+        assert_eq!(metrics.percentiles().get_blocks.count, 2);
+        assert_eq!(metrics.cache_hit_window.ratios().ratio_1m, Some(0.75));
+    }
+
+    #[test]
+    fn test_label_lookup_and_display() {
+        let labels = [("route", "/stats"), ("status_class", "2xx")];
+
+        assert_eq!(label(&labels, "route"), Some("/stats"));
+        assert_eq!(label(&labels, "worker"), None);
+        assert_eq!(
+            DisplayLabels(&labels).to_string(),
+            "route=/stats status_class=2xx"
+        );
+        assert_eq!(DisplayLabels(&[]).to_string(), "");
+        assert_eq!(
+            IntervalOutcome::from_label("failure"),
+            Some(IntervalOutcome::Failure)
+        );
+        assert_eq!(IntervalOutcome::from_label("skipped"), None);
+    }
+
+    #[test]
+    fn test_slow_threshold_boundaries() {
+        let thresholds = SlowThresholds::new(Duration::from_millis(1000))
+            .with_override(names::IS_SLOT_CONFIRMED, Duration::from_millis(200));
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            thresholds.performance(names::GET_BLOCKS, ms(1000)),
+            "moderate"
+        );
+        assert_eq!(thresholds.performance(names::GET_BLOCKS, ms(1001)), "slow");
+        assert_eq!(thresholds.performance(names::GET_BLOCKS, ms(500)), "fast");
+        assert_eq!(
+            thresholds.performance(names::GET_BLOCKS, ms(501)),
+            "moderate"
+        );
+        assert_eq!(
+            thresholds.performance(names::IS_SLOT_CONFIRMED, ms(200)),
+            "moderate"
+        );
+        assert_eq!(
+            thresholds.performance(names::IS_SLOT_CONFIRMED, ms(201)),
+            "slow"
+        );
+        assert_eq!(
+            thresholds.performance(names::IS_SLOT_CONFIRMED, ms(100)),
+            "fast"
+        );
+        assert_eq!(
+            SlowThresholds::default().threshold("anything"),
+            ms(DEFAULT_SLOW_OPERATION_THRESHOLD_MS)
+        );
+    }
+
+    #[test]
+    fn test_slow_thresholds_replaced_at_runtime() {
+        let metrics = TracingMetrics::new();
+        let thresholds = SlowThresholds::new(Duration::from_millis(50))
+            .with_override(names::GET_BLOCKS, Duration::from_millis(10));
+        metrics.set_slow_thresholds(thresholds.clone());
+
+        assert_eq!(*metrics.slow_thresholds.lock().unwrap(), thresholds);
+    }
+
+    #[test]
+    fn test_cache_sampling_keeps_hit_ratio_exact() {
+        let metrics = TracingMetrics::new().with_cache_sample_rate(0.01);
+
+        for _ in 0..300 {
+            metrics.record_cache_hit(true);
+        }
+        metrics.record_cache_lookups(0, 100);
+
+        assert_eq!(metrics.cache_hit_window.ratios().ratio_1m, Some(0.75));
+    }
+
+    #[test]
+    fn test_cache_hit_miss_tracking() {
+        let metrics = TracingMetrics::new();
+
+        metrics.record_cache_hit(true);
+        metrics.record_cache_hit(false);
+        metrics.record_cache_hit(true);
+    }
+}
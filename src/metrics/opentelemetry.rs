@@ -0,0 +1,961 @@
+use serde_json::{Value, json};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Subscriber, warn};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use super::{
+    DEFAULT_LATENCY_BUCKETS_MS, HttpRequestStats, IntervalCounters, LabelSet, LabeledSeries,
+    LatencyHistogram, LatencyReport, Metrics, RpcErrorCounts, WorkerStatsMap, label, names,
+};
+use crate::redact::redacted_url;
+use crate::types::BoxError;
+
+pub const DEFAULT_OTLP_ENDPOINT: &str = "http://127.0.0.1:4318";
+pub const DEFAULT_SERVICE_NAME: &str = "solana-block-monitor";
+
+const SCOPE_NAME: &str = "solana_block_monitor";
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Finished spans kept between exports; newer spans are dropped beyond this.
+const MAX_PENDING_SPANS: usize = 4096;
+
+/// OTLP `AggregationTemporality::CUMULATIVE`.
+const CUMULATIVE: u8 = 2;
+/// OTLP `SpanKind` values.
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_SERVER: u8 = 2;
+const SPAN_KIND_CLIENT: u8 = 3;
+
+fn now_unix_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn label_attributes(labels: &LabelSet) -> Vec<Value> {
+    labels
+        .iter()
+        .map(|(key, value)| string_attribute(key, value))
+        .collect()
+}
+
+/// Metrics backend that keeps cumulative state in memory and is pushed to an
+/// OTLP collector by `OtlpExporter`.
+///
+/// Generic measurements under any other name are exported as their own
+/// metrics, `solana_block_monitor.<name>`, with their labels as attributes
+/// and the same per-name label set limit as `PrometheusMetrics`.
+pub struct OpenTelemetryMetrics {
+    start_time_nanos: u64,
+    latest_slot: AtomicU64,
+    get_blocks_latency: LatencyHistogram,
+    is_slot_confirmed_latency: LatencyHistogram,
+    process_interval_latency: LatencyHistogram,
+    intervals: IntervalCounters,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    sync_lag: AtomicU64,
+    queue_depth: AtomicU64,
+    interval_age_us: AtomicU64,
+    rpc_errors: RpcErrorCounts,
+    workers: WorkerStatsMap,
+    http_requests: HttpRequestStats,
+    slots_inserted: AtomicU64,
+    labeled: LabeledSeries,
+}
+
+impl OpenTelemetryMetrics {
+    pub fn new() -> Self {
+        Self::with_latency_buckets(DEFAULT_LATENCY_BUCKETS_MS)
+    }
+
+    pub fn with_latency_buckets(bounds_ms: &[u64]) -> Self {
+        Self {
+            start_time_nanos: now_unix_nanos(),
+            latest_slot: AtomicU64::new(0),
+            get_blocks_latency: LatencyHistogram::new(bounds_ms),
+            is_slot_confirmed_latency: LatencyHistogram::new(bounds_ms),
+            process_interval_latency: LatencyHistogram::new(bounds_ms),
+            intervals: IntervalCounters::default(),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+            sync_lag: AtomicU64::new(0),
+            queue_depth: AtomicU64::new(0),
+            interval_age_us: AtomicU64::new(0),
+            rpc_errors: RpcErrorCounts::default(),
+            workers: WorkerStatsMap::default(),
+            http_requests: HttpRequestStats::new(bounds_ms),
+            slots_inserted: AtomicU64::new(0),
+            labeled: LabeledSeries::new(bounds_ms),
+        }
+    }
+
+    /// The `scopeMetrics` entry of an OTLP/JSON `ExportMetricsServiceRequest`.
+    fn scope_metrics(&self) -> Value {
+        let now = now_unix_nanos().to_string();
+        let start = self.start_time_nanos.to_string();
+
+        let labeled_histogram_point = |attributes: Vec<Value>, histogram: &LatencyHistogram| {
+            json!({
+                "attributes": attributes,
+                "startTimeUnixNano": start,
+                "timeUnixNano": now,
+                "count": histogram.count().to_string(),
+                "sum": histogram.sum().as_secs_f64(),
+                "bucketCounts": histogram
+                    .bucket_counts()
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>(),
+                "explicitBounds": histogram
+                    .bounds()
+                    .iter()
+                    .map(Duration::as_secs_f64)
+                    .collect::<Vec<_>>(),
+            })
+        };
+        let histogram_point = |operation: &str, histogram: &LatencyHistogram| {
+            labeled_histogram_point(vec![string_attribute("operation", operation)], histogram)
+        };
+        let counter_point = |result: &str, value: &AtomicU64| {
+            json!({
+                "attributes": [string_attribute("result", result)],
+                "startTimeUnixNano": start,
+                "timeUnixNano": now,
+                "asInt": value.load(Ordering::Relaxed).to_string(),
+            })
+        };
+
+        let outcome_point = |outcome: &str, value: u64| {
+            json!({
+                "attributes": [string_attribute("outcome", outcome)],
+                "startTimeUnixNano": start,
+                "timeUnixNano": now,
+                "asInt": value.to_string(),
+            })
+        };
+        let total_point = |value: u64| {
+            json!({
+                "startTimeUnixNano": start,
+                "timeUnixNano": now,
+                "asInt": value.to_string(),
+            })
+        };
+        let rpc_error_points: Vec<Value> = self
+            .rpc_errors
+            .snapshot()
+            .into_iter()
+            .map(|(method, kind, count)| {
+                json!({
+                    "attributes": [
+                        string_attribute("method", &method),
+                        string_attribute("kind", &kind),
+                    ],
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": now,
+                    "asInt": count.to_string(),
+                })
+            })
+            .collect();
+        let http_request_points: Vec<Value> = self
+            .http_requests
+            .latencies()
+            .iter()
+            .map(|(route, class, histogram)| {
+                labeled_histogram_point(
+                    vec![
+                        string_attribute("route", route),
+                        string_attribute("status_class", class),
+                    ],
+                    histogram,
+                )
+            })
+            .collect();
+        let http_in_flight_points: Vec<Value> = self
+            .http_requests
+            .in_flight()
+            .into_iter()
+            .map(|(route, in_flight)| {
+                json!({
+                    "attributes": [string_attribute("route", &route)],
+                    "timeUnixNano": now,
+                    "asInt": in_flight.to_string(),
+                })
+            })
+            .collect();
+        let workers = self.workers.snapshot();
+        let worker_utilization_points: Vec<Value> = workers
+            .iter()
+            .filter_map(|(worker_id, stats)| {
+                stats.busy_ratio.map(|busy_ratio| {
+                    json!({
+                        "attributes": [string_attribute("worker", &worker_id.to_string())],
+                        "timeUnixNano": now,
+                        "asDouble": busy_ratio,
+                    })
+                })
+            })
+            .collect();
+        let worker_interval_points: Vec<Value> = workers
+            .iter()
+            .flat_map(|(worker_id, stats)| {
+                [("success", stats.succeeded), ("failure", stats.failed)].map(|(outcome, count)| {
+                    json!({
+                        "attributes": [
+                            string_attribute("worker", &worker_id.to_string()),
+                            string_attribute("outcome", outcome),
+                        ],
+                        "startTimeUnixNano": start,
+                        "timeUnixNano": now,
+                        "asInt": count.to_string(),
+                    })
+                })
+            })
+            .collect();
+
+        let mut scope = json!({
+            "scope": { "name": SCOPE_NAME },
+            "metrics": [
+                {
+                    "name": "solana_block_monitor.latest_slot",
+                    "description": "Latest slot reported by the RPC endpoint.",
+                    "unit": "1",
+                    "gauge": {
+                        "dataPoints": [{
+                            "timeUnixNano": now,
+                            "asInt": self.latest_slot.load(Ordering::Relaxed).to_string(),
+                        }]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.sync_lag",
+                    "description": "Slots between the latest slot and contiguous synchronizer coverage.",
+                    "unit": "1",
+                    "gauge": {
+                        "dataPoints": [{
+                            "timeUnixNano": now,
+                            "asInt": self.sync_lag.load(Ordering::Relaxed).to_string(),
+                        }]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.operation.duration",
+                    "description": "Duration of monitored operations.",
+                    "unit": "s",
+                    "histogram": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "dataPoints": [
+                            histogram_point("get_blocks", &self.get_blocks_latency),
+                            histogram_point("is_slot_confirmed", &self.is_slot_confirmed_latency),
+                            histogram_point("process_interval", &self.process_interval_latency),
+                        ]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.cache.requests",
+                    "description": "Cache lookups by result.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [
+                            counter_point("hit", &self.cache_hits),
+                            counter_point("miss", &self.cache_misses),
+                        ]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.interval_queue.depth",
+                    "description": "Intervals waiting in the synchronizer queue.",
+                    "unit": "1",
+                    "gauge": {
+                        "dataPoints": [{
+                            "timeUnixNano": now,
+                            "asInt": self.queue_depth.load(Ordering::Relaxed).to_string(),
+                        }]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.interval_queue.oldest_age",
+                    "description": "Time the oldest queued interval has been waiting.",
+                    "unit": "s",
+                    "gauge": {
+                        "dataPoints": [{
+                            "timeUnixNano": now,
+                            "asDouble": Duration::from_micros(
+                                self.interval_age_us.load(Ordering::Relaxed)
+                            ).as_secs_f64(),
+                        }]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.intervals.processed",
+                    "description": "Synchronizer intervals processed by outcome.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [
+                            outcome_point("success", self.intervals.succeeded.sum()),
+                            outcome_point("failure", self.intervals.failed.sum()),
+                        ]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.intervals.slots",
+                    "description": "Slots covered by successfully processed intervals.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [total_point(self.intervals.slots.sum())]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.intervals.confirmed_slots",
+                    "description": "Confirmed slots found by successfully processed intervals.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [total_point(self.intervals.confirmed_slots.sum())]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.intervals.sub_intervals",
+                    "description": "Gap sub-intervals produced by successfully processed intervals.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [total_point(self.intervals.sub_intervals.sum())]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.rpc.errors",
+                    "description": "Failed RPC calls by method and error kind.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": rpc_error_points,
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.worker.utilization",
+                    "description": "Share of the last report period a synchronizer worker spent processing.",
+                    "unit": "1",
+                    "gauge": {
+                        "dataPoints": worker_utilization_points,
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.worker.intervals.processed",
+                    "description": "Synchronizer intervals processed per worker by outcome.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": worker_interval_points,
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.http.request.duration",
+                    "description": "HTTP request duration by route template and status class.",
+                    "unit": "s",
+                    "histogram": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "dataPoints": http_request_points,
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.sync.slots_inserted",
+                    "description": "Slots newly cached by the synchronizer.",
+                    "unit": "1",
+                    "sum": {
+                        "aggregationTemporality": CUMULATIVE,
+                        "isMonotonic": true,
+                        "dataPoints": [total_point(self.slots_inserted.load(Ordering::Relaxed))]
+                    }
+                },
+                {
+                    "name": "solana_block_monitor.http.requests.in_flight",
+                    "description": "HTTP requests currently being handled by route template.",
+                    "unit": "1",
+                    "gauge": {
+                        "dataPoints": http_in_flight_points,
+                    }
+                }
+            ]
+        });
+
+        let Some(metrics) = scope["metrics"].as_array_mut() else {
+            return scope;
+        };
+        for (name, series) in self.labeled.counters() {
+            metrics.push(json!({
+                "name": format!("{}.{}", SCOPE_NAME, name),
+                "unit": "1",
+                "sum": {
+                    "aggregationTemporality": CUMULATIVE,
+                    "isMonotonic": true,
+                    "dataPoints": series
+                        .iter()
+                        .map(|(labels, count)| json!({
+                            "attributes": label_attributes(labels),
+                            "startTimeUnixNano": start,
+                            "timeUnixNano": now,
+                            "asInt": count.to_string(),
+                        }))
+                        .collect::<Vec<_>>(),
+                }
+            }));
+        }
+        for (name, series) in self.labeled.gauges() {
+            metrics.push(json!({
+                "name": format!("{}.{}", SCOPE_NAME, name),
+                "unit": "1",
+                "gauge": {
+                    "dataPoints": series
+                        .iter()
+                        .map(|(labels, value)| json!({
+                            "attributes": label_attributes(labels),
+                            "timeUnixNano": now,
+                            "asDouble": value,
+                        }))
+                        .collect::<Vec<_>>(),
+                }
+            }));
+        }
+        for (name, series) in self.labeled.durations() {
+            metrics.push(json!({
+                "name": format!("{}.{}.duration", SCOPE_NAME, name),
+                "unit": "s",
+                "histogram": {
+                    "aggregationTemporality": CUMULATIVE,
+                    "dataPoints": series
+                        .iter()
+                        .map(|(labels, histogram)| {
+                            labeled_histogram_point(label_attributes(labels), histogram)
+                        })
+                        .collect::<Vec<_>>(),
+                }
+            }));
+        }
+
+        scope
+    }
+}
+
+impl Default for OpenTelemetryMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for OpenTelemetryMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        match op {
+            names::GET_BLOCKS => self.get_blocks_latency.record(elapsed),
+            names::IS_SLOT_CONFIRMED => self.is_slot_confirmed_latency.record(elapsed),
+            names::PROCESS_INTERVAL => {
+                self.process_interval_latency.record(elapsed);
+                self.intervals.record_pass(&self.workers, labels);
+            }
+            names::HTTP_REQUEST => self.http_requests.record(labels, elapsed),
+            _ => self.labeled.record_duration(op, labels, elapsed),
+        }
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        if let Some(total) = self.intervals.total(counter) {
+            total.add(n);
+            return;
+        }
+        match counter {
+            names::CACHE_LOOKUPS => match label(labels, "result") {
+                Some("hit") => {
+                    self.cache_hits.fetch_add(n, Ordering::Relaxed);
+                }
+                Some("miss") => {
+                    self.cache_misses.fetch_add(n, Ordering::Relaxed);
+                }
+                _ => self.labeled.increment(counter, labels, n),
+            },
+            names::RPC_ERRORS => self.rpc_errors.increment(labels, n),
+            names::SLOTS_INSERTED => {
+                self.slots_inserted.fetch_add(n, Ordering::Relaxed);
+            }
+            _ => self.labeled.increment(counter, labels, n),
+        }
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        match gauge {
+            names::LATEST_SLOT => self.latest_slot.store(value as u64, Ordering::Relaxed),
+            names::SYNC_LAG => self.sync_lag.store(value as u64, Ordering::Relaxed),
+            names::QUEUE_DEPTH => self.queue_depth.store(value as u64, Ordering::Relaxed),
+            names::INTERVAL_AGE_SECONDS => self
+                .interval_age_us
+                .store((value * 1_000_000.0) as u64, Ordering::Relaxed),
+            names::WORKER_UTILIZATION => self.workers.record_utilization(labels, value),
+            names::HTTP_IN_FLIGHT => self
+                .http_requests
+                .set_in_flight(label(labels, "route").unwrap_or_default(), value as usize),
+            _ => self.labeled.set_gauge(gauge, labels, value),
+        }
+    }
+
+    fn latency_percentiles(&self) -> Option<LatencyReport> {
+        Some(LatencyReport {
+            get_blocks: self.get_blocks_latency.percentiles(),
+            is_slot_confirmed: self.is_slot_confirmed_latency.percentiles(),
+        })
+    }
+}
+
+/// Identifiers and attributes of an open span, stored in its extensions.
+struct SpanData {
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    start_time_nanos: u64,
+    attributes: Vec<Value>,
+}
+
+struct AttributeVisitor<'a>(&'a mut Vec<Value>);
+
+impl Visit for AttributeVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.push(string_attribute(field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0
+            .push(json!({ "key": field.name(), "value": { "intValue": value.to_string() } }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0
+            .push(json!({ "key": field.name(), "value": { "intValue": value.to_string() } }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0
+            .push(json!({ "key": field.name(), "value": { "boolValue": value } }));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .push(string_attribute(field.name(), &format!("{:?}", value)));
+    }
+}
+
+/// `tracing` layer that turns this crate's spans into OTLP spans.
+///
+/// Spans from dependencies are ignored. Finished spans are buffered until
+/// the next `OtlpExporter::export`; `rpc` spans are exported as client spans
+/// and `http` spans as server spans.
+#[derive(Clone, Default)]
+pub struct OtlpSpanLayer {
+    finished: Arc<Mutex<Vec<Value>>>,
+    id_counter: Arc<AtomicU64>,
+    dropped_spans: Arc<AtomicU64>,
+}
+
+impl OtlpSpanLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dropped_spans(&self) -> u64 {
+        self.dropped_spans.load(Ordering::Relaxed)
+    }
+
+    fn take_spans(&self) -> Vec<Value> {
+        std::mem::take(&mut *self.finished.lock().unwrap())
+    }
+
+    /// A pseudo-random 64-bit id (splitmix64 over a clock-seeded counter).
+    fn next_id(&self) -> u64 {
+        let seed = now_unix_nanos() ^ (self.id_counter.fetch_add(1, Ordering::Relaxed) << 32);
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl<S> Layer<S> for OtlpSpanLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !attrs
+            .metadata()
+            .target()
+            .starts_with(env!("CARGO_CRATE_NAME"))
+        {
+            return;
+        }
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let parent = span.parent().and_then(|parent| {
+            parent
+                .extensions()
+                .get::<SpanData>()
+                .map(|data| (data.trace_id.clone(), data.span_id.clone()))
+        });
+        let (trace_id, parent_span_id) = match parent {
+            Some((trace_id, parent_span_id)) => (trace_id, Some(parent_span_id)),
+            None => (
+                format!("{:016x}{:016x}", self.next_id(), self.next_id()),
+                None,
+            ),
+        };
+
+        let mut attributes = Vec::new();
+        attrs.record(&mut AttributeVisitor(&mut attributes));
+
+        span.extensions_mut().insert(SpanData {
+            trace_id,
+            span_id: format!("{:016x}", self.next_id()),
+            parent_span_id,
+            start_time_nanos: now_unix_nanos(),
+            attributes,
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        if let Some(data) = span.extensions_mut().get_mut::<SpanData>() {
+            values.record(&mut AttributeVisitor(&mut data.attributes));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(data) = span.extensions_mut().remove::<SpanData>() else {
+            return;
+        };
+
+        let kind = match span.name() {
+            "rpc" => SPAN_KIND_CLIENT,
+            "http" => SPAN_KIND_SERVER,
+            _ => SPAN_KIND_INTERNAL,
+        };
+        let mut otlp_span = json!({
+            "traceId": data.trace_id,
+            "spanId": data.span_id,
+            "name": span.name(),
+            "kind": kind,
+            "startTimeUnixNano": data.start_time_nanos.to_string(),
+            "endTimeUnixNano": now_unix_nanos().to_string(),
+            "attributes": data.attributes,
+        });
+        if let Some(parent_span_id) = data.parent_span_id {
+            otlp_span["parentSpanId"] = json!(parent_span_id);
+        }
+
+        let mut finished = self.finished.lock().unwrap();
+        if finished.len() < MAX_PENDING_SPANS {
+            finished.push(otlp_span);
+        } else {
+            self.dropped_spans.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Pushes metrics and spans to an OTLP/HTTP collector using the JSON
+/// encoding (`POST {endpoint}/v1/metrics` and `/v1/traces`).
+///
+/// Only plain `http://` endpoints are supported; put a local collector or
+/// agent in front of TLS-terminated backends.
+pub struct OtlpExporter {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    service_name: String,
+    metrics: Option<Arc<OpenTelemetryMetrics>>,
+    spans: Option<OtlpSpanLayer>,
+}
+
+impl OtlpExporter {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            headers: Vec::new(),
+            service_name: DEFAULT_SERVICE_NAME.to_string(),
+            metrics: None,
+            spans: None,
+        }
+    }
+
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_service_name(mut self, service_name: &str) -> Self {
+        self.service_name = service_name.to_string();
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<OpenTelemetryMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    pub fn with_spans(mut self, spans: OtlpSpanLayer) -> Self {
+        self.spans = Some(spans);
+        self
+    }
+
+    /// Sends the current metric values and every span finished since the
+    /// previous export.
+    pub async fn export(&self) -> Result<(), BoxError> {
+        let resource = json!({
+            "attributes": [string_attribute("service.name", &self.service_name)]
+        });
+
+        if let Some(metrics) = &self.metrics {
+            let body = json!({
+                "resourceMetrics": [{
+                    "resource": resource,
+                    "scopeMetrics": [metrics.scope_metrics()],
+                }]
+            });
+            self.post("/v1/metrics", &body).await?;
+        }
+
+        if let Some(spans) = &self.spans {
+            let pending = spans.take_spans();
+            if !pending.is_empty() {
+                let body = json!({
+                    "resourceSpans": [{
+                        "resource": resource,
+                        "scopeSpans": [{
+                            "scope": { "name": SCOPE_NAME },
+                            "spans": pending,
+                        }],
+                    }]
+                });
+                self.post("/v1/traces", &body).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Final export on shutdown; failures are logged rather than returned.
+    pub async fn shutdown(&self) {
+        if let Err(e) = self.export().await {
+            warn!(
+                error = %e,
+                endpoint = %redacted_url(&self.endpoint),
+                "Final OTLP export failed"
+            );
+        }
+    }
+
+    async fn post(&self, signal_path: &str, body: &Value) -> Result<(), BoxError> {
+        let rest = self.endpoint.strip_prefix("http://").ok_or_else(|| {
+            format!(
+                "unsupported OTLP endpoint: {}",
+                redacted_url(&self.endpoint)
+            )
+        })?;
+        let (authority, base_path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let address = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+
+        let body = body.to_string();
+        let mut request = format!(
+            "POST {}{} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            base_path,
+            signal_path,
+            authority,
+            body.len()
+        );
+        for (name, value) in &self.headers {
+            request.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        request.push_str("\r\n");
+        request.push_str(&body);
+
+        let response = tokio::time::timeout(EXPORT_TIMEOUT, async {
+            let mut stream = TcpStream::connect(&address).await?;
+            stream.write_all(request.as_bytes()).await?;
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await?;
+            Ok::<_, std::io::Error>(response)
+        })
+        .await??;
+
+        let status: u16 = response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .unwrap_or_default();
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(format!(
+                "OTLP export to {} failed with status {}",
+                signal_path, status
+            )
+            .into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::State, http::HeaderMap, routing::post};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    type Received = Arc<Mutex<Vec<(String, Option<String>, Value)>>>;
+
+    async fn spawn_collector() -> (String, Received) {
+        async fn collect(
+            State((path, received)): State<(&'static str, Received)>,
+            headers: HeaderMap,
+            body: String,
+        ) {
+            let api_key = headers
+                .get("x-api-key")
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+            let body = serde_json::from_str(&body).unwrap();
+            received
+                .lock()
+                .unwrap()
+                .push((path.to_string(), api_key, body));
+        }
+
+        let received = Received::default();
+        let app = Router::new()
+            .route(
+                "/v1/metrics",
+                post(collect).with_state(("/v1/metrics", received.clone())),
+            )
+            .route(
+                "/v1/traces",
+                post(collect).with_state(("/v1/traces", received.clone())),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{}", addr), received)
+    }
+
+    #[tokio::test]
+    async fn test_exports_metrics_and_spans() {
+        let (endpoint, received) = spawn_collector().await;
+        let metrics = Arc::new(OpenTelemetryMetrics::with_latency_buckets(&[10, 100]));
+        let spans = OtlpSpanLayer::new();
+
+        metrics.record_latest_slot(12345);
+        metrics.record_get_blocks_elapsed(Duration::from_millis(30));
+        metrics.record_cache_lookups(2, 1);
+        metrics.record_sync_lag(7);
+        metrics.increment("backfill_jobs", &[("queue", "a")], 2);
+
+        let subscriber = tracing_subscriber::registry().with(spans.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let _http = tracing::info_span!("http", http.route = "/stats").entered();
+            let _rpc = tracing::info_span!("rpc", rpc.method = "getSlot").entered();
+        });
+
+        let exporter = OtlpExporter::new(&endpoint)
+            .with_headers(vec![("x-api-key".to_string(), "secret".to_string())])
+            .with_service_name("monitor-test")
+            .with_metrics(metrics)
+            .with_spans(spans);
+        exporter.export().await.unwrap();
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+
+        let (path, api_key, body) = &received[0];
+        assert_eq!(path, "/v1/metrics");
+        assert_eq!(api_key.as_deref(), Some("secret"));
+        let resource_metrics = &body["resourceMetrics"][0];
+        assert_eq!(
+            resource_metrics["resource"]["attributes"][0]["value"]["stringValue"],
+            "monitor-test"
+        );
+        let exported = &resource_metrics["scopeMetrics"][0]["metrics"];
+        assert_eq!(exported[0]["gauge"]["dataPoints"][0]["asInt"], "12345");
+        assert_eq!(exported[1]["gauge"]["dataPoints"][0]["asInt"], "7");
+        let get_blocks = &exported[2]["histogram"]["dataPoints"][0];
+        assert_eq!(get_blocks["count"], "1");
+        assert_eq!(get_blocks["bucketCounts"], json!(["0", "1", "0"]));
+        assert_eq!(exported[3]["sum"]["dataPoints"][0]["asInt"], "2");
+        let backfill = exported.as_array().unwrap().last().unwrap();
+        assert_eq!(backfill["name"], "solana_block_monitor.backfill_jobs");
+        let point = &backfill["sum"]["dataPoints"][0];
+        assert_eq!(point["attributes"][0]["value"]["stringValue"], "a");
+        assert_eq!(point["asInt"], "2");
+
+        let (path, _, body) = &received[1];
+        assert_eq!(path, "/v1/traces");
+        let exported = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        let rpc = &exported[0];
+        let http = &exported[1];
+        assert_eq!(rpc["name"], "rpc");
+        assert_eq!(rpc["kind"], SPAN_KIND_CLIENT);
+        assert_eq!(rpc["attributes"][0]["value"]["stringValue"], "getSlot");
+        assert_eq!(http["kind"], SPAN_KIND_SERVER);
+        assert_eq!(rpc["traceId"], http["traceId"]);
+        assert_eq!(rpc["parentSpanId"], http["spanId"]);
+    }
+
+    #[tokio::test]
+    async fn test_export_reports_collector_errors() {
+        let (endpoint, _) = spawn_collector().await;
+        let exporter = OtlpExporter::new(&format!("{}/missing", endpoint))
+            .with_metrics(Arc::new(OpenTelemetryMetrics::new()));
+
+        assert!(exporter.export().await.is_err());
+        assert!(
+            OtlpExporter::new("https://collector.example.com")
+                .with_metrics(Arc::new(OpenTelemetryMetrics::new()))
+                .export()
+                .await
+                .is_err()
+        );
+    }
+}
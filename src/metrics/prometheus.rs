@@ -0,0 +1,772 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{
+    CacheHitRatios, CacheHitWindow, DEFAULT_LATENCY_BUCKETS_MS, HttpRequestStats, IntervalCounters,
+    LabelSet, LabeledSeries, LatencyHistogram, LatencyReport, Metrics, RpcErrorCounts,
+    ShardedCounter, SlotThroughput, SlotThroughputWindow, WorkerStatsMap, backfill_fraction, label,
+    names,
+};
+
+const PREFIX: &str = "solana_block_monitor";
+
+/// Metrics backend that keeps real counters, gauges and histograms in memory
+/// and renders them in the Prometheus text exposition format on scrape.
+///
+/// All values except the RPC error, per-worker and HTTP request series are
+/// plain atomics, so recording the synchronizer metrics never takes a lock.
+/// The counters bumped on every cache lookup and interval are
+/// `ShardedCounter`s, so the workers and request handlers do not contend on
+/// them; their shards are summed on scrape.
+///
+/// Generic measurements under any other name are rendered as their own
+/// families with the labels they were recorded with: counters as
+/// `<name>_total`, gauges as `<name>` and durations as a
+/// `<name>_duration_seconds` histogram. Each such name keeps at most
+/// `DEFAULT_MAX_SERIES_PER_METRIC` label sets unless changed with
+/// `with_max_series_per_metric`; measurements for further label sets are
+/// dropped and counted in `metrics_dropped_series_total`.
+pub struct PrometheusMetrics {
+    latest_slot: AtomicU64,
+    get_blocks_latency: LatencyHistogram,
+    is_slot_confirmed_latency: LatencyHistogram,
+    process_interval_latency: LatencyHistogram,
+    intervals: IntervalCounters,
+    cache_hits: ShardedCounter,
+    cache_misses: ShardedCounter,
+    cache_hit_window: CacheHitWindow,
+    slot_throughput_window: SlotThroughputWindow,
+    sync_lag: AtomicU64,
+    queue_depth: AtomicU64,
+    interval_age_us: AtomicU64,
+    backfill_completed: AtomicU64,
+    backfill_total: AtomicU64,
+    rpc_errors: RpcErrorCounts,
+    workers: WorkerStatsMap,
+    http_requests: HttpRequestStats,
+    /// Unix timestamp (seconds) of the last success per activity.
+    last_success: Mutex<BTreeMap<String, f64>>,
+    labeled: LabeledSeries,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::with_latency_buckets(DEFAULT_LATENCY_BUCKETS_MS)
+    }
+
+    pub fn with_latency_buckets(bounds_ms: &[u64]) -> Self {
+        Self {
+            latest_slot: AtomicU64::new(0),
+            get_blocks_latency: LatencyHistogram::new(bounds_ms),
+            is_slot_confirmed_latency: LatencyHistogram::new(bounds_ms),
+            process_interval_latency: LatencyHistogram::new(bounds_ms),
+            intervals: IntervalCounters::default(),
+            cache_hits: ShardedCounter::new(),
+            cache_misses: ShardedCounter::new(),
+            cache_hit_window: CacheHitWindow::new(),
+            slot_throughput_window: SlotThroughputWindow::new(),
+            sync_lag: AtomicU64::new(0),
+            queue_depth: AtomicU64::new(0),
+            interval_age_us: AtomicU64::new(0),
+            backfill_completed: AtomicU64::new(0),
+            backfill_total: AtomicU64::new(0),
+            rpc_errors: RpcErrorCounts::default(),
+            workers: WorkerStatsMap::default(),
+            http_requests: HttpRequestStats::new(bounds_ms),
+            last_success: Mutex::default(),
+            labeled: LabeledSeries::new(bounds_ms),
+        }
+    }
+
+    /// Label sets each generically recorded metric name may grow to.
+    pub fn with_max_series_per_metric(mut self, max_series: usize) -> Self {
+        self.labeled = self.labeled.with_max_series(max_series);
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        Self::write_header(
+            &mut out,
+            "latest_slot",
+            "gauge",
+            "Latest slot reported by the RPC endpoint.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_latest_slot {}",
+            PREFIX,
+            self.latest_slot.load(Ordering::Relaxed)
+        );
+
+        Self::write_header(
+            &mut out,
+            "sync_lag_slots",
+            "gauge",
+            "Slots between the latest slot and contiguous synchronizer coverage.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_sync_lag_slots {}",
+            PREFIX,
+            self.sync_lag.load(Ordering::Relaxed)
+        );
+
+        Self::write_header(
+            &mut out,
+            "interval_queue_depth",
+            "gauge",
+            "Intervals waiting in the synchronizer queue.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_interval_queue_depth {}",
+            PREFIX,
+            self.queue_depth.load(Ordering::Relaxed)
+        );
+
+        Self::write_header(
+            &mut out,
+            "oldest_interval_age_seconds",
+            "gauge",
+            "Time the oldest queued interval has been waiting.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_oldest_interval_age_seconds {}",
+            PREFIX,
+            Duration::from_micros(self.interval_age_us.load(Ordering::Relaxed)).as_secs_f64()
+        );
+
+        let backfill_completed = self.backfill_completed.load(Ordering::Relaxed);
+        let backfill_total = self.backfill_total.load(Ordering::Relaxed);
+        Self::write_header(
+            &mut out,
+            "backfill_completed_slots",
+            "gauge",
+            "Slots of the monitoring window backfilled so far.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_backfill_completed_slots {}",
+            PREFIX, backfill_completed
+        );
+        Self::write_header(
+            &mut out,
+            "backfill_total_slots",
+            "gauge",
+            "Slots of the monitoring window to backfill.",
+        );
+        let _ = writeln!(out, "{}_backfill_total_slots {}", PREFIX, backfill_total);
+        Self::write_header(
+            &mut out,
+            "backfill_progress_ratio",
+            "gauge",
+            "Share of the monitoring window backfilled so far.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_backfill_progress_ratio {}",
+            PREFIX,
+            backfill_fraction(backfill_completed, backfill_total)
+        );
+
+        Self::write_header(
+            &mut out,
+            "seconds_since_last_success",
+            "gauge",
+            "Time since getSlot, getBlocks or a cache insert last succeeded.",
+        );
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        for (activity, timestamp) in self
+            .last_success
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+        {
+            let _ = writeln!(
+                out,
+                "{}_seconds_since_last_success{{activity=\"{}\"}} {}",
+                PREFIX,
+                activity,
+                (now - timestamp).max(0.0)
+            );
+        }
+
+        Self::write_header(
+            &mut out,
+            "operation_duration_seconds",
+            "histogram",
+            "Duration of monitored operations.",
+        );
+        Self::write_histogram(&mut out, "get_blocks", &self.get_blocks_latency);
+        Self::write_histogram(
+            &mut out,
+            "is_slot_confirmed",
+            &self.is_slot_confirmed_latency,
+        );
+        Self::write_histogram(&mut out, "process_interval", &self.process_interval_latency);
+
+        let http_latencies = self.http_requests.latencies();
+        Self::write_header(
+            &mut out,
+            "http_requests_total",
+            "counter",
+            "HTTP requests by route template and status class.",
+        );
+        for (route, class, histogram) in &http_latencies {
+            let _ = writeln!(
+                out,
+                "{}_http_requests_total{{route=\"{}\",status_class=\"{}\"}} {}",
+                PREFIX,
+                route,
+                class,
+                histogram.count()
+            );
+        }
+
+        Self::write_header(
+            &mut out,
+            "http_request_duration_seconds",
+            "histogram",
+            "HTTP request duration by route template and status class.",
+        );
+        for (route, class, histogram) in &http_latencies {
+            Self::write_labeled_histogram(
+                &mut out,
+                "http_request_duration_seconds",
+                &format!("route=\"{}\",status_class=\"{}\"", route, class),
+                histogram,
+            );
+        }
+
+        Self::write_header(
+            &mut out,
+            "http_requests_in_flight",
+            "gauge",
+            "HTTP requests currently being handled by route template.",
+        );
+        for (route, in_flight) in self.http_requests.in_flight() {
+            let _ = writeln!(
+                out,
+                "{}_http_requests_in_flight{{route=\"{}\"}} {}",
+                PREFIX, route, in_flight
+            );
+        }
+
+        Self::write_header(
+            &mut out,
+            "intervals_processed_total",
+            "counter",
+            "Synchronizer intervals processed by outcome.",
+        );
+        for (outcome, count) in [
+            ("success", &self.intervals.succeeded),
+            ("failure", &self.intervals.failed),
+        ] {
+            let _ = writeln!(
+                out,
+                "{}_intervals_processed_total{{outcome=\"{}\"}} {}",
+                PREFIX,
+                outcome,
+                count.sum()
+            );
+        }
+        for (name, help, count) in [
+            (
+                "interval_slots_total",
+                "Slots covered by successfully processed intervals.",
+                &self.intervals.slots,
+            ),
+            (
+                "interval_confirmed_slots_total",
+                "Confirmed slots found by successfully processed intervals.",
+                &self.intervals.confirmed_slots,
+            ),
+            (
+                "interval_sub_intervals_total",
+                "Gap sub-intervals produced by successfully processed intervals.",
+                &self.intervals.sub_intervals,
+            ),
+        ] {
+            Self::write_header(&mut out, name, "counter", help);
+            let _ = writeln!(out, "{}_{} {}", PREFIX, name, count.sum());
+        }
+
+        Self::write_header(
+            &mut out,
+            "sync_slots_per_second",
+            "gauge",
+            "Slots newly cached by the synchronizer per second over a trailing window.",
+        );
+        for (window, rate) in self.slot_throughput_window.rates().windows() {
+            let _ = writeln!(
+                out,
+                "{}_sync_slots_per_second{{window=\"{}\"}} {}",
+                PREFIX, window, rate
+            );
+        }
+
+        let workers = self.workers.snapshot();
+        Self::write_header(
+            &mut out,
+            "worker_utilization_ratio",
+            "gauge",
+            "Share of the last report period a synchronizer worker spent processing.",
+        );
+        for (worker_id, stats) in &workers {
+            if let Some(busy_ratio) = stats.busy_ratio {
+                let _ = writeln!(
+                    out,
+                    "{}_worker_utilization_ratio{{worker=\"{}\"}} {}",
+                    PREFIX, worker_id, busy_ratio
+                );
+            }
+        }
+
+        Self::write_header(
+            &mut out,
+            "worker_intervals_processed_total",
+            "counter",
+            "Synchronizer intervals processed per worker by outcome.",
+        );
+        for (worker_id, stats) in &workers {
+            for (outcome, count) in [("success", stats.succeeded), ("failure", stats.failed)] {
+                let _ = writeln!(
+                    out,
+                    "{}_worker_intervals_processed_total{{worker=\"{}\",outcome=\"{}\"}} {}",
+                    PREFIX, worker_id, outcome, count
+                );
+            }
+        }
+
+        Self::write_header(
+            &mut out,
+            "cache_requests_total",
+            "counter",
+            "Cache lookups by result.",
+        );
+        let _ = writeln!(
+            out,
+            "{}_cache_requests_total{{result=\"hit\"}} {}",
+            PREFIX,
+            self.cache_hits.sum()
+        );
+        let _ = writeln!(
+            out,
+            "{}_cache_requests_total{{result=\"miss\"}} {}",
+            PREFIX,
+            self.cache_misses.sum()
+        );
+
+        Self::write_header(
+            &mut out,
+            "cache_hit_ratio",
+            "gauge",
+            "Cache hit ratio over a trailing window; absent while there were no lookups.",
+        );
+        for (window, ratio) in self.cache_hit_window.ratios().windows() {
+            if let Some(ratio) = ratio {
+                let _ = writeln!(
+                    out,
+                    "{}_cache_hit_ratio{{window=\"{}\"}} {}",
+                    PREFIX, window, ratio
+                );
+            }
+        }
+
+        Self::write_header(
+            &mut out,
+            "rpc_errors_total",
+            "counter",
+            "Failed RPC calls by method and error kind.",
+        );
+        for (method, kind, count) in self.rpc_errors.snapshot() {
+            let _ = writeln!(
+                out,
+                "{}_rpc_errors_total{{method=\"{}\",kind=\"{}\"}} {}",
+                PREFIX, method, kind, count
+            );
+        }
+
+        self.write_labeled_series(&mut out);
+
+        out
+    }
+
+    fn write_labeled_series(&self, out: &mut String) {
+        for (name, series) in self.labeled.counters() {
+            let family = format!("{}_total", name);
+            Self::write_header(out, &family, "counter", "Counter recorded by name.");
+            for (labels, count) in series {
+                Self::write_sample(out, &family, &labels, count);
+            }
+        }
+
+        for (name, series) in self.labeled.gauges() {
+            Self::write_header(out, name, "gauge", "Gauge recorded by name.");
+            for (labels, value) in series {
+                Self::write_sample(out, name, &labels, value);
+            }
+        }
+
+        for (name, series) in self.labeled.durations() {
+            let family = format!("{}_duration_seconds", name);
+            Self::write_header(out, &family, "histogram", "Duration recorded by name.");
+            for (labels, histogram) in series {
+                Self::write_labeled_histogram(
+                    out,
+                    &family,
+                    &Self::format_labels(&labels),
+                    &histogram,
+                );
+            }
+        }
+
+        let dropped = self.labeled.dropped();
+        if !dropped.is_empty() {
+            Self::write_header(
+                out,
+                "metrics_dropped_series_total",
+                "counter",
+                "Measurements dropped because their metric reached its label set limit.",
+            );
+            for (name, count) in dropped {
+                let _ = writeln!(
+                    out,
+                    "{}_metrics_dropped_series_total{{metric=\"{}\"}} {}",
+                    PREFIX, name, count
+                );
+            }
+        }
+    }
+
+    /// Labels as `key="value"` pairs without the braces, with values escaped.
+    fn format_labels(labels: &LabelSet) -> String {
+        labels
+            .iter()
+            .map(|(key, value)| {
+                let value = value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n");
+                format!("{}=\"{}\"", key, value)
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn write_sample(
+        out: &mut String,
+        name: &str,
+        labels: &LabelSet,
+        value: impl std::fmt::Display,
+    ) {
+        if labels.is_empty() {
+            let _ = writeln!(out, "{}_{} {}", PREFIX, name, value);
+        } else {
+            let _ = writeln!(
+                out,
+                "{}_{}{{{}}} {}",
+                PREFIX,
+                name,
+                Self::format_labels(labels),
+                value
+            );
+        }
+    }
+
+    fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {}_{} {}", PREFIX, name, help);
+        let _ = writeln!(out, "# TYPE {}_{} {}", PREFIX, name, kind);
+    }
+
+    fn write_histogram(out: &mut String, operation: &str, histogram: &LatencyHistogram) {
+        Self::write_labeled_histogram(
+            out,
+            "operation_duration_seconds",
+            &format!("operation=\"{}\"", operation),
+            histogram,
+        );
+    }
+
+    fn write_labeled_histogram(
+        out: &mut String,
+        name: &str,
+        labels: &str,
+        histogram: &LatencyHistogram,
+    ) {
+        let name = format!("{}_{}", PREFIX, name);
+        let counts = histogram.bucket_counts();
+        let (bucket_labels, labels) = if labels.is_empty() {
+            (String::new(), String::new())
+        } else {
+            (format!("{},", labels), format!("{{{}}}", labels))
+        };
+
+        let mut cumulative = 0;
+        for (bound, count) in histogram.bounds().iter().zip(&counts) {
+            cumulative += count;
+            let _ = writeln!(
+                out,
+                "{}_bucket{{{}le=\"{}\"}} {}",
+                name,
+                bucket_labels,
+                bound.as_secs_f64(),
+                cumulative
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}le=\"+Inf\"}} {}",
+            name,
+            bucket_labels,
+            counts.iter().sum::<u64>()
+        );
+        let _ = writeln!(
+            out,
+            "{}_sum{} {}",
+            name,
+            labels,
+            histogram.sum().as_secs_f64()
+        );
+        let _ = writeln!(out, "{}_count{} {}", name, labels, histogram.count());
+    }
+}
+
+impl Default for PrometheusMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        match op {
+            names::GET_BLOCKS => self.get_blocks_latency.record(elapsed),
+            names::IS_SLOT_CONFIRMED => self.is_slot_confirmed_latency.record(elapsed),
+            names::PROCESS_INTERVAL => {
+                self.process_interval_latency.record(elapsed);
+                self.intervals.record_pass(&self.workers, labels);
+            }
+            names::HTTP_REQUEST => self.http_requests.record(labels, elapsed),
+            _ => self.labeled.record_duration(op, labels, elapsed),
+        }
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        if let Some(total) = self.intervals.total(counter) {
+            total.add(n);
+            return;
+        }
+        match counter {
+            names::CACHE_LOOKUPS => match label(labels, "result") {
+                Some("hit") => {
+                    self.cache_hits.add(n);
+                    self.cache_hit_window.record(n as usize, 0);
+                }
+                Some("miss") => {
+                    self.cache_misses.add(n);
+                    self.cache_hit_window.record(0, n as usize);
+                }
+                _ => self.labeled.increment(counter, labels, n),
+            },
+            names::RPC_ERRORS => self.rpc_errors.increment(labels, n),
+            names::SLOTS_INSERTED => self.slot_throughput_window.record(n as usize),
+            _ => self.labeled.increment(counter, labels, n),
+        }
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        match gauge {
+            names::LATEST_SLOT => self.latest_slot.store(value as u64, Ordering::Relaxed),
+            names::SYNC_LAG => self.sync_lag.store(value as u64, Ordering::Relaxed),
+            names::QUEUE_DEPTH => self.queue_depth.store(value as u64, Ordering::Relaxed),
+            names::INTERVAL_AGE_SECONDS => self
+                .interval_age_us
+                .store((value * 1_000_000.0) as u64, Ordering::Relaxed),
+            names::WORKER_UTILIZATION => self.workers.record_utilization(labels, value),
+            names::HTTP_IN_FLIGHT => self
+                .http_requests
+                .set_in_flight(label(labels, "route").unwrap_or_default(), value as usize),
+            names::BACKFILL_COMPLETED_SLOTS => self
+                .backfill_completed
+                .store(value as u64, Ordering::Relaxed),
+            names::BACKFILL_TOTAL_SLOTS => {
+                self.backfill_total.store(value as u64, Ordering::Relaxed)
+            }
+            names::LAST_SUCCESS => {
+                self.last_success
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .insert(
+                        label(labels, "activity").unwrap_or_default().to_string(),
+                        value,
+                    );
+            }
+            _ => self.labeled.set_gauge(gauge, labels, value),
+        }
+    }
+
+    fn latency_percentiles(&self) -> Option<LatencyReport> {
+        Some(LatencyReport {
+            get_blocks: self.get_blocks_latency.percentiles(),
+            is_slot_confirmed: self.is_slot_confirmed_latency.percentiles(),
+        })
+    }
+
+    fn cache_hit_ratios(&self) -> Option<CacheHitRatios> {
+        Some(self.cache_hit_window.ratios())
+    }
+
+    fn slot_throughput(&self) -> Option<SlotThroughput> {
+        Some(self.slot_throughput_window.rates())
+    }
+
+    fn render_prometheus(&self) -> Option<String> {
+        Some(self.render())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::IntervalOutcome;
+
+    #[test]
+    fn test_render_metric_families() {
+        let metrics = PrometheusMetrics::new();
+
+        metrics.record_latest_slot(12345);
+        metrics.record_get_blocks_elapsed(Duration::from_millis(30));
+        metrics.record_get_blocks_elapsed(Duration::from_millis(3000));
+        metrics.record_cache_hit(true);
+        metrics.record_cache_lookups(2, 3);
+        metrics.record_sync_lag(42);
+        metrics.record_interval_processed(
+            0,
+            100,
+            Duration::from_millis(80),
+            60,
+            3,
+            IntervalOutcome::Success,
+        );
+        metrics.record_interval_processed(
+            1,
+            100,
+            Duration::from_millis(5),
+            0,
+            0,
+            IntervalOutcome::Failure,
+        );
+        metrics.record_worker_utilization(0, 0.25);
+        metrics.record_slots_inserted(30);
+        metrics.record_last_success("get_slot", SystemTime::now() - Duration::from_secs(90));
+        metrics.record_backfill_progress(250, 1_000);
+
+        let output = metrics.render();
+        assert!(output.contains("solana_block_monitor_latest_slot 12345"));
+        assert!(output.contains("solana_block_monitor_sync_lag_slots 42"));
+        assert!(
+            output.contains(
+                "solana_block_monitor_seconds_since_last_success{activity=\"get_slot\"} 90"
+            )
+        );
+        assert!(output.contains(
+            "solana_block_monitor_operation_duration_seconds_bucket{operation=\"get_blocks\",le=\"0.05\"} 1"
+        ));
+        assert!(output.contains(
+            "solana_block_monitor_operation_duration_seconds_bucket{operation=\"get_blocks\",le=\"+Inf\"} 2"
+        ));
+        assert!(output.contains(
+            "solana_block_monitor_operation_duration_seconds_count{operation=\"is_slot_confirmed\"} 0"
+        ));
+        assert!(output.contains("solana_block_monitor_cache_requests_total{result=\"hit\"} 3"));
+        assert!(output.contains("solana_block_monitor_cache_requests_total{result=\"miss\"} 3"));
+        assert!(output.contains("solana_block_monitor_cache_hit_ratio{window=\"1m\"} 0.5"));
+        assert!(output.contains(
+            "solana_block_monitor_operation_duration_seconds_count{operation=\"process_interval\"} 2"
+        ));
+        assert!(
+            output
+                .contains("solana_block_monitor_intervals_processed_total{outcome=\"success\"} 1")
+        );
+        assert!(
+            output
+                .contains("solana_block_monitor_intervals_processed_total{outcome=\"failure\"} 1")
+        );
+        assert!(output.contains("solana_block_monitor_interval_slots_total 100"));
+        assert!(output.contains("solana_block_monitor_interval_confirmed_slots_total 60"));
+        assert!(output.contains("solana_block_monitor_interval_sub_intervals_total 3"));
+        assert!(output.contains("solana_block_monitor_cache_hit_ratio{window=\"60m\"} 0.5"));
+        assert!(
+            output.contains("solana_block_monitor_worker_utilization_ratio{worker=\"0\"} 0.25")
+        );
+        assert!(!output.contains("solana_block_monitor_worker_utilization_ratio{worker=\"1\"}"));
+        assert!(output.contains("solana_block_monitor_sync_slots_per_second{window=\"1m\"} "));
+        assert_ne!(metrics.slot_throughput().unwrap().slots_per_sec_5m, 0.0);
+        assert!(output.contains(
+            "solana_block_monitor_worker_intervals_processed_total{worker=\"0\",outcome=\"success\"} 1"
+        ));
+        assert!(output.contains(
+            "solana_block_monitor_worker_intervals_processed_total{worker=\"1\",outcome=\"failure\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_render_generic_labeled_series() {
+        let metrics =
+            PrometheusMetrics::with_latency_buckets(&[10, 100]).with_max_series_per_metric(2);
+
+        metrics.increment("backfill_jobs", &[("queue", "a\"b")], 2);
+        metrics.increment("backfill_jobs", &[("queue", "c")], 1);
+        metrics.increment("backfill_jobs", &[("queue", "d")], 1);
+        metrics.set_gauge("backfill_depth", &[], 3.5);
+        metrics.record_duration(
+            "backfill_pass",
+            &[("queue", "c")],
+            Duration::from_millis(50),
+        );
+        metrics.record_duration(
+            names::HTTP_REQUEST,
+            &[
+                ("route", "/stats"),
+                ("status", "503"),
+                ("status_class", "5xx"),
+            ],
+            Duration::from_millis(5),
+        );
+
+        let output = metrics.render();
+        assert!(output.contains("solana_block_monitor_backfill_jobs_total{queue=\"a\\\"b\"} 2"));
+        assert!(output.contains("solana_block_monitor_backfill_jobs_total{queue=\"c\"} 1"));
+        assert!(!output.contains("queue=\"d\""));
+        assert!(output.contains(
+            "solana_block_monitor_metrics_dropped_series_total{metric=\"backfill_jobs\"} 1"
+        ));
+        assert!(output.contains("solana_block_monitor_backfill_depth 3.5"));
+        assert!(output.contains(
+            "solana_block_monitor_backfill_pass_duration_seconds_bucket{queue=\"c\",le=\"0.1\"} 1"
+        ));
+        assert!(
+            output.contains(
+                "solana_block_monitor_backfill_pass_duration_seconds_count{queue=\"c\"} 1"
+            )
+        );
+        assert!(output.contains(
+            "solana_block_monitor_http_requests_total{route=\"/stats\",status_class=\"5xx\"} 1"
+        ));
+    }
+}
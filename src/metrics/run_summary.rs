@@ -0,0 +1,162 @@
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use super::{IntervalOutcome, Metrics, ShardedCounter, label, names};
+
+/// Totals for the whole run, reported once on shutdown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub uptime_secs: u64,
+    pub rpc_calls: u64,
+    pub rpc_errors: u64,
+    pub cache_hits: u64,
+    pub cache_misses: u64,
+    /// Cache hits over all lookups, `None` if nothing was looked up.
+    pub cache_hit_rate: Option<f64>,
+    /// Slots covered by successfully processed synchronizer intervals.
+    pub slots_processed: u64,
+}
+
+impl RunSummary {
+    pub fn log(&self) {
+        info!(
+            target: "metrics::summary",
+            uptime_secs = self.uptime_secs,
+            rpc_calls = self.rpc_calls,
+            rpc_errors = self.rpc_errors,
+            cache_hits = self.cache_hits,
+            cache_misses = self.cache_misses,
+            cache_hit_rate = self.cache_hit_rate,
+            slots_processed = self.slots_processed,
+            "Run summary"
+        );
+    }
+
+    /// Writes the summary to `path` as pretty-printed JSON, replacing any
+    /// existing file.
+    pub async fn write(&self, path: &Path) -> std::io::Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        tokio::fs::write(path, json).await
+    }
+}
+
+/// Metrics backend that only adds up the totals of a `RunSummary`.
+///
+/// It keeps no per-period state and is never flushed, so it is registered
+/// next to the configured backends for every run. RPC call counts come
+/// from `AppState`, which sees every call, rather than from metrics, which
+/// only see failures and timed calls.
+pub struct RunTotals {
+    started: Instant,
+    cache_hits: ShardedCounter,
+    cache_misses: ShardedCounter,
+    slots_processed: ShardedCounter,
+}
+
+impl RunTotals {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            cache_hits: ShardedCounter::new(),
+            cache_misses: ShardedCounter::new(),
+            slots_processed: ShardedCounter::new(),
+        }
+    }
+
+    /// Totals so far, with `(rpc_calls, rpc_errors)` as counted by
+    /// `AppState::rpc_call_counts`.
+    pub fn summary(&self, (rpc_calls, rpc_errors): (u64, u64)) -> RunSummary {
+        let cache_hits = self.cache_hits.sum();
+        let cache_misses = self.cache_misses.sum();
+        let lookups = cache_hits + cache_misses;
+        RunSummary {
+            uptime_secs: self.started.elapsed().as_secs(),
+            rpc_calls,
+            rpc_errors,
+            cache_hits,
+            cache_misses,
+            cache_hit_rate: (lookups > 0).then(|| cache_hits as f64 / lookups as f64),
+            slots_processed: self.slots_processed.sum(),
+        }
+    }
+}
+
+impl Default for RunTotals {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for RunTotals {
+    fn record_duration(
+        &self,
+        _op: &'static str,
+        _labels: &[(&'static str, &str)],
+        _elapsed: Duration,
+    ) {
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        match counter {
+            names::CACHE_LOOKUPS => match label(labels, "result") {
+                Some("hit") => self.cache_hits.add(n),
+                Some("miss") => self.cache_misses.add(n),
+                _ => {}
+            },
+            names::INTERVAL_SLOTS
+                if label(labels, "outcome") == Some(IntervalOutcome::Success.as_str()) =>
+            {
+                self.slots_processed.add(n)
+            }
+            _ => {}
+        }
+    }
+
+    fn set_gauge(&self, _gauge: &'static str, _labels: &[(&'static str, &str)], _value: f64) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summary_is_written_as_json() {
+        let totals = RunTotals::new();
+        assert_eq!(totals.summary((0, 0)).cache_hit_rate, None);
+
+        totals.record_cache_lookups(3, 1);
+        totals.record_interval_processed(
+            0,
+            100,
+            Duration::from_millis(5),
+            40,
+            1,
+            IntervalOutcome::Success,
+        );
+        totals.record_interval_processed(
+            1,
+            50,
+            Duration::from_millis(5),
+            0,
+            0,
+            IntervalOutcome::Failure,
+        );
+
+        let path = std::env::temp_dir().join(format!("run-summary-{}.json", std::process::id()));
+        totals.summary((12, 2)).write(&path).await.unwrap();
+        let written = tokio::fs::read_to_string(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(json["rpc_calls"], 12);
+        assert_eq!(json["rpc_errors"], 2);
+        assert_eq!(json["cache_hits"], 3);
+        assert_eq!(json["cache_misses"], 1);
+        assert_eq!(json["cache_hit_rate"], 0.75);
+        assert_eq!(json["slots_processed"], 100);
+        assert_eq!(json["uptime_secs"], 0);
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Picks which of a stream of events to log so that about `rate` of them
+/// are logged, without a random draw per event.
+///
+/// Events are counted, and a call is sampled when its events carry the
+/// running count across the next multiple of `1 / rate`. The share of
+/// logged events therefore tracks the rate exactly over time, and a call
+/// reporting many events at once is logged at most once.
+pub(crate) struct LogSampler {
+    rate: f64,
+    seen: AtomicU64,
+}
+
+impl LogSampler {
+    /// `rate` is clamped to `0.0..=1.0`; zero never samples.
+    pub(crate) fn new(rate: f64) -> Self {
+        Self {
+            rate: rate.clamp(0.0, 1.0),
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    /// Counts `events` more events and reports whether to log this call.
+    pub(crate) fn sample(&self, events: u64) -> bool {
+        if self.rate >= 1.0 {
+            return events > 0;
+        }
+        let before = self.seen.fetch_add(events, Ordering::Relaxed);
+        let after = before + events;
+        (after as f64 * self.rate).floor() > (before as f64 * self.rate).floor()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampled_share_tracks_rate() {
+        for rate in [0.01, 0.25, 0.3] {
+            let sampler = LogSampler::new(rate);
+            let sampled = (0..10_000).filter(|_| sampler.sample(1)).count();
+            let expected = 10_000.0 * rate;
+            assert!(
+                (sampled as f64 - expected).abs() <= 1.0,
+                "rate {} sampled {}",
+                rate,
+                sampled
+            );
+        }
+    }
+
+    #[test]
+    fn test_sampling_edge_rates() {
+        let always = LogSampler::new(1.0);
+        assert!((0..100).all(|_| always.sample(1)));
+        assert!(!always.sample(0));
+
+        let never = LogSampler::new(0.0);
+        assert!(!(0..100).any(|_| never.sample(1)));
+
+        // A batch of events is logged once, however many multiples it spans.
+        let batched = LogSampler::new(0.1);
+        assert!(batched.sample(35));
+        assert!(!batched.sample(4));
+        assert!(batched.sample(1));
+        assert_eq!(LogSampler::new(2.0).rate(), 1.0);
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Shards per counter; more than the synchronizer workers plus the runtime
+/// threads serving requests, so threads rarely share one.
+const SHARD_COUNT: usize = 16;
+
+/// A shard padded to 128 bytes, which covers the cache line and the
+/// adjacent line some CPUs prefetch with it, so threads adding to
+/// neighbouring shards do not invalidate each other's line.
+#[derive(Default)]
+#[repr(align(128))]
+struct Shard(AtomicU64);
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Shard the current thread adds to, assigned round-robin on first use.
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARD_COUNT;
+}
+
+/// A monotonic counter split over `SHARD_COUNT` shards.
+///
+/// Every thread adds to its own shard with a relaxed atomic, so workers and
+/// request handlers counting at the same time do not contend on one cache
+/// line. Reading sums the shards; it is only done on scrape or export and
+/// may miss additions racing with it, like any relaxed load.
+pub struct ShardedCounter {
+    shards: Box<[Shard]>,
+}
+
+impl ShardedCounter {
+    pub fn new() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT).map(|_| Shard::default()).collect(),
+        }
+    }
+
+    pub fn add(&self, n: u64) {
+        let index = SHARD.with(|index| *index);
+        self.shards[index].0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn sum(&self) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| shard.0.load(Ordering::Relaxed))
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+impl Default for ShardedCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sum_covers_every_thread() {
+        let counter = Arc::new(ShardedCounter::new());
+        let threads: Vec<_> = (0..SHARD_COUNT + 3)
+            .map(|_| {
+                let counter = Arc::clone(&counter);
+                std::thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        counter.add(2);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        counter.add(1);
+
+        assert_eq!(counter.sum(), (SHARD_COUNT as u64 + 3) * 2_000 + 1);
+        assert_eq!(std::mem::align_of::<Shard>(), 128);
+    }
+}
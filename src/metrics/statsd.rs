@@ -0,0 +1,327 @@
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use tracing::debug;
+
+use super::{Metrics, label, names};
+
+/// Largest payload that fits a single Ethernet frame without fragmentation
+/// (1500 MTU minus IP and UDP headers, with headroom for IP options).
+pub const DEFAULT_MAX_DATAGRAM_BYTES: usize = 1432;
+
+pub const DEFAULT_STATSD_ADDR: &str = "127.0.0.1:8125";
+
+/// Metrics backend that pushes DogStatsD lines over UDP.
+///
+/// Labels become DogStatsD tags. Generic measurements under any other name
+/// are sent as counters and gauges of that name and as
+/// `operation.duration` timings tagged with `operation:<name>`.
+///
+/// Lines are batched newline-separated into datagrams no larger than
+/// `max_datagram_bytes`. A datagram is sent as soon as the next line would
+/// not fit, and whatever is left is sent on `flush`. The socket is
+/// non-blocking, so a full send buffer or an unreachable agent never stalls
+/// the caller; such datagrams are dropped and counted instead.
+pub struct StatsdMetrics {
+    socket: UdpSocket,
+    prefix: String,
+    tags: String,
+    max_datagram_bytes: usize,
+    buffer: Mutex<String>,
+    sent_datagrams: AtomicU64,
+    dropped_datagrams: AtomicU64,
+}
+
+impl StatsdMetrics {
+    pub fn new(addr: &str) -> io::Result<Self> {
+        let target = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("no address for {}", addr),
+            )
+        })?;
+        let local: SocketAddr = match target {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(target)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            prefix: String::new(),
+            tags: String::new(),
+            max_datagram_bytes: DEFAULT_MAX_DATAGRAM_BYTES,
+            buffer: Mutex::new(String::new()),
+            sent_datagrams: AtomicU64::new(0),
+            dropped_datagrams: AtomicU64::new(0),
+        })
+    }
+
+    /// Prepended to every metric name, separated by a dot.
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches('.');
+        self.prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}.", prefix)
+        };
+        self
+    }
+
+    /// Constant tags (`key:value` or bare `key`) attached to every metric.
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        self.tags = if tags.is_empty() {
+            String::new()
+        } else {
+            format!("|#{}", tags.join(","))
+        };
+        self
+    }
+
+    pub fn with_max_datagram_bytes(mut self, max_datagram_bytes: usize) -> Self {
+        self.max_datagram_bytes = max_datagram_bytes;
+        self
+    }
+
+    pub fn sent_datagrams(&self) -> u64 {
+        self.sent_datagrams.load(Ordering::Relaxed)
+    }
+
+    pub fn dropped_datagrams(&self) -> u64 {
+        self.dropped_datagrams.load(Ordering::Relaxed)
+    }
+
+    /// Sends any buffered lines as one datagram.
+    pub fn flush_buffer(&self) {
+        let payload = std::mem::take(&mut *self.lock_buffer());
+        if !payload.is_empty() {
+            self.send(&payload);
+        }
+    }
+
+    fn emit(&self, name: &str, value: &str, kind: &str, extra_tag: Option<&str>) {
+        let tags = match extra_tag {
+            Some(tag) if self.tags.is_empty() => format!("|#{}", tag),
+            Some(tag) => format!("{},{}", self.tags, tag),
+            None => self.tags.clone(),
+        };
+        let line = format!("{}{}:{}|{}{}", self.prefix, name, value, kind, tags);
+
+        let mut buffer = self.lock_buffer();
+        if !buffer.is_empty() && buffer.len() + 1 + line.len() > self.max_datagram_bytes {
+            let payload = std::mem::take(&mut *buffer);
+            self.send(&payload);
+        }
+        if !buffer.is_empty() {
+            buffer.push('\n');
+        }
+        buffer.push_str(&line);
+    }
+
+    fn send(&self, payload: &str) {
+        match self.socket.send(payload.as_bytes()) {
+            Ok(_) => {
+                self.sent_datagrams.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(err) => {
+                self.dropped_datagrams.fetch_add(1, Ordering::Relaxed);
+                debug!(
+                    target: "metrics::statsd",
+                    error = %err,
+                    bytes = payload.len(),
+                    "Dropped StatsD datagram"
+                );
+            }
+        }
+    }
+
+    fn lock_buffer(&self) -> std::sync::MutexGuard<'_, String> {
+        self.buffer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Labels as `key:value` tags, or `None` when there are none.
+    fn label_tags(labels: &[(&'static str, &str)]) -> Option<String> {
+        (!labels.is_empty()).then(|| {
+            labels
+                .iter()
+                .map(|(key, value)| format!("{}:{}", key, value))
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+    }
+}
+
+impl Metrics for StatsdMetrics {
+    fn record_duration(
+        &self,
+        op: &'static str,
+        labels: &[(&'static str, &str)],
+        elapsed: Duration,
+    ) {
+        let elapsed_ms = format!("{:.3}", elapsed.as_secs_f64() * 1000.0);
+        match op {
+            names::HTTP_REQUEST => {
+                let tags = format!(
+                    "route:{},status_class:{}",
+                    label(labels, "route").unwrap_or_default(),
+                    label(labels, "status_class").unwrap_or_default()
+                );
+                self.emit("http.requests", "1", "c", Some(&tags));
+                self.emit("http.request.duration", &elapsed_ms, "ms", Some(&tags));
+            }
+            names::PROCESS_INTERVAL => {
+                self.emit(
+                    "operation.duration",
+                    &elapsed_ms,
+                    "ms",
+                    Some("operation:process_interval"),
+                );
+                self.emit(
+                    "interval.processed",
+                    "1",
+                    "c",
+                    Self::label_tags(labels).as_deref(),
+                );
+            }
+            _ => {
+                let mut tags = format!("operation:{}", op);
+                if let Some(label_tags) = Self::label_tags(labels) {
+                    tags.push(',');
+                    tags.push_str(&label_tags);
+                }
+                self.emit("operation.duration", &elapsed_ms, "ms", Some(&tags));
+            }
+        }
+    }
+
+    fn increment(&self, counter: &'static str, labels: &[(&'static str, &str)], n: u64) {
+        let (name, labels) = match counter {
+            names::CACHE_LOOKUPS => ("cache.requests", labels),
+            names::RPC_ERRORS => ("rpc.errors", labels),
+            names::INTERVAL_SLOTS => ("interval.slots", &[][..]),
+            names::INTERVAL_CONFIRMED_SLOTS => ("interval.confirmed_slots", &[][..]),
+            names::INTERVAL_SUB_INTERVALS => ("interval.sub_intervals", &[][..]),
+            names::SLOTS_INSERTED => ("sync.slots_inserted", labels),
+            other => (other, labels),
+        };
+        self.emit(
+            name,
+            &n.to_string(),
+            "c",
+            Self::label_tags(labels).as_deref(),
+        );
+    }
+
+    fn set_gauge(&self, gauge: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        let (name, value) = match gauge {
+            names::QUEUE_DEPTH => ("interval_queue.depth", value.to_string()),
+            names::INTERVAL_AGE_SECONDS => (
+                "interval_queue.oldest_age_ms",
+                ((value * 1000.0) as u64).to_string(),
+            ),
+            names::WORKER_UTILIZATION => ("worker.utilization", value.to_string()),
+            names::HTTP_IN_FLIGHT => ("http.requests.in_flight", value.to_string()),
+            other => (other, value.to_string()),
+        };
+        self.emit(name, &value, "g", Self::label_tags(labels).as_deref());
+    }
+
+    fn flush(&self) {
+        self.flush_buffer();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receiver() -> (UdpSocket, String) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_secs(1)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap().to_string();
+        (socket, addr)
+    }
+
+    fn recv(socket: &UdpSocket) -> String {
+        let mut buf = [0u8; 65536];
+        let len = socket.recv(&mut buf).unwrap();
+        String::from_utf8(buf[..len].to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_emits_prefixed_tagged_lines() {
+        let (socket, addr) = receiver();
+        let metrics = StatsdMetrics::new(&addr)
+            .unwrap()
+            .with_prefix("sbm")
+            .with_tags(&["env:test".to_string()]);
+
+        metrics.record_latest_slot(12345);
+        metrics.record_get_blocks_elapsed(Duration::from_millis(30));
+        metrics.record_cache_lookups(2, 0);
+        metrics.increment("backfill.jobs", &[("queue", "a")], 3);
+        metrics.record_duration("backfill", &[("queue", "a")], Duration::from_millis(2));
+        metrics.flush();
+
+        let lines: Vec<_> = recv(&socket).lines().map(String::from).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "sbm.latest_slot:12345|g|#env:test",
+                "sbm.operation.duration:30.000|ms|#env:test,operation:get_blocks",
+                "sbm.cache.requests:2|c|#env:test,result:hit",
+                "sbm.backfill.jobs:3|c|#env:test,queue:a",
+                "sbm.operation.duration:2.000|ms|#env:test,operation:backfill,queue:a",
+            ]
+        );
+        assert_eq!(metrics.sent_datagrams(), 1);
+        assert_eq!(metrics.dropped_datagrams(), 0);
+    }
+
+    #[test]
+    fn test_batches_under_datagram_limit() {
+        let (socket, addr) = receiver();
+        let metrics = StatsdMetrics::new(&addr)
+            .unwrap()
+            .with_max_datagram_bytes(64);
+
+        for slot in 0..20 {
+            metrics.record_latest_slot(slot);
+        }
+        metrics.flush();
+
+        let mut received = Vec::new();
+        for _ in 0..metrics.sent_datagrams() {
+            let datagram = recv(&socket);
+            assert!(datagram.len() <= 64);
+            received.extend(datagram.lines().map(String::from));
+        }
+        assert!(metrics.sent_datagrams() > 1);
+        assert_eq!(received.len(), 20);
+        assert_eq!(received[19], "latest_slot:19|g");
+    }
+
+    #[test]
+    fn test_send_failures_are_counted() {
+        let (socket, addr) = receiver();
+        let metrics = StatsdMetrics::new(&addr).unwrap();
+        drop(socket);
+
+        for slot in 0..5 {
+            metrics.record_latest_slot(slot);
+            metrics.flush();
+        }
+
+        assert_eq!(metrics.sent_datagrams() + metrics.dropped_datagrams(), 5);
+    }
+}
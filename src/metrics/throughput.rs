@@ -0,0 +1,161 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+const BUCKET_WIDTH: Duration = Duration::from_secs(1);
+/// One bucket per second for the last five minutes.
+const BUCKET_COUNT: u64 = 300;
+
+/// Newly cached slots per second over the trailing 1 and 5 minutes.
+///
+/// Right after startup a window covers only the time since the window was
+/// created, so early rates are not diluted by time before startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct SlotThroughput {
+    pub slots_per_sec_1m: f64,
+    pub slots_per_sec_5m: f64,
+}
+
+impl SlotThroughput {
+    /// Window label and rate pairs, shortest window first.
+    pub fn windows(&self) -> [(&'static str, f64); 2] {
+        [("1m", self.slots_per_sec_1m), ("5m", self.slots_per_sec_5m)]
+    }
+}
+
+struct SecondBucket {
+    /// Second (since the window was created) plus one; zero means unused.
+    second: AtomicU64,
+    slots: AtomicU64,
+}
+
+/// Ring buffer of per-second inserted slot counts covering five minutes.
+///
+/// Buckets are claimed and reset the same way as in `CacheHitWindow`.
+pub struct SlotThroughputWindow {
+    started: Instant,
+    buckets: Vec<SecondBucket>,
+}
+
+impl SlotThroughputWindow {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            buckets: (0..BUCKET_COUNT)
+                .map(|_| SecondBucket {
+                    second: AtomicU64::new(0),
+                    slots: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn record(&self, slots: usize) {
+        self.record_at(slots, Instant::now());
+    }
+
+    pub fn rates(&self) -> SlotThroughput {
+        self.rates_at(Instant::now())
+    }
+
+    fn second_at(&self, now: Instant) -> u64 {
+        (now.saturating_duration_since(self.started).as_secs() / BUCKET_WIDTH.as_secs()) + 1
+    }
+
+    fn record_at(&self, slots: usize, now: Instant) {
+        let second = self.second_at(now);
+        let bucket = &self.buckets[(second % BUCKET_COUNT) as usize];
+
+        let current = bucket.second.load(Ordering::Acquire);
+        if current < second
+            && bucket
+                .second
+                .compare_exchange(current, second, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            bucket.slots.store(0, Ordering::Relaxed);
+        }
+
+        bucket.slots.fetch_add(slots as u64, Ordering::Relaxed);
+    }
+
+    fn rates_at(&self, now: Instant) -> SlotThroughput {
+        let second = self.second_at(now);
+        let rate = |seconds: u64| {
+            let slots: u64 = self
+                .buckets
+                .iter()
+                .filter(|bucket| {
+                    let bucket_second = bucket.second.load(Ordering::Acquire);
+                    bucket_second != 0
+                        && bucket_second + seconds > second
+                        && bucket_second <= second
+                })
+                .map(|bucket| bucket.slots.load(Ordering::Relaxed))
+                .sum();
+            slots as f64 / seconds.min(second) as f64
+        };
+
+        SlotThroughput {
+            slots_per_sec_1m: rate(60),
+            slots_per_sec_5m: rate(BUCKET_COUNT),
+        }
+    }
+}
+
+impl Default for SlotThroughputWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_rates_per_window() {
+        let window = SlotThroughputWindow::new();
+        let start = window.started;
+
+        // Backfill: 250 slots per second for the first four minutes.
+        for second in 0..240 {
+            window.record_at(250, start + seconds(second));
+        }
+        // Steady state: the chain's ~2.5 blocks per second for a minute.
+        for second in 240..300 {
+            window.record_at(if second % 2 == 0 { 2 } else { 3 }, start + seconds(second));
+        }
+
+        let rates = window.rates_at(start + seconds(299));
+        assert_eq!(rates.slots_per_sec_1m, 2.5);
+        assert_eq!(rates.slots_per_sec_5m, (240.0 * 250.0 + 150.0) / 300.0);
+    }
+
+    #[test]
+    fn test_young_window_and_roll_off() {
+        let window = SlotThroughputWindow::new();
+        let start = window.started;
+        assert_eq!(window.rates(), SlotThroughput::default());
+
+        window.record_at(100, start);
+        window.record_at(100, start + seconds(9));
+        let rates = window.rates_at(start + seconds(9));
+        assert_eq!(rates.slots_per_sec_1m, 20.0);
+        assert_eq!(rates.slots_per_sec_5m, 20.0);
+
+        let rates = window.rates_at(start + seconds(70));
+        assert_eq!(rates.slots_per_sec_1m, 0.0);
+        assert_eq!(rates.slots_per_sec_5m, 200.0 / 71.0);
+
+        // Second 300 reuses second 0's bucket and must not inherit its count.
+        window.record_at(30, start + seconds(300));
+        let rates = window.rates_at(start + seconds(300));
+        assert_eq!(rates.slots_per_sec_1m, 0.5);
+        assert_eq!(rates.slots_per_sec_5m, 130.0 / 300.0);
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{debug, info};
+
+use crate::logic::SyndicaAppLogic;
+
+/// Periodically drops cached slots that fell below the monitoring window.
+///
+/// The cache only evicts once it is full, so slots cached by lookups of old
+/// slots, or left behind when the window moves on or shrinks, otherwise
+/// stay until they are the least recently used. Pruning frees that room
+/// for the window itself.
+pub struct CachePruner {
+    logic: Arc<SyndicaAppLogic>,
+    keep_from: Option<u64>,
+}
+
+impl CachePruner {
+    pub fn new(logic: Arc<SyndicaAppLogic>) -> Self {
+        Self {
+            logic,
+            keep_from: None,
+        }
+    }
+
+    /// Keeps slots from `slot` on even below the window, for the region
+    /// `START_FROM_SLOT` backfills.
+    pub fn with_keep_from(mut self, slot: u64) -> Self {
+        self.keep_from = Some(slot);
+        self
+    }
+
+    /// Removes cached slots below `last_processed_slot - monitoring_depth`
+    /// and returns how many were removed. Nothing is removed before the
+    /// first slot update.
+    pub fn prune(&self) -> usize {
+        let state = self.logic.state();
+        let tip = state.last_processed_slot();
+        if tip == 0 {
+            return 0;
+        }
+        let window_start = tip.saturating_sub(self.logic.monitoring_depth());
+        let floor = self
+            .keep_from
+            .map_or(window_start, |slot| slot.min(window_start));
+
+        let pruned = state.cache().remove_below(floor);
+        if pruned > 0 {
+            info!(
+                pruned,
+                floor, "Pruned slots below the monitoring window from the cache"
+            );
+        } else {
+            debug!(floor, "No cached slots below the monitoring window");
+        }
+        pruned
+    }
+
+    /// Prunes every `period`, starting one period from now. The task holds
+    /// no resources beyond `logic`, so aborting it is safe.
+    pub fn spawn(self, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut prune_timer = tokio::time::interval(period);
+            prune_timer.tick().await;
+            loop {
+                prune_timer.tick().await;
+                self.prune();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FakeClient, test_state};
+
+    #[tokio::test]
+    async fn test_prunes_slots_below_the_window() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 1_000);
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()).with_monitoring_depth(100));
+        for slot in [10, 500, 899, 900, 950, 1_000] {
+            state.cache().insert(slot);
+        }
+
+        // Before the first slot update there is no window to prune to.
+        assert_eq!(CachePruner::new(logic.clone()).prune(), 0);
+
+        state.set_last_processed_slot(1_000);
+        let handle = CachePruner::new(logic.clone()).spawn(Duration::from_millis(10));
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while state.cache().len() > 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        handle.abort();
+        assert_eq!(
+            state.cache().cached_in_range(0, 1_000),
+            vec![900, 950, 1_000]
+        );
+
+        // The START_FROM_SLOT region below the window is kept.
+        for slot in [10, 500, 899] {
+            state.cache().insert(slot);
+        }
+        assert_eq!(CachePruner::new(logic).with_keep_from(500).prune(), 1);
+        assert_eq!(
+            state.cache().cached_in_range(0, 1_000),
+            vec![500, 899, 900, 950, 1_000]
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+use tracing_subscriber::EnvFilter;
+
+use crate::config::{Config, RELOADABLE_SETTINGS};
+use crate::logic::SyndicaAppLogic;
+use crate::types::BoxError;
+
+/// Swaps the log filter of the running subscriber.
+pub type FilterReload = Box<dyn Fn(EnvFilter) -> Result<(), BoxError> + Send + Sync>;
+
+/// What a reload changed.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ReloadOutcome {
+    /// Settings now in effect, from `RELOADABLE_SETTINGS`.
+    pub applied: Vec<&'static str>,
+    /// Settings that changed but keep their old value until a restart.
+    pub requires_restart: Vec<&'static str>,
+}
+
+/// Re-reads the configuration on request and applies the settings listed
+/// in `RELOADABLE_SETTINGS` without a restart, so the cache stays warm.
+///
+/// The configuration is loaded the same way as at startup, from the same
+/// env file and command-line overrides. A reload that fails to load or to
+/// apply leaves the previous configuration in effect.
+pub struct ConfigReloader {
+    current: Config,
+    logic: Arc<SyndicaAppLogic>,
+    env_file: Option<String>,
+    overrides: HashMap<String, String>,
+    filter_reload: Option<FilterReload>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        current: Config,
+        logic: Arc<SyndicaAppLogic>,
+        env_file: Option<String>,
+        overrides: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            current,
+            logic,
+            env_file,
+            overrides,
+            filter_reload: None,
+        }
+    }
+
+    /// Lets `LOG_LEVEL` and `LOG_FILTER` be reloaded through `reload`,
+    /// typically a `tracing_subscriber::reload::Handle`.
+    pub fn with_filter_reload(
+        mut self,
+        reload: impl Fn(EnvFilter) -> Result<(), BoxError> + Send + Sync + 'static,
+    ) -> Self {
+        self.filter_reload = Some(Box::new(reload));
+        self
+    }
+
+    /// The configuration in effect: the one loaded at startup with every
+    /// reloaded setting updated.
+    pub fn current(&self) -> &Config {
+        &self.current
+    }
+
+    /// Loads the configuration again and applies what changed.
+    pub async fn reload(&mut self) -> Result<ReloadOutcome, BoxError> {
+        let loaded =
+            Config::load_with_overrides(self.env_file.as_deref(), self.overrides.clone()).await?;
+        for warning in &loaded.warnings {
+            warn!("{}", warning);
+        }
+        self.apply(loaded)
+    }
+
+    fn apply(&mut self, loaded: Config) -> Result<ReloadOutcome, BoxError> {
+        let (applied, requires_restart): (Vec<_>, Vec<_>) = self
+            .current
+            .changed_settings(&loaded)
+            .into_iter()
+            .partition(|name| RELOADABLE_SETTINGS.contains(name));
+        for setting in &requires_restart {
+            warn!(setting, "Setting changed on reload requires restart");
+        }
+
+        // The only step that can fail goes first, so a failure leaves
+        // every setting as it was.
+        let filter_changed = applied
+            .iter()
+            .any(|name| *name == "log_level" || *name == "log_filter");
+        if let Some(filter_reload) = self.filter_reload.as_ref().filter(|_| filter_changed) {
+            filter_reload(loaded.tracing_filter())?;
+        }
+        if loaded.monitor_interval_ms != self.current.monitor_interval_ms {
+            self.logic
+                .set_monitor_interval_ms(loaded.monitor_interval_ms);
+        }
+        if loaded.slow_thresholds() != self.current.slow_thresholds() {
+            self.logic
+                .state()
+                .metrics()
+                .set_slow_thresholds(loaded.slow_thresholds());
+        }
+
+        self.current.log_level = loaded.log_level;
+        self.current.log_filter = loaded.log_filter;
+        self.current.monitor_interval_ms = loaded.monitor_interval_ms;
+        self.current.slow_operation_threshold_ms = loaded.slow_operation_threshold_ms;
+        self.current.slow_thresholds_ms = loaded.slow_thresholds_ms;
+        Ok(ReloadOutcome {
+            applied,
+            requires_restart,
+        })
+    }
+
+    /// Reloads on every SIGHUP until the process exits.
+    #[cfg(unix)]
+    pub fn spawn_on_sighup(mut self) -> std::io::Result<JoinHandle<()>> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        let mut hangup = signal(SignalKind::hangup())?;
+        Ok(tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                info!("Received SIGHUP, reloading configuration");
+                match self.reload().await {
+                    Ok(outcome) => info!(
+                        applied = ?outcome.applied,
+                        requires_restart = ?outcome.requires_restart,
+                        "Configuration reloaded"
+                    ),
+                    Err(e) => error!(
+                        error = %e,
+                        "Configuration reload failed, keeping the previous configuration"
+                    ),
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LogLevel;
+    use crate::testing::{FakeClient, test_logic};
+    use std::sync::Mutex;
+    use tokio::fs;
+
+    #[tokio::test]
+    async fn test_reload_applies_whitelisted_settings_only() {
+        let path = "test-reload-config.env";
+        let write = |content: &'static str| fs::write(path, content);
+        write(
+            "SOLANA_RPC_URL=https://rpc.example.com\n\
+             MONITOR_INTERVAL_MS=400\n\
+             SERVER_PORT=8002\n",
+        )
+        .await
+        .unwrap();
+
+        let config = Config::load_with_overrides(Some(path), HashMap::new())
+            .await
+            .unwrap();
+        let logic = Arc::new(
+            test_logic(Arc::new(FakeClient::new(1_000)))
+                .with_monitor_interval_ms(config.monitor_interval_ms),
+        );
+        let filters = Arc::new(Mutex::new(Vec::new()));
+        let recorded = filters.clone();
+        let mut reloader = ConfigReloader::new(
+            config,
+            logic.clone(),
+            Some(path.to_string()),
+            HashMap::new(),
+        )
+        .with_filter_reload(move |filter| {
+            recorded.lock().unwrap().push(filter.to_string());
+            Ok(())
+        });
+
+        write(
+            "SOLANA_RPC_URL=https://rpc.example.com\n\
+             MONITOR_INTERVAL_MS=200\n\
+             LOG_LEVEL=debug\n\
+             SLOW_OPERATION_THRESHOLD_MS=50\n\
+             SERVER_PORT=9000\n",
+        )
+        .await
+        .unwrap();
+        let outcome = reloader.reload().await.unwrap();
+        assert_eq!(
+            outcome,
+            ReloadOutcome {
+                applied: vec![
+                    "log_level",
+                    "monitor_interval_ms",
+                    "slow_operation_threshold_ms"
+                ],
+                requires_restart: vec!["server_port"],
+            }
+        );
+        assert_eq!(logic.monitor_interval_ms(), 200);
+        let filters = filters.lock().unwrap().clone();
+        assert_eq!(filters.len(), 1);
+        assert!(filters[0].contains("debug"), "{:?}", filters);
+        assert_eq!(reloader.current().server_port, 8002);
+
+        write(
+            "SOLANA_RPC_URL=https://rpc.example.com\n\
+             MONITOR_INTERVAL_MS=often\n",
+        )
+        .await
+        .unwrap();
+        let failed = reloader.reload().await;
+        fs::remove_file(path).await.unwrap();
+        assert!(failed.is_err());
+        assert_eq!(logic.monitor_interval_ms(), 200);
+        assert_eq!(reloader.current().monitor_interval_ms, 200);
+        assert_eq!(reloader.current().slow_operation_threshold_ms, 50);
+    }
+
+    #[tokio::test]
+    async fn test_failed_filter_reload_keeps_previous_settings() {
+        let path = "test-reload-filter.env";
+        fs::write(path, "SOLANA_RPC_URL=https://rpc.example.com\n")
+            .await
+            .unwrap();
+        let config = Config::load_with_overrides(Some(path), HashMap::new())
+            .await
+            .unwrap();
+        let logic = Arc::new(test_logic(Arc::new(FakeClient::new(1_000))));
+        let interval_ms = logic.monitor_interval_ms();
+        let mut reloader = ConfigReloader::new(
+            config,
+            logic.clone(),
+            Some(path.to_string()),
+            HashMap::new(),
+        )
+        .with_filter_reload(|_| Err("subscriber is gone".into()));
+
+        fs::write(
+            path,
+            "SOLANA_RPC_URL=https://rpc.example.com\n\
+             LOG_LEVEL=trace\n\
+             MONITOR_INTERVAL_MS=25\n",
+        )
+        .await
+        .unwrap();
+        let result = reloader.reload().await;
+        fs::remove_file(path).await.unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(logic.monitor_interval_ms(), interval_ms);
+        assert_eq!(reloader.current().log_level, LogLevel::Info);
+    }
+}
@@ -1,63 +1,827 @@
 use axum::{
-    Router,
-    extract::{Path, State},
-    http::StatusCode,
-    routing::get,
+    Extension, Json, Router,
+    body::Body,
+    extract::{
+        FromRequestParts, MatchedPath, Path, Query, Request, State, rejection::QueryRejection,
+    },
+    http::{StatusCode, header, request::Parts},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
 };
+use futures_util::{StreamExt, future, stream};
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{debug, error, info};
+use tracing::{Instrument, debug, error, info, info_span, warn};
 
-use crate::logic::SyndicaAppLogic;
+use crate::config::{MAX_MONITORING_DEPTH, RedactedConfig};
+use crate::failover::EndpointStats;
+use crate::logic::{SyncMode, SyndicaAppLogic};
+use crate::metrics::{CacheHitRatios, LatencyReport, SlotThroughput, names, status_class};
+use crate::state::{Activity, BackfillProgress, DeadLetteredInterval};
+use crate::synchronizer::{QueuedIntervalInfo, SlotInterval};
+use crate::syndica_client::RpcCallCounts;
+use crate::types::BoxError;
+
+/// How JSON responses are written. Compact unless `?pretty=true` is given
+/// or `JSON_PRETTY` makes pretty-printing the default, which `?pretty=false`
+/// turns back off for a single request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormat {
+    pub pretty: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonFormatQuery {
+    pretty: Option<bool>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for JsonFormat {
+    type Rejection = QueryRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let default = parts
+            .extensions
+            .get::<JsonFormat>()
+            .copied()
+            .unwrap_or_default();
+        let Query(query) = Query::<JsonFormatQuery>::try_from_uri(&parts.uri)?;
+        Ok(Self {
+            pretty: query.pretty.unwrap_or(default.pretty),
+        })
+    }
+}
+
+impl JsonFormat {
+    /// Serializes `value` as the JSON body of a `200` response.
+    pub fn render<T: Serialize>(self, value: &T) -> Response {
+        let body = if self.pretty {
+            serde_json::to_string_pretty(value)
+        } else {
+            serde_json::to_string(value)
+        };
+        match body {
+            Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+            Err(e) => {
+                error!(error = %e, "Failed to serialize response");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatsResponse {
+    pub last_processed_slot: u64,
+    pub monitoring_depth: u64,
+    pub cache_size: usize,
+    pub cache_min_slot: Option<u64>,
+    pub cache_max_slot: Option<u64>,
+    pub latency: Option<LatencyReport>,
+    pub avg_confirmation_latency_ms: Option<u64>,
+    /// Health of each RPC endpoint, left out with a single endpoint.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rpc_endpoints: Vec<EndpointStats>,
+    pub rpc_calls: RpcCallCounts,
+}
+
+/// RFC 7234 warning attached to answers served from the cache after an RPC failure.
+const STALE_WARNING: &str = "110 - \"Response is Stale\"";
+
+/// Route template whose request duration also feeds
+/// `record_is_slot_confirmed_elapsed`.
+const IS_SLOT_CONFIRMED_ROUTE: &str = "/isSlotConfirmed/{slot}";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SlotConfirmedQuery {
+    /// Skip the cache and ask the RPC endpoint, updating the cache with the
+    /// answer.
+    #[serde(default)]
+    pub fresh: bool,
+}
 
 pub async fn is_slot_confirmed(
     Path(slot): Path<u64>,
+    Query(query): Query<SlotConfirmedQuery>,
     State(logic): State<Arc<SyndicaAppLogic>>,
-) -> Result<StatusCode, StatusCode> {
-    let start_time = Instant::now();
-    debug!(slot, "Checking if slot is confirmed");
+) -> Response {
+    debug!(slot, fresh = query.fresh, "Checking if slot is confirmed");
+
+    // Past the tip the endpoint answers "no block" just as for a skipped
+    // slot; tell the two apart without asking it.
+    if logic.is_future_slot(slot) {
+        debug!(slot, "Slot {} is in the future", slot);
+        return (StatusCode::UNPROCESSABLE_ENTITY, "slot is in the future").into_response();
+    }
 
-    let result = match logic.get_block(slot).await {
+    let result = if query.fresh {
+        logic.verify_block(slot).await
+    } else {
+        logic.get_block(slot).await
+    };
+    match result {
         Ok(Some(_)) => {
             debug!(slot, "Slot {} confirmed", slot);
-            Ok(StatusCode::OK)
+            StatusCode::OK.into_response()
         }
         Ok(None) => {
             debug!(slot, "Slot {} not confirmed", slot);
-            Err(StatusCode::NOT_FOUND)
+            StatusCode::NOT_FOUND.into_response()
+        }
+        // A fresh check asks for the authoritative answer, which the cache
+        // cannot give.
+        Err(e) if !query.fresh && logic.can_serve_stale(slot) => {
+            // The cache was already consulted before the RPC call, so the
+            // last-known answer for this slot is "not confirmed".
+            warn!(slot, error = %e, "Serving stale answer for slot {}", slot);
+            (StatusCode::NOT_FOUND, [(header::WARNING, STALE_WARNING)]).into_response()
         }
         Err(e) => {
             error!(slot, error = %e, "Failed to check slot {}", slot);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
         }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CacheStatsResponse {
+    pub len: usize,
+    pub capacity: usize,
+    pub estimated_memory_bytes: usize,
+    pub capacity_memory_bytes: usize,
+    pub max_memory_bytes: Option<usize>,
+    pub hit_ratio: Option<CacheHitRatios>,
+}
+
+pub async fn get_cache_stats(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    format: JsonFormat,
+) -> Response {
+    let cache = logic.state().cache();
+
+    format.render(&CacheStatsResponse {
+        len: cache.len(),
+        capacity: cache.capacity(),
+        estimated_memory_bytes: cache.estimated_memory_bytes(),
+        capacity_memory_bytes: cache.capacity_memory_bytes(),
+        max_memory_bytes: cache.max_memory_bytes(),
+        hit_ratio: logic.state().metrics().cache_hit_ratios(),
+    })
+}
+
+pub async fn get_stats(State(logic): State<Arc<SyndicaAppLogic>>, format: JsonFormat) -> Response {
+    let state = logic.state();
+
+    format.render(&StatsResponse {
+        last_processed_slot: state.last_processed_slot(),
+        monitoring_depth: logic.monitoring_depth(),
+        cache_size: state.cache().len(),
+        cache_min_slot: state.cache().min_slot(),
+        cache_max_slot: state.cache().max_slot(),
+        latency: state.metrics().latency_percentiles(),
+        avg_confirmation_latency_ms: state
+            .confirmation_latency()
+            .map(|latency| latency.as_millis() as u64),
+        rpc_endpoints: state.client().endpoint_stats(),
+        rpc_calls: state.client().call_counts(),
+    })
+}
+
+/// Newest dead letters listed by `/status`; older ones are only counted.
+const STATUS_DEAD_LETTERS_LIMIT: usize = 100;
+
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    pub last_processed_slot: u64,
+    pub sync_lag: u64,
+    pub sync_throughput: Option<SlotThroughput>,
+    pub sync_mode: SyncMode,
+    pub backfill: Option<BackfillProgress>,
+    pub dead_letters: Vec<DeadLetteredInterval>,
+    /// Dead letters kept, including those not listed.
+    pub dead_letters_total: usize,
+    /// Dead letters dropped to stay within `MAX_DEAD_LETTERS`.
+    pub dead_letters_dropped: u64,
+}
+
+pub async fn get_status(State(logic): State<Arc<SyndicaAppLogic>>, format: JsonFormat) -> Response {
+    let state = logic.state();
+    let mut dead_letters = state.dead_letters();
+    let dead_letters_total = dead_letters.len();
+    let dead_letters =
+        dead_letters.split_off(dead_letters_total.saturating_sub(STATUS_DEAD_LETTERS_LIMIT));
+
+    format.render(&StatusResponse {
+        last_processed_slot: state.last_processed_slot(),
+        sync_lag: state.sync_lag(),
+        sync_throughput: state.metrics().slot_throughput(),
+        sync_mode: logic.sync_mode(),
+        backfill: state.backfill_progress(),
+        dead_letters,
+        dead_letters_total,
+        dead_letters_dropped: state.dead_letters_dropped(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub healthy: bool,
+    pub max_staleness_secs: f64,
+    pub seconds_since_last_success: BTreeMap<&'static str, f64>,
+}
+
+/// `200` while `getSlot`, `getBlocks` and cache inserts have all succeeded
+/// within the staleness bound, `503` once any of them has stalled.
+pub async fn get_health(State(logic): State<Arc<SyndicaAppLogic>>, format: JsonFormat) -> Response {
+    let now = Instant::now();
+    let healthy = logic.is_healthy_at(now);
+    let seconds_since_last_success: BTreeMap<_, _> = Activity::ALL
+        .iter()
+        .map(|activity| {
+            let since = logic.state().since_last_success_at(*activity, now);
+            (activity.as_str(), since.as_secs_f64())
+        })
+        .collect();
+
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
     };
+    (
+        status,
+        format.render(&HealthResponse {
+            healthy,
+            max_staleness_secs: logic.max_staleness().as_secs_f64(),
+            seconds_since_last_success,
+        }),
+    )
+        .into_response()
+}
 
-    let elapsed = start_time.elapsed();
-    logic
-        .state()
-        .metrics()
-        .record_is_slot_confirmed_elapsed(elapsed);
-
-    debug!(
-        slot,
-        elapsed_ms = elapsed.as_millis(),
-        "Slot confirmation check completed"
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub healthy: bool,
+    pub sync_lag_slots: u64,
+    pub max_ready_lag_slots: u64,
+}
+
+/// `200` while the monitor is healthy and its sync lag is within
+/// `MAX_READY_LAG`, `503` otherwise, so a load balancer stops routing to
+/// an instance that would answer from far behind the tip.
+pub async fn get_readyz(State(logic): State<Arc<SyndicaAppLogic>>, format: JsonFormat) -> Response {
+    let now = Instant::now();
+    let ready = logic.is_ready_at(now);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        format.render(&ReadyResponse {
+            ready,
+            healthy: logic.is_healthy_at(now),
+            sync_lag_slots: logic.state().sync_lag(),
+            max_ready_lag_slots: logic.max_ready_lag(),
+        }),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlotTimeQuery {
+    #[serde(default)]
+    pub estimate: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotTimeResponse {
+    pub slot: u64,
+    pub block_time: i64,
+    pub estimated: bool,
+}
+
+/// Unix timestamp of `slot`. With `estimate=true` it is extrapolated from
+/// the last known block time without an RPC call, falling back to
+/// `getBlockTime` until a block time has been seen.
+pub async fn get_slot_time(
+    Path(slot): Path<u64>,
+    Query(query): Query<SlotTimeQuery>,
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    format: JsonFormat,
+) -> Response {
+    let estimate = query
+        .estimate
+        .then(|| logic.estimate_slot_time(slot))
+        .flatten();
+    if let Some(block_time) = estimate {
+        return format.render(&SlotTimeResponse {
+            slot,
+            block_time,
+            estimated: true,
+        });
+    }
+
+    match logic.get_slot_time(slot).await {
+        Ok(Some(block_time)) => format.render(&SlotTimeResponse {
+            slot,
+            block_time,
+            estimated: false,
+        }),
+        Ok(None) => StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!(slot, error = %e, "Failed to get block time for slot {}", slot);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignatureConfirmedResponse {
+    pub slot: u64,
+    pub confirmed: bool,
+}
+
+/// Resolves the slot of a transaction signature and checks that slot like
+/// `/isSlotConfirmed` does. `404` when the endpoint does not know the
+/// signature, `400` when it is not a valid base58 signature.
+pub async fn is_signature_confirmed(
+    Path(signature): Path<String>,
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    format: JsonFormat,
+) -> Response {
+    let Ok(signature) = signature.parse::<Signature>() else {
+        return (StatusCode::BAD_REQUEST, "invalid signature").into_response();
+    };
+
+    let slot = match logic.get_signature_slot(signature).await {
+        Ok(Some(slot)) => slot,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(e) => {
+            error!(%signature, error = %e, "Failed to resolve signature {}", signature);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    match logic.get_block(slot).await {
+        Ok(confirmed) => format.render(&SignatureConfirmedResponse {
+            slot,
+            confirmed: confirmed.is_some(),
+        }),
+        Err(e) => {
+            error!(slot, error = %e, "Failed to check slot {}", slot);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Widest slot range `/gaps` will scan in one request.
+const MAX_GAPS_RANGE: u64 = 100_000;
+
+#[derive(Debug, Deserialize)]
+pub struct GapsQuery {
+    pub start: Option<u64>,
+    pub end: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GapsResponse {
+    pub start: u64,
+    pub end: u64,
+    pub missing_slots: u64,
+    pub gaps: Vec<(u64, u64)>,
+}
+
+/// Unconfirmed slot ranges between `start` and `end`, which default to the
+/// monitoring window.
+pub async fn get_gaps(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    Query(query): Query<GapsQuery>,
+    format: JsonFormat,
+) -> Response {
+    let (window_start, window_end) = logic.monitoring_window();
+    let start = query.start.unwrap_or(window_start);
+    let end = query.end.unwrap_or(window_end);
+    if start > end || end - start >= MAX_GAPS_RANGE {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let gaps = logic.find_gaps(start, end);
+    format.render(&GapsResponse {
+        start,
+        end,
+        missing_slots: gaps.iter().map(|(from, to)| to - from + 1).sum(),
+        gaps,
+    })
+}
+
+/// Slots read per step of a `/confirmedSlots` stream: one `getBlocks` call
+/// below the monitoring window, well within the provider's range limit.
+const CONFIRMED_SLOTS_CHUNK: u64 = 10_000;
+
+#[derive(Debug, Deserialize)]
+pub struct RangeQuery {
+    pub start: u64,
+    pub end: u64,
+}
+
+fn end_of_chunk(start: u64, end: u64) -> u64 {
+    start.saturating_add(CONFIRMED_SLOTS_CHUNK - 1).min(end)
+}
+
+/// Confirmed slots between `start` and `end` as a JSON array, streamed a
+/// chunk at a time so memory stays flat however wide the range is.
+pub async fn get_confirmed_slots(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    Query(query): Query<RangeQuery>,
+) -> Response {
+    let RangeQuery { start, end } = query;
+    if start > end || end - start >= logic.max_range_slots() {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    // The first chunk is read before answering, so a failing RPC endpoint
+    // still gets a 500. A later failure can only cut the body short.
+    let first_end = end_of_chunk(start, end);
+    let first = match logic.confirmed_slots_in_range(start, first_end).await {
+        Ok(slots) => slots,
+        Err(e) => {
+            error!(start, end, error = %e, "Failed to read confirmed slots");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let next = (first_end < end).then(|| first_end + 1);
+    let rest = stream::unfold(next, move |next| {
+        let logic = logic.clone();
+        async move {
+            let chunk_start = next?;
+            let chunk_end = end_of_chunk(chunk_start, end);
+            let following = (chunk_end < end).then(|| chunk_end + 1);
+            match logic.confirmed_slots_in_range(chunk_start, chunk_end).await {
+                Ok(slots) => Some((Ok(slots), following)),
+                Err(e) => {
+                    error!(
+                        start = chunk_start,
+                        end = chunk_end,
+                        error = %e,
+                        "Failed to read confirmed slots, ending the response early"
+                    );
+                    Some((Err(e), None))
+                }
+            }
+        }
+    });
+
+    let mut separator = "";
+    let slots = stream::once(future::ready(Ok::<_, BoxError>(first)))
+        .chain(rest)
+        .map(move |chunk| {
+            chunk.map(|slots| {
+                let mut json = String::new();
+                for slot in slots {
+                    json.push_str(separator);
+                    json.push_str(&slot.to_string());
+                    separator = ",";
+                }
+                json
+            })
+        });
+    let body = stream::once(future::ready(Ok("[".to_string())))
+        .chain(slots)
+        .chain(stream::once(future::ready(Ok("]".to_string()))));
+    (
+        [(header::CONTENT_TYPE, "application/json")],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
+/// Slots `/recent` returns when no `n` is given.
+const DEFAULT_RECENT_SLOTS: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct RecentQuery {
+    pub n: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecentSlot {
+    pub slot: u64,
+    pub confirmed: bool,
+}
+
+/// Confirmation of the last `n` slots up to the latest processed slot,
+/// answered from the cache alone so dashboards can poll it cheaply.
+pub async fn get_recent(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    Query(query): Query<RecentQuery>,
+    format: JsonFormat,
+) -> Response {
+    let n = query.n.unwrap_or(DEFAULT_RECENT_SLOTS);
+    let recent: Vec<RecentSlot> = logic
+        .recent_slots(n)
+        .into_iter()
+        .map(|(slot, confirmed)| RecentSlot { slot, confirmed })
+        .collect();
+    format.render(&recent)
+}
+
+/// Upper bound on intervals returned by `/admin/queue`, whatever the
+/// requested `limit`.
+const ADMIN_QUEUE_LIMIT: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueResponse {
+    pub total: usize,
+    pub truncated: bool,
+    pub intervals: Vec<QueuedIntervalInfo>,
+}
+
+pub async fn get_admin_queue(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    Query(query): Query<QueueQuery>,
+    format: JsonFormat,
+) -> Response {
+    let queue = logic.state().interval_queue();
+    let limit = query
+        .limit
+        .unwrap_or(ADMIN_QUEUE_LIMIT)
+        .min(ADMIN_QUEUE_LIMIT);
+
+    let total = queue.len();
+    let intervals = queue.snapshot(limit);
+    format.render(&QueueResponse {
+        total,
+        truncated: intervals.len() < total,
+        intervals,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct RequeueResponse {
+    pub requeued: usize,
+}
+
+/// Moves every dead-lettered interval back onto the synchronizer queue with
+/// a fresh attempt count, e.g. once the endpoint serves the range again.
+pub async fn requeue_dead_letters(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    format: JsonFormat,
+) -> Response {
+    let state = logic.state();
+    let dead_letters = state.take_dead_letters();
+    for interval in &dead_letters {
+        state
+            .interval_queue()
+            .push(SlotInterval::new(interval.start, interval.end));
+    }
+    info!(
+        requeued = dead_letters.len(),
+        "Requeued dead-lettered intervals"
+    );
+
+    format.render(&RequeueResponse {
+        requeued: dead_letters.len(),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DepthRequest {
+    pub depth: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DepthResponse {
+    pub depth: usize,
+    pub cache_capacity: usize,
+}
+
+/// Changes the monitoring depth without a restart. The cache is resized at
+/// once and the synchronizer picks up the new window on its next tick.
+pub async fn set_admin_depth(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    format: JsonFormat,
+    Json(request): Json<DepthRequest>,
+) -> Response {
+    if !(1..=MAX_MONITORING_DEPTH).contains(&request.depth) {
+        let message = format!("depth must be between 1 and {}", MAX_MONITORING_DEPTH);
+        return (StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    let cache_capacity = logic.set_monitoring_depth(request.depth);
+    format.render(&DepthResponse {
+        depth: request.depth,
+        cache_capacity,
+    })
+}
+
+/// The configuration the process loaded, redacted, with defaults and
+/// derived values filled in. Runtime changes such as `/admin/depth` are not
+/// reflected here; `/stats` reports those.
+pub async fn get_debug_config(
+    Extension(config): Extension<Arc<RedactedConfig>>,
+    format: JsonFormat,
+) -> Response {
+    format.render(&*config)
+}
+
+/// Rejects admin requests without a matching `Authorization: Bearer` token.
+/// The read-only admin routes are open when no token is configured.
+async fn require_admin_token(
+    State(admin_token): State<Option<Arc<str>>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(expected) = admin_token.as_deref() {
+        let authorized = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| constant_time_eq(token.as_bytes(), expected.as_bytes()));
+        if !authorized {
+            warn!(path = %request.uri().path(), "Rejected unauthorized admin request");
+            return StatusCode::UNAUTHORIZED.into_response();
+        }
+    }
+    next.run(request).await
+}
+
+/// Compares without short-circuiting so response timing does not reveal
+/// how much of the token matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Content type of the Prometheus text exposition format.
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+pub async fn get_metrics(State(logic): State<Arc<SyndicaAppLogic>>) -> Response {
+    match logic.state().metrics().render_prometheus() {
+        Some(body) => ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], body).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            "Prometheus metrics backend is not enabled",
+        )
+            .into_response(),
+    }
+}
+
+/// The route template a request matched, falling back to the raw path.
+fn matched_route(request: &Request) -> String {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| request.uri().path().to_owned())
+}
+
+/// Wraps every routed request in an `http` span so handlers and the RPC
+/// calls they make can be exported as traces.
+async fn trace_request(request: Request, next: Next) -> Response {
+    let route = matched_route(&request);
+    let span = info_span!(
+        "http",
+        http.request.method = %request.method(),
+        http.route = %route,
+        http.response.status_code = tracing::field::Empty
     );
 
-    result
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
 }
 
-pub fn create_router(logic: Arc<SyndicaAppLogic>) -> Router {
+/// Keeps a request counted as in flight until it is dropped, so requests
+/// abandoned by a disconnecting client are not counted forever.
+struct InFlightRequest {
+    logic: Arc<SyndicaAppLogic>,
+    route: String,
+}
+
+impl InFlightRequest {
+    fn start(logic: Arc<SyndicaAppLogic>, route: String) -> Self {
+        let in_flight = logic.state().update_http_in_flight(&route, 1);
+        logic.state().metrics().set_gauge(
+            names::HTTP_IN_FLIGHT,
+            &[("route", route.as_str())],
+            in_flight as f64,
+        );
+        Self { logic, route }
+    }
+}
+
+impl Drop for InFlightRequest {
+    fn drop(&mut self) {
+        let in_flight = self.logic.state().update_http_in_flight(&self.route, -1);
+        self.logic.state().metrics().set_gauge(
+            names::HTTP_IN_FLIGHT,
+            &[("route", self.route.as_str())],
+            in_flight as f64,
+        );
+    }
+}
+
+/// Records count, duration and in-flight metrics for every routed request,
+/// labelled by route template so slot numbers never become label values.
+async fn record_request_metrics(
+    State(logic): State<Arc<SyndicaAppLogic>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = matched_route(&request);
+    let in_flight = InFlightRequest::start(logic.clone(), route);
+    let start_time = Instant::now();
+
+    let response = next.run(request).await;
+
+    let elapsed = start_time.elapsed();
+    let metrics = logic.state().metrics();
+    let status = response.status();
+    metrics.record_duration(
+        names::HTTP_REQUEST,
+        &[
+            ("route", in_flight.route.as_str()),
+            ("status", status.as_str()),
+            ("status_class", status_class(status.as_u16())),
+        ],
+        elapsed,
+    );
+    if in_flight.route == IS_SLOT_CONFIRMED_ROUTE {
+        metrics.record_duration(names::IS_SLOT_CONFIRMED, &[], elapsed);
+    }
+    response
+}
+
+pub fn create_router(
+    logic: Arc<SyndicaAppLogic>,
+    admin_token: Option<String>,
+    json_pretty: bool,
+    config: RedactedConfig,
+) -> Router {
+    let mut admin = Router::new()
+        .route("/admin/queue", get(get_admin_queue))
+        .route("/debug/config", get(get_debug_config));
+    // Routes that change what the service does are never open: without a
+    // token they are not mounted at all.
+    if admin_token.is_some() {
+        admin = admin
+            .route("/admin/depth", post(set_admin_depth))
+            .route("/admin/dead-letters/requeue", post(requeue_dead_letters));
+    } else {
+        info!("ADMIN_TOKEN is not set, /admin/depth and /admin/dead-letters/requeue are disabled");
+    }
+    let admin = admin.route_layer(middleware::from_fn_with_state(
+        admin_token.map(Arc::from),
+        require_admin_token,
+    ));
+
     Router::new()
-        .route("/isSlotConfirmed/{slot}", get(is_slot_confirmed))
+        .route(IS_SLOT_CONFIRMED_ROUTE, get(is_slot_confirmed))
+        .route("/stats", get(get_stats))
+        .route("/status", get(get_status))
+        .route("/health", get(get_health))
+        .route("/readyz", get(get_readyz))
+        .route("/gaps", get(get_gaps))
+        .route("/recent", get(get_recent))
+        .route("/confirmedSlots", get(get_confirmed_slots))
+        .route(
+            "/isSignatureConfirmed/{signature}",
+            get(is_signature_confirmed),
+        )
+        .route("/slotTime/{slot}", get(get_slot_time))
+        .route("/cache/stats", get(get_cache_stats))
+        .route("/metrics", get(get_metrics))
+        .merge(admin)
+        .route_layer(middleware::from_fn_with_state(
+            logic.clone(),
+            record_request_metrics,
+        ))
+        .route_layer(middleware::from_fn(trace_request))
+        .layer(Extension(JsonFormat {
+            pretty: json_pretty,
+        }))
+        .layer(Extension(Arc::new(config)))
         .with_state(logic)
 }
 
 pub async fn start_server(
     port: u16,
     logic: Arc<SyndicaAppLogic>,
+    admin_token: Option<String>,
+    json_pretty: bool,
+    config: RedactedConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let app = create_router(logic);
+    let app = create_router(logic, admin_token, json_pretty, config);
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
     info!(port, "Server starting");
@@ -66,3 +830,762 @@ pub async fn start_server(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::BlockMetadata;
+    use crate::config::Config;
+    use crate::metrics::PrometheusMetrics;
+    use crate::synchronizer::SlotInterval;
+    use crate::testing::{
+        FakeClient, http_get, http_get_with_headers, http_post_with_headers, test_state,
+        test_state_with_metrics,
+    };
+    use std::time::Duration;
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn failing_logic(serve_stale_on_error: bool) -> Arc<SyndicaAppLogic> {
+        let client = Arc::new(FakeClient::new(1_000));
+        client.set_failing(true);
+        let state = test_state(client, 100);
+        state.set_last_processed_slot(1_000);
+
+        Arc::new(
+            SyndicaAppLogic::new(state)
+                .with_monitoring_depth(100)
+                .with_serve_stale_on_error(serve_stale_on_error),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_rpc_failure_without_stale_mode() {
+        let logic = failing_logic(false);
+
+        let response = is_slot_confirmed(
+            Path(950),
+            Query(SlotConfirmedQuery::default()),
+            State(logic),
+        )
+        .await;
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(response.headers().get(header::WARNING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_failure_with_stale_mode() {
+        let logic = failing_logic(true);
+
+        let check = |slot: u64, fresh: bool| {
+            is_slot_confirmed(
+                Path(slot),
+                Query(SlotConfirmedQuery { fresh }),
+                State(logic.clone()),
+            )
+        };
+
+        let response = check(950, false).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::WARNING], STALE_WARNING);
+
+        let outside_window = check(10, false).await;
+        assert_eq!(outside_window.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        // A fresh check never falls back to the cache.
+        let fresh = check(950, true).await;
+        assert_eq!(fresh.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_fresh_check_bypasses_and_updates_the_cache() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed([901]));
+        let state = test_state(client.clone(), 100);
+        // Cached earlier, but the endpoint no longer confirms it.
+        state.cache().upsert(900, BlockMetadata::default());
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()));
+        let check = |slot: u64, fresh: bool| {
+            is_slot_confirmed(
+                Path(slot),
+                Query(SlotConfirmedQuery { fresh }),
+                State(logic.clone()),
+            )
+        };
+
+        assert_eq!(check(900, false).await.status(), StatusCode::OK);
+        assert_eq!(client.get_blocks_calls(), 0);
+
+        assert_eq!(check(900, true).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(client.get_blocks_calls(), 1);
+        assert!(!state.cache().peek(900));
+        assert_eq!(check(900, false).await.status(), StatusCode::NOT_FOUND);
+
+        assert_eq!(check(901, true).await.status(), StatusCode::OK);
+        assert!(state.cache().peek(901));
+        assert_eq!(check(901, true).await.status(), StatusCode::OK);
+        assert_eq!(client.get_blocks_calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_future_slot_is_unprocessable() {
+        let client = Arc::new(FakeClient::new(1_000));
+        let state = test_state(client.clone(), 100);
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()).with_future_slot_tolerance(10));
+        let check = |slot: u64| {
+            is_slot_confirmed(
+                Path(slot),
+                Query(SlotConfirmedQuery::default()),
+                State(logic.clone()),
+            )
+        };
+
+        // Without a known tip every slot is looked up.
+        assert_eq!(check(5_000).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(client.get_blocks_calls(), 1);
+
+        state.set_last_processed_slot(1_000);
+        assert_eq!(check(1_010).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(client.get_blocks_calls(), 2);
+
+        let response = check(1_011).await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(client.get_blocks_calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_health_flips_when_client_stalls() {
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed(900..910));
+        let state = test_state_with_metrics(client.clone(), 100, metrics.clone());
+        let logic =
+            Arc::new(SyndicaAppLogic::new(state).with_max_staleness(Duration::from_secs(60)));
+
+        // Nothing has succeeded yet, so staleness counts from startup.
+        assert!(!logic.is_healthy_at(Instant::now() + Duration::from_secs(61)));
+
+        logic.get_latest_slot().await.unwrap();
+        assert_eq!(logic.query_slot_range(900, 910).await.unwrap(), 10);
+        let last_success = Instant::now();
+        let response = get_health(State(logic.clone()), JsonFormat::default()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        client.set_failing(true);
+        assert!(logic.get_latest_slot().await.is_err());
+        assert!(logic.query_slot_range(910, 920).await.is_err());
+        assert!(logic.is_healthy_at(last_success + Duration::from_secs(59)));
+        assert!(!logic.is_healthy_at(last_success + Duration::from_secs(61)));
+
+        let stalled = Arc::new(
+            SyndicaAppLogic::new(logic.state().clone()).with_max_staleness(Duration::ZERO),
+        );
+        let response = get_health(State(stalled), JsonFormat::default()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        for activity in ["get_slot", "get_blocks", "cache_insert"] {
+            assert!(metrics.render().contains(&format!(
+                "solana_block_monitor_seconds_since_last_success{{activity=\"{}\"}} ",
+                activity
+            )));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_above_max_ready_lag() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        let logic = Arc::new(
+            SyndicaAppLogic::new(state)
+                .with_max_staleness(Duration::from_secs(60))
+                .with_max_ready_lag(100),
+        );
+
+        logic.state().set_sync_lag(100);
+        let response = get_readyz(State(logic.clone()), JsonFormat::default()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await,
+            serde_json::json!({
+                "ready": true,
+                "healthy": true,
+                "sync_lag_slots": 100,
+                "max_ready_lag_slots": 100,
+            })
+        );
+
+        logic.state().set_sync_lag(101);
+        let response = get_readyz(State(logic.clone()), JsonFormat::default()).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let body = json_body(response).await;
+        assert_eq!(body["ready"], false);
+        assert_eq!(body["healthy"], true);
+        assert_eq!(body["sync_lag_slots"], 101);
+    }
+
+    #[tokio::test]
+    async fn test_status_reports_sync_lag() {
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed(900..910));
+        let state = test_state_with_metrics(client, 100, metrics.clone());
+        state.set_last_processed_slot(1_000);
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()));
+
+        assert_eq!(logic.record_sync_lag(940), 60);
+        assert_eq!(logic.query_slot_range(900, 940).await.unwrap(), 10);
+
+        let status = json_body(get_status(State(logic.clone()), JsonFormat::default()).await).await;
+        assert_eq!(status["last_processed_slot"], 1_000);
+        assert_eq!(status["sync_lag"], 60);
+        assert_eq!(status["sync_mode"], "full");
+        assert!(
+            status["sync_throughput"]["slots_per_sec_5m"]
+                .as_f64()
+                .unwrap()
+                > 0.0
+        );
+        assert!(
+            metrics
+                .render()
+                .contains("solana_block_monitor_sync_lag_slots 60")
+        );
+        assert!(status["backfill"].is_null());
+
+        state.set_backfill_progress(BackfillProgress {
+            from_slot: 500,
+            to_slot: 1_000,
+            completed_slots: 200,
+            total_slots: 501,
+        });
+        let status = json_body(get_status(State(logic), JsonFormat::default()).await).await;
+        assert_eq!(
+            status["backfill"],
+            serde_json::json!({
+                "from_slot": 500,
+                "to_slot": 1_000,
+                "completed_slots": 200,
+                "total_slots": 501,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gaps_endpoint() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        state.set_last_processed_slot(1_000);
+        for slot in [990, 991, 995, 1_000] {
+            state.cache().insert(slot);
+        }
+        let logic = Arc::new(SyndicaAppLogic::new(state).with_monitoring_depth(10));
+
+        let response = get_gaps(
+            State(logic.clone()),
+            Query(GapsQuery {
+                start: None,
+                end: None,
+            }),
+            JsonFormat::default(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["start"], 990);
+        assert_eq!(body["missing_slots"], 7);
+        assert_eq!(body["gaps"], serde_json::json!([[992, 994], [996, 999]]));
+
+        let response = get_gaps(
+            State(logic),
+            Query(GapsQuery {
+                start: Some(20),
+                end: Some(10),
+            }),
+            JsonFormat::default(),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_recent_endpoint() {
+        let client = Arc::new(FakeClient::new(1_000));
+        let state = test_state(client.clone(), 100);
+        state.set_last_processed_slot(1_000);
+        for slot in [995, 997, 998, 1_000] {
+            state.cache().insert(slot);
+        }
+        let logic = Arc::new(SyndicaAppLogic::new(state).with_monitoring_depth(6));
+
+        let recent = |n: Option<usize>| {
+            let logic = logic.clone();
+            async move {
+                let query = Query(RecentQuery { n });
+                json_body(get_recent(State(logic), query, JsonFormat::default()).await).await
+            }
+        };
+
+        assert_eq!(
+            recent(Some(4)).await,
+            serde_json::json!([
+                {"slot": 997, "confirmed": true},
+                {"slot": 998, "confirmed": true},
+                {"slot": 999, "confirmed": false},
+                {"slot": 1000, "confirmed": true},
+            ])
+        );
+        // Capped at the monitoring depth, including the default of 50.
+        let capped = recent(None).await;
+        assert_eq!(capped.as_array().unwrap().len(), 6);
+        assert_eq!(
+            capped[0],
+            serde_json::json!({"slot": 995, "confirmed": true})
+        );
+        assert_eq!(capped[1]["confirmed"], false);
+        assert!(client.requested_ranges().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slot_time_endpoint() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed([900]));
+        let logic = Arc::new(SyndicaAppLogic::new(test_state(client.clone(), 100)));
+
+        let slot_time = |slot: u64, estimate: bool| {
+            get_slot_time(
+                Path(slot),
+                Query(SlotTimeQuery { estimate }),
+                State(logic.clone()),
+                JsonFormat::default(),
+            )
+        };
+
+        // Without a reference point the estimate falls through to RPC.
+        let response = slot_time(900, true).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let seeded = json_body(response).await;
+        assert_eq!(seeded["block_time"], FakeClient::block_time(900));
+        assert_eq!(seeded["estimated"], false);
+
+        let estimated = json_body(slot_time(910, true).await).await;
+        assert_eq!(estimated["block_time"], FakeClient::block_time(910));
+        assert_eq!(estimated["estimated"], true);
+        assert_eq!(client.get_block_time_calls(), 1);
+
+        assert_eq!(slot_time(910, false).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(client.get_block_time_calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_signature_confirmed_endpoint() {
+        let known = Signature::new_unique();
+        let client = Arc::new(
+            FakeClient::new(1_000)
+                .with_confirmed([900])
+                .with_signature(known, 900),
+        );
+        let logic = Arc::new(SyndicaAppLogic::new(test_state(client.clone(), 100)));
+
+        let signature_confirmed = |signature: String| {
+            is_signature_confirmed(Path(signature), State(logic.clone()), JsonFormat::default())
+        };
+
+        let response = signature_confirmed(known.to_string()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await,
+            serde_json::json!({"slot": 900, "confirmed": true})
+        );
+        assert_eq!(client.get_signature_statuses_calls(), 1);
+
+        assert_eq!(
+            signature_confirmed(Signature::new_unique().to_string())
+                .await
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            signature_confirmed("not-a-signature".into()).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(client.get_signature_statuses_calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_slots_stream_a_json_array() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed([5, 500, 950]));
+        let state = test_state(client.clone(), 100);
+        state.set_last_processed_slot(1_000);
+        for slot in [950, 951, 12_000] {
+            state.cache().insert(slot);
+        }
+        let logic = Arc::new(
+            SyndicaAppLogic::new(state)
+                .with_monitoring_depth(100)
+                .with_max_range_slots(30_000),
+        );
+        let confirmed = |start: u64, end: u64| {
+            get_confirmed_slots(State(logic.clone()), Query(RangeQuery { start, end }))
+        };
+
+        // Below the window comes from RPC, the rest from the cache, over
+        // three chunks of which two add nothing.
+        let response = confirmed(0, 25_000).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let slots: Vec<u64> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(slots, vec![5, 500, 950, 951, 12_000]);
+        assert_eq!(client.get_blocks_calls(), 1);
+
+        let empty = json_body(confirmed(20_000, 20_010).await).await;
+        assert_eq!(empty, serde_json::json!([]));
+
+        assert_eq!(confirmed(10, 5).await.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(confirmed(0, 30_000).await.status(), StatusCode::BAD_REQUEST);
+
+        client.fail_next("rpc down".into());
+        assert_eq!(
+            confirmed(0, 100).await.status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_exposes_families() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed([900]));
+        let state =
+            test_state_with_metrics(client.clone(), 100, Arc::new(PrometheusMetrics::new()));
+        let logic = Arc::new(SyndicaAppLogic::new(state));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                create_router(logic, None, false, RedactedConfig::default()),
+            )
+            .await
+            .unwrap();
+        });
+
+        let (status, _) = http_get(addr, "/isSlotConfirmed/900").await;
+        assert_eq!(status, 200);
+        let (status, _) = http_get(addr, "/isSlotConfirmed/900").await;
+        assert_eq!(status, 200);
+        client.fail_next("rpc down".into());
+        let (status, _) = http_get(addr, "/isSlotConfirmed/901").await;
+        assert_eq!(status, 500);
+        let (status, _) = http_get(addr, "/gaps?start=20&end=10").await;
+        assert_eq!(status, 400);
+
+        let (status, body) = http_get(addr, "/metrics").await;
+        assert_eq!(status, 200);
+        assert!(body.contains("# TYPE solana_block_monitor_latest_slot gauge"));
+        assert!(body.contains("# TYPE solana_block_monitor_operation_duration_seconds histogram"));
+        assert!(body.contains(
+            "solana_block_monitor_operation_duration_seconds_count{operation=\"is_slot_confirmed\"} 3"
+        ));
+        assert!(body.contains("solana_block_monitor_cache_requests_total{result=\"hit\"} 1"));
+        assert!(body.contains("solana_block_monitor_cache_requests_total{result=\"miss\"} 2"));
+        assert!(body.contains(
+            "solana_block_monitor_http_requests_total{route=\"/isSlotConfirmed/{slot}\",status_class=\"2xx\"} 2"
+        ));
+        assert!(body.contains(
+            "solana_block_monitor_http_requests_total{route=\"/isSlotConfirmed/{slot}\",status_class=\"5xx\"} 1"
+        ));
+        assert!(body.contains(
+            "solana_block_monitor_http_requests_total{route=\"/gaps\",status_class=\"4xx\"} 1"
+        ));
+        assert!(body.contains(
+            "solana_block_monitor_http_request_duration_seconds_count{route=\"/gaps\",status_class=\"4xx\"} 1"
+        ));
+        assert!(
+            body.contains("solana_block_monitor_http_requests_in_flight{route=\"/metrics\"} 1")
+        );
+        assert!(body.contains("solana_block_monitor_http_requests_in_flight{route=\"/gaps\"} 0"));
+    }
+
+    #[tokio::test]
+    async fn test_admin_queue_requires_token_and_caps_output() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        for start in (0..1_000).step_by(100) {
+            state
+                .interval_queue()
+                .push(SlotInterval::new(start, start + 99));
+        }
+        let logic = Arc::new(SyndicaAppLogic::new(state));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = create_router(
+            logic,
+            Some("s3cret".to_string()),
+            false,
+            RedactedConfig::default(),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        let (status, _) = http_get(addr, "/admin/queue").await;
+        assert_eq!(status, 401);
+        let (status, _) =
+            http_get_with_headers(addr, "/admin/queue", &[("Authorization", "Bearer wrong")]).await;
+        assert_eq!(status, 401);
+
+        let (status, body) = http_get_with_headers(
+            addr,
+            "/admin/queue?limit=3",
+            &[("Authorization", "Bearer s3cret")],
+        )
+        .await;
+        assert_eq!(status, 200);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["total"], 10);
+        assert_eq!(body["truncated"], true);
+        assert_eq!(body["intervals"].as_array().unwrap().len(), 3);
+        assert_eq!(body["intervals"][1]["start"], 100);
+        assert_eq!(body["intervals"][1]["size"], 100);
+        assert_eq!(body["intervals"][1]["attempts"], 0);
+
+        let (status, _) = http_get(addr, "/stats").await;
+        assert_eq!(status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_mutating_admin_routes_need_a_token() {
+        for admin_token in [None, Some("s3cret")] {
+            let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+            let logic = Arc::new(SyndicaAppLogic::new(state).with_monitoring_depth(100));
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let router = create_router(
+                Arc::clone(&logic),
+                admin_token.map(str::to_string),
+                false,
+                RedactedConfig::default(),
+            );
+            tokio::spawn(async move {
+                axum::serve(listener, router).await.unwrap();
+            });
+
+            let authorization = [("Authorization", "Bearer s3cret")];
+            let depth =
+                |headers| http_post_with_headers(addr, "/admin/depth", headers, r#"{"depth": 50}"#);
+            let requeue =
+                |headers| http_post_with_headers(addr, "/admin/dead-letters/requeue", headers, "");
+            match admin_token {
+                None => {
+                    assert_eq!(depth(&authorization).await.0, 404);
+                    assert_eq!(requeue(&authorization).await.0, 404);
+                    assert_eq!(logic.monitoring_depth(), 100);
+                    // Read-only admin routes stay open.
+                    assert_eq!(http_get(addr, "/admin/queue").await.0, 200);
+                }
+                Some(_) => {
+                    assert_eq!(depth(&[]).await.0, 401);
+                    assert_eq!(requeue(&[]).await.0, 401);
+                    assert_eq!(depth(&authorization).await.0, 200);
+                    assert_eq!(requeue(&authorization).await.0, 200);
+                    assert_eq!(logic.monitoring_depth(), 50);
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_config_is_redacted_and_guarded() {
+        let path = "test-debug-config.env";
+        tokio::fs::write(
+            path,
+            "SOLANA_RPC_URL=https://rpc.example.com/access-token/url-secret\n\
+             SOLANA_RPC_KEY=key-secret\n\
+             ADMIN_TOKEN=s3cret\n\
+             MONITOR_WINDOW_SLOTS=500\n",
+        )
+        .await
+        .unwrap();
+        let config = Config::load_from_env_file(path).await;
+        tokio::fs::remove_file(path).await.unwrap();
+        let config = config.unwrap();
+
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        let logic = Arc::new(SyndicaAppLogic::new(state));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = create_router(logic, config.admin_token.clone(), false, config.redacted());
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        let (status, _) = http_get(addr, "/debug/config").await;
+        assert_eq!(status, 401);
+
+        let (status, body) =
+            http_get_with_headers(addr, "/debug/config", &[("Authorization", "Bearer s3cret")])
+                .await;
+        assert_eq!(status, 200);
+        assert!(!body.contains("key-secret"), "{}", body);
+        assert!(!body.contains("url-secret"), "{}", body);
+        assert!(!body.contains("s3cret"), "{}", body);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["settings"]["solana_rpc_key"], "<redacted>");
+        assert_eq!(body["settings"]["monitoring_depth"], "500");
+        assert_eq!(
+            body["settings"]["cache_full_memory_bytes"],
+            (config.cache_capacity * crate::cache::ENTRY_SIZE_BYTES).to_string()
+        );
+        assert_eq!(body["sources"]["SOLANA_RPC_KEY"], "env file");
+    }
+
+    #[tokio::test]
+    async fn test_json_pretty_query_and_default() {
+        let serve = |json_pretty: bool| async move {
+            let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+            let logic = Arc::new(SyndicaAppLogic::new(state));
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let router = create_router(logic, None, json_pretty, RedactedConfig::default());
+            tokio::spawn(async move {
+                axum::serve(listener, router).await.unwrap();
+            });
+            addr
+        };
+
+        let compact = serve(false).await;
+        let (status, body) = http_get(compact, "/stats").await;
+        assert_eq!(status, 200);
+        assert!(!body.contains('\n'), "{}", body);
+        let (_, body) = http_get(compact, "/stats?pretty=true").await;
+        assert!(body.contains("{\n  \"last_processed_slot\""), "{}", body);
+        let (_, body) = http_get(compact, "/gaps?pretty=true&start=1&end=3").await;
+        assert!(body.contains("\n  \"start\": 1,\n"), "{}", body);
+        let (status, _) = http_get(compact, "/stats?pretty=maybe").await;
+        assert_eq!(status, 400);
+
+        let pretty = serve(true).await;
+        let (_, body) = http_get(pretty, "/cache/stats").await;
+        assert!(body.contains('\n'), "{}", body);
+        let (_, body) = http_get(pretty, "/cache/stats?pretty=false").await;
+        assert!(!body.contains('\n'), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_reported_and_requeued() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        state.dead_letter(DeadLetteredInterval {
+            start: 100,
+            end: 199,
+            attempts: 10,
+            error: "block not available".to_string(),
+        });
+        let logic = Arc::new(SyndicaAppLogic::new(state.clone()));
+
+        let status = json_body(get_status(State(logic.clone()), JsonFormat::default()).await).await;
+        assert_eq!(
+            status["dead_letters"],
+            serde_json::json!([
+                {"start": 100, "end": 199, "attempts": 10, "error": "block not available"}
+            ])
+        );
+
+        let response = requeue_dead_letters(State(logic.clone()), JsonFormat::default()).await;
+        assert_eq!(
+            json_body(response).await,
+            serde_json::json!({"requeued": 1})
+        );
+        assert!(state.dead_letters().is_empty());
+        let requeued = state.interval_queue().snapshot(10);
+        assert_eq!(requeued.len(), 1);
+        assert_eq!((requeued[0].start, requeued[0].end), (100, 199));
+        assert_eq!(requeued[0].attempts, 0);
+
+        let status = json_body(get_status(State(logic), JsonFormat::default()).await).await;
+        assert_eq!(status["dead_letters"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_dead_letters_are_capped() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        let count = crate::state::MAX_DEAD_LETTERS as u64 + 5;
+        for start in 0..count {
+            state.dead_letter(DeadLetteredInterval {
+                start,
+                end: start,
+                attempts: 10,
+                error: "block not available".to_string(),
+            });
+        }
+        assert_eq!(state.dead_letters().len(), crate::state::MAX_DEAD_LETTERS);
+        assert_eq!(state.dead_letters()[0].start, 5);
+        let logic = Arc::new(SyndicaAppLogic::new(state));
+
+        let status = json_body(get_status(State(logic), JsonFormat::default()).await).await;
+        let listed = status["dead_letters"].as_array().unwrap();
+        assert_eq!(listed.len(), STATUS_DEAD_LETTERS_LIMIT);
+        assert_eq!(listed.last().unwrap()["start"], count - 1);
+        assert_eq!(status["dead_letters_total"], crate::state::MAX_DEAD_LETTERS);
+        assert_eq!(status["dead_letters_dropped"], 5);
+    }
+
+    #[tokio::test]
+    async fn test_admin_depth_resizes_window_and_cache() {
+        let state = test_state(Arc::new(FakeClient::new(1_000)), 100);
+        state.set_last_processed_slot(1_000);
+        let logic = Arc::new(SyndicaAppLogic::new(state).with_monitoring_depth(100));
+
+        let set_depth = |depth: usize| {
+            set_admin_depth(
+                State(logic.clone()),
+                JsonFormat::default(),
+                Json(DepthRequest { depth }),
+            )
+        };
+
+        assert_eq!(set_depth(0).await.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            set_depth(MAX_MONITORING_DEPTH + 1).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(logic.monitoring_depth(), 100);
+
+        let response = set_depth(250).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(response).await,
+            serde_json::json!({"depth": 250, "cache_capacity": 250})
+        );
+
+        assert_eq!(logic.monitoring_window(), (750, 1_000));
+        assert_eq!(logic.state().cache().capacity(), 250);
+        let stats = json_body(get_stats(State(logic), JsonFormat::default()).await).await;
+        assert_eq!(stats["monitoring_depth"], 250);
+        assert_eq!(
+            stats["avg_confirmation_latency_ms"],
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            stats["rpc_calls"],
+            serde_json::json!({
+                "get_slot": 0,
+                "get_blocks": 0,
+                "get_block_time": 0,
+                "get_signature_statuses": 0,
+                "get_first_available_block": 0,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_without_prometheus_backend() {
+        let logic = Arc::new(SyndicaAppLogic::new(test_state(
+            Arc::new(FakeClient::new(1_000)),
+            100,
+        )));
+
+        let response = get_metrics(State(logic)).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,36 +1,171 @@
-use std::sync::Arc;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 
 use crate::cache::BlockCache;
 use crate::metrics::Metrics;
-use crate::syndica_client::SyndicaClient;
+use crate::synchronizer::{FifoIntervalQueue, IntervalQueue};
+use crate::syndica_client::SolanaRpc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Newly confirmed slots a subscriber may fall behind by before it misses
+/// some and receives `RecvError::Lagged` instead.
+pub const DEFAULT_SLOT_BROADCAST_BUFFER: usize = 1024;
+
+/// Confirmation latencies averaged for `/stats`.
+const CONFIRMATION_LATENCY_WINDOW: usize = 100;
+
+/// Dead-lettered intervals kept until they are requeued. Beyond this the
+/// oldest are dropped and only counted, so an endpoint that keeps failing
+/// cannot grow the list without bound.
+pub const MAX_DEAD_LETTERS: usize = 1_000;
+
+/// Average of the last `CONFIRMATION_LATENCY_WINDOW` samples.
+#[derive(Debug, Default)]
+struct RollingAverage {
+    samples: VecDeque<Duration>,
+    total: Duration,
+}
+
+impl RollingAverage {
+    fn record(&mut self, sample: Duration) {
+        if self.samples.len() == CONFIRMATION_LATENCY_WINDOW {
+            self.total -= self.samples.pop_front().unwrap_or_default();
+        }
+        self.samples.push_back(sample);
+        self.total += sample;
+    }
+
+    fn average(&self) -> Option<Duration> {
+        (!self.samples.is_empty()).then(|| self.total / self.samples.len() as u32)
+    }
+}
+
+/// Work whose last success is tracked so a stalled monitor can be detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    GetSlot,
+    GetBlocks,
+    CacheInsert,
+}
+
+impl Activity {
+    pub const ALL: [Activity; 3] = [
+        Activity::GetSlot,
+        Activity::GetBlocks,
+        Activity::CacheInsert,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Activity::GetSlot => "get_slot",
+            Activity::GetBlocks => "get_blocks",
+            Activity::CacheInsert => "cache_insert",
+        }
+    }
+}
+
+/// How far the synchronizer has backfilled history, as reported by
+/// `/status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BackfillProgress {
+    /// Oldest slot being backfilled: the start of the monitoring window, or
+    /// `START_FROM_SLOT`.
+    pub from_slot: u64,
+    /// Newest slot being backfilled, the tip at startup.
+    pub to_slot: u64,
+    pub completed_slots: u64,
+    pub total_slots: u64,
+}
+
+/// An interval the synchronizer stopped retrying after `SYNC_MAX_ATTEMPTS`
+/// failed attempts, as reported by `/status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeadLetteredInterval {
+    pub start: u64,
+    pub end: u64,
+    pub attempts: u32,
+    /// Error of the last attempt.
+    pub error: String,
+}
+
+/// The newest `MAX_DEAD_LETTERS` dead letters, oldest first.
+#[derive(Debug, Default)]
+struct DeadLetters {
+    intervals: VecDeque<DeadLetteredInterval>,
+    dropped: u64,
+}
 
 pub struct AppState {
     cache: Arc<BlockCache>,
-    client: Arc<SyndicaClient>,
+    client: Arc<dyn SolanaRpc>,
     metrics: Arc<dyn Metrics + Send + Sync>,
     last_processed_slot: AtomicU64,
+    sync_lag: AtomicU64,
+    interval_queue: Arc<dyn IntervalQueue>,
+    confirmed_slots: broadcast::Sender<u64>,
+    rpc_calls: AtomicU64,
+    rpc_errors: AtomicU64,
+    slot_time_reference: Mutex<Option<(u64, i64)>>,
+    backfill_progress: Mutex<Option<BackfillProgress>>,
+    dead_letters: Mutex<DeadLetters>,
+    confirmation_latency: Mutex<RollingAverage>,
+    http_in_flight: Mutex<HashMap<String, usize>>,
+    started: Instant,
+    last_success: Mutex<[Option<Instant>; Activity::ALL.len()]>,
 }
 
 impl AppState {
     pub fn new(
         cache: Arc<BlockCache>,
-        client: Arc<SyndicaClient>,
+        client: Arc<dyn SolanaRpc>,
         metrics: Arc<dyn Metrics + Send + Sync>,
     ) -> Self {
+        let interval_queue = Arc::new(FifoIntervalQueue::new(Arc::clone(&metrics)));
         Self {
             cache,
             client,
             metrics,
+            interval_queue,
             last_processed_slot: AtomicU64::new(0),
+            sync_lag: AtomicU64::new(0),
+            confirmed_slots: broadcast::channel(DEFAULT_SLOT_BROADCAST_BUFFER).0,
+            rpc_calls: AtomicU64::new(0),
+            rpc_errors: AtomicU64::new(0),
+            slot_time_reference: Mutex::new(None),
+            backfill_progress: Mutex::new(None),
+            dead_letters: Mutex::default(),
+            confirmation_latency: Mutex::default(),
+            http_in_flight: Mutex::default(),
+            started: Instant::now(),
+            last_success: Mutex::default(),
         }
     }
 
+    /// Capacity of the confirmed-slot broadcast channel. The channel keeps
+    /// this many of the most recent slots (8 bytes each) for its slowest
+    /// subscriber, so a larger buffer costs memory but lets slow consumers
+    /// fall further behind before they miss slots.
+    pub fn with_slot_broadcast_buffer(mut self, buffer: usize) -> Self {
+        self.confirmed_slots = broadcast::channel(buffer.max(1)).0;
+        self
+    }
+
+    /// Replaces the `FifoIntervalQueue` the synchronizer works off, e.g.
+    /// with one that orders intervals differently or holds a bounded number.
+    /// Must be set before a `Synchronizer` is created from this state.
+    pub fn with_interval_queue(mut self, interval_queue: Arc<dyn IntervalQueue>) -> Self {
+        self.interval_queue = interval_queue;
+        self
+    }
+
     pub fn cache(&self) -> &Arc<BlockCache> {
         &self.cache
     }
 
-    pub fn client(&self) -> &Arc<SyndicaClient> {
+    pub fn client(&self) -> &Arc<dyn SolanaRpc> {
         &self.client
     }
 
@@ -38,6 +173,21 @@ impl AppState {
         &self.metrics
     }
 
+    pub fn interval_queue(&self) -> &Arc<dyn IntervalQueue> {
+        &self.interval_queue
+    }
+
+    /// Receives every slot newly added to the cache from now on.
+    pub fn subscribe_confirmed_slots(&self) -> broadcast::Receiver<u64> {
+        self.confirmed_slots.subscribe()
+    }
+
+    /// Publishes a newly confirmed slot. Slots published while nobody is
+    /// subscribed are dropped.
+    pub fn publish_confirmed_slot(&self, slot: u64) {
+        let _ = self.confirmed_slots.send(slot);
+    }
+
     pub fn last_processed_slot(&self) -> u64 {
         self.last_processed_slot.load(Ordering::Relaxed)
     }
@@ -45,4 +195,153 @@ impl AppState {
     pub fn set_last_processed_slot(&self, slot: u64) {
         self.last_processed_slot.store(slot, Ordering::Relaxed);
     }
+
+    /// Slots between the latest known slot and the highest slot up to which
+    /// the synchronizer has contiguous coverage.
+    pub fn sync_lag(&self) -> u64 {
+        self.sync_lag.load(Ordering::Relaxed)
+    }
+
+    pub fn set_sync_lag(&self, slots: u64) {
+        self.sync_lag.store(slots, Ordering::Relaxed);
+    }
+
+    /// The most recent slot with a known block time, and that time.
+    pub fn slot_time_reference(&self) -> Option<(u64, i64)> {
+        *self
+            .slot_time_reference
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_slot_time_reference(&self, slot: u64, block_time: i64) {
+        *self
+            .slot_time_reference
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((slot, block_time));
+    }
+
+    /// Latest backfill progress, or `None` before the synchronizer's first
+    /// tick and in tip-only mode.
+    pub fn backfill_progress(&self) -> Option<BackfillProgress> {
+        *self
+            .backfill_progress
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_backfill_progress(&self, progress: BackfillProgress) {
+        *self
+            .backfill_progress
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(progress);
+    }
+
+    /// Records an interval the synchronizer gave up on, dropping the
+    /// oldest one once `MAX_DEAD_LETTERS` are kept.
+    pub fn dead_letter(&self, interval: DeadLetteredInterval) {
+        let mut dead_letters = self
+            .dead_letters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        dead_letters.intervals.push_back(interval);
+        if dead_letters.intervals.len() > MAX_DEAD_LETTERS {
+            dead_letters.intervals.pop_front();
+            dead_letters.dropped += 1;
+        }
+    }
+
+    /// Intervals the synchronizer gave up on, oldest first.
+    pub fn dead_letters(&self) -> Vec<DeadLetteredInterval> {
+        self.dead_letters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .intervals
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Dead letters dropped since startup to stay within `MAX_DEAD_LETTERS`.
+    pub fn dead_letters_dropped(&self) -> u64 {
+        self.dead_letters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .dropped
+    }
+
+    /// Removes and returns every dead-lettered interval, for requeueing.
+    pub fn take_dead_letters(&self) -> Vec<DeadLetteredInterval> {
+        std::mem::take(
+            &mut self
+                .dead_letters
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .intervals,
+        )
+        .into()
+    }
+
+    pub fn record_confirmation_latency(&self, latency: Duration) {
+        self.confirmation_latency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .record(latency);
+    }
+
+    /// Average confirmation latency over the most recent samples, or `None`
+    /// before the first one.
+    pub fn confirmation_latency(&self) -> Option<Duration> {
+        self.confirmation_latency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .average()
+    }
+
+    /// Adds `delta` (±1) to the requests in flight for `route` and returns
+    /// the new count.
+    pub fn update_http_in_flight(&self, route: &str, delta: isize) -> usize {
+        let mut in_flight = self
+            .http_in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let count = in_flight.entry(route.to_string()).or_default();
+        *count = count.saturating_add_signed(delta);
+        *count
+    }
+
+    pub fn record_rpc_call(&self, ok: bool) {
+        self.rpc_calls.fetch_add(1, Ordering::Relaxed);
+        if !ok {
+            self.rpc_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Total RPC calls and failed calls since startup.
+    pub fn rpc_call_counts(&self) -> (u64, u64) {
+        (
+            self.rpc_calls.load(Ordering::Relaxed),
+            self.rpc_errors.load(Ordering::Relaxed),
+        )
+    }
+
+    pub fn record_success(&self, activity: Activity) {
+        self.record_success_at(activity, Instant::now());
+    }
+
+    pub fn record_success_at(&self, activity: Activity, at: Instant) {
+        self.last_success
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())[activity as usize] = Some(at);
+    }
+
+    /// Time from the last success of `activity` to `now`, counted from
+    /// startup if it has never succeeded.
+    pub fn since_last_success_at(&self, activity: Activity, now: Instant) -> Duration {
+        let last_success = self
+            .last_success
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())[activity as usize];
+        now.saturating_duration_since(last_success.unwrap_or(self.started))
+    }
 }
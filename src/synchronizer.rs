@@ -1,26 +1,97 @@
-use scc::Queue;
-use std::sync::Arc;
-use std::time::Duration;
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
-use tokio::time::interval;
-use tracing::{debug, error, info};
+use tokio::time::{Interval, interval_at};
+use tracing::{debug, error, info, warn};
 
-use crate::logic::SyndicaAppLogic;
+use crate::logic::{SyncMode, SyndicaAppLogic};
+use crate::metrics::{IntervalOutcome, Metrics, names};
+use crate::state::{BackfillProgress, DeadLetteredInterval};
 
-const WORKERS_COUNT: usize = 5;
-const INTERVAL_SIZE: u64 = 100;
-const MIN_INTERVAL_SIZE: u64 = 5;
+pub const WORKERS_COUNT: usize = 5;
+/// Slots a gap is extended to when it is queued again, unless set with
+/// `SYNC_INTERVAL_SIZE`.
+pub const DEFAULT_SYNC_INTERVAL_SIZE: u64 = 100;
+/// Smallest gap sub-interval queued again, in slots.
+pub const MIN_INTERVAL_SIZE: u64 = 5;
+/// Times an interval is tried before it is dead-lettered, unless set with
+/// `SYNC_MAX_ATTEMPTS`. The nine requeue backoffs in between add up to
+/// about 51 seconds, plus the time the failing attempts themselves take.
+pub const DEFAULT_SYNC_MAX_ATTEMPTS: u32 = 10;
+/// Queued intervals at which the slot updater stops adding history
+/// backfill steps, and the depth the queue must drain to before it resumes.
+pub const DEFAULT_QUEUE_HIGH_WATER: usize = 2_000;
+pub const DEFAULT_QUEUE_LOW_WATER: usize = 1_000;
 const POLL_DIVIDER: u64 = 10;
+const REQUEUE_BASE_DELAY_MS: u64 = 100;
+const REQUEUE_MAX_DELAY_MS: u64 = 30_000;
+/// Widest range a worker merges queued intervals into in batch mode.
+const BATCH_MAX_SLOTS: u64 = 10 * DEFAULT_SYNC_INTERVAL_SIZE;
+/// Tracked worker time after which a worker reports its utilization.
+const UTILIZATION_REPORT_PERIOD: Duration = Duration::from_secs(10);
+/// Minimum time between two backfill progress reports.
+const BACKFILL_PROGRESS_PERIOD: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
-struct SlotInterval {
+pub struct SlotInterval {
     start: u64,
     end: u64,
+    attempts: u32,
+    /// Set on intervals queued by the slot updater, whose completion
+    /// advances `SyncCoverage`.
+    tracked: bool,
 }
 
 impl SlotInterval {
-    fn new(start: u64, end: u64) -> Self {
-        Self { start, end }
+    pub(crate) fn new(start: u64, end: u64) -> Self {
+        Self {
+            start,
+            end,
+            attempts: 0,
+            tracked: false,
+        }
+    }
+
+    fn tracked(mut self) -> Self {
+        self.tracked = true;
+        self
+    }
+
+    /// The same range, recorded as having failed one more time.
+    fn retry(&self) -> Self {
+        Self {
+            start: self.start,
+            end: self.end,
+            attempts: self.attempts + 1,
+            tracked: self.tracked,
+        }
+    }
+
+    /// The smallest interval covering all of `intervals`, carrying the
+    /// highest attempt count among them.
+    fn spanning(intervals: &[SlotInterval]) -> Self {
+        Self {
+            start: intervals.iter().map(|i| i.start).min().unwrap_or_default(),
+            end: intervals.iter().map(|i| i.end).max().unwrap_or_default(),
+            attempts: intervals
+                .iter()
+                .map(|i| i.attempts)
+                .max()
+                .unwrap_or_default(),
+            tracked: false,
+        }
+    }
+
+    /// Delay before a failed interval is requeued: doubles with every
+    /// attempt and is capped at `REQUEUE_MAX_DELAY_MS`.
+    fn backoff_delay(&self) -> Duration {
+        let exponent = self.attempts.saturating_sub(1).min(16);
+        let delay_ms = (REQUEUE_BASE_DELAY_MS << exponent).min(REQUEUE_MAX_DELAY_MS);
+        Duration::from_millis(delay_ms)
     }
 
     fn size(&self) -> u64 {
@@ -32,6 +103,429 @@ impl SlotInterval {
     }
 }
 
+/// Time a worker spent processing intervals versus sleeping since its last
+/// utilization report.
+#[derive(Debug, Default)]
+struct WorkerUtilization {
+    busy: Duration,
+    idle: Duration,
+}
+
+impl WorkerUtilization {
+    fn add_busy(&mut self, elapsed: Duration) {
+        self.busy += elapsed;
+    }
+
+    fn add_idle(&mut self, elapsed: Duration) {
+        self.idle += elapsed;
+    }
+
+    /// The busy share of the tracked time once at least `period` has been
+    /// tracked, starting a new period.
+    fn take_ratio(&mut self, period: Duration) -> Option<f64> {
+        let total = self.busy + self.idle;
+        if total.is_zero() || total < period {
+            return None;
+        }
+        let ratio = self.busy.as_secs_f64() / total.as_secs_f64();
+        *self = Self::default();
+        Some(ratio)
+    }
+}
+
+/// Tracks which part of the monitoring window the slot updater has queued.
+///
+/// The first tick reaches back at most `backfill_depth` slots. Every later
+/// tick queues the newly produced slots plus one more `backfill_depth` step
+/// of history, until the whole monitoring window has been covered. A
+/// tip-only window queues just the tip on its first tick and never
+/// backfills. A window with `start_from` set backfills forward from that
+/// slot instead, see `advance_from`.
+#[derive(Debug, Default)]
+struct SlotWindow {
+    tip_only: bool,
+    start_from: Option<u64>,
+    /// Skip the backfill step on the following ticks, leaving it for when
+    /// the workers have caught up. Newly produced slots are still queued.
+    defer_backfill: bool,
+    last_tracked_slot: u64,
+    backfill_floor: u64,
+    /// Latest slot at the first tick; the backfill covers the window below
+    /// and including it.
+    backfill_ceiling: u64,
+    /// Oldest slot not queued yet by a forward backfill.
+    forward_cursor: u64,
+}
+
+impl SlotWindow {
+    fn new(sync_mode: SyncMode) -> Self {
+        Self {
+            tip_only: sync_mode == SyncMode::TipOnly,
+            ..Self::default()
+        }
+    }
+
+    /// Oldest slot of the monitoring window ending at `tip`. A tip below
+    /// `monitoring_depth`, as on a local test validator or a fresh devnet,
+    /// gives a shorter window starting at slot 1.
+    fn window_start(tip: u64, monitoring_depth: u64) -> u64 {
+        tip.saturating_sub(monitoring_depth).max(1)
+    }
+
+    fn advance(
+        &mut self,
+        start_slot: u64,
+        monitoring_depth: u64,
+        backfill_depth: u64,
+    ) -> Vec<SlotInterval> {
+        if let Some(anchor) = self.start_from {
+            return self.advance_from(anchor, start_slot, backfill_depth);
+        }
+
+        let mut intervals = Vec::new();
+        let window_start = Self::window_start(start_slot, monitoring_depth);
+        let first_tick = self.last_tracked_slot == 0;
+
+        let floor = if !first_tick {
+            window_start
+        } else if self.tip_only {
+            start_slot
+        } else {
+            std::cmp::max(window_start, start_slot.saturating_sub(backfill_depth))
+        };
+        let begin_slot = std::cmp::max(self.last_tracked_slot + 1, floor);
+        if begin_slot <= start_slot {
+            intervals.push(SlotInterval::new(begin_slot, start_slot));
+        }
+
+        if first_tick {
+            self.backfill_floor = begin_slot;
+            self.backfill_ceiling = start_slot;
+        } else if !self.tip_only && !self.defer_backfill && self.backfill_floor > window_start {
+            let step_start = std::cmp::max(
+                window_start,
+                self.backfill_floor.saturating_sub(backfill_depth),
+            );
+            intervals.push(SlotInterval::new(step_start, self.backfill_floor - 1));
+            self.backfill_floor = step_start;
+        }
+
+        self.last_tracked_slot = start_slot;
+        intervals
+    }
+
+    /// Like `advance`, but the backfill starts at `anchor` and moves forward
+    /// one `backfill_depth` step per tick until it reaches the latest slot
+    /// at the first tick. Newly produced slots are queued as usual, and
+    /// nothing below `anchor` is backfilled.
+    fn advance_from(
+        &mut self,
+        anchor: u64,
+        start_slot: u64,
+        backfill_depth: u64,
+    ) -> Vec<SlotInterval> {
+        let mut intervals = Vec::new();
+        if self.last_tracked_slot == 0 {
+            self.backfill_floor = std::cmp::min(anchor, start_slot);
+            self.backfill_ceiling = start_slot;
+            self.forward_cursor = self.backfill_floor;
+        } else if self.last_tracked_slot < start_slot {
+            intervals.push(SlotInterval::new(self.last_tracked_slot + 1, start_slot));
+        }
+
+        if !self.defer_backfill && self.forward_cursor <= self.backfill_ceiling {
+            let step_end = std::cmp::min(
+                self.backfill_ceiling,
+                self.forward_cursor + backfill_depth.max(1) - 1,
+            );
+            intervals.push(SlotInterval::new(self.forward_cursor, step_end));
+            self.forward_cursor = step_end + 1;
+        }
+
+        self.last_tracked_slot = start_slot;
+        intervals
+    }
+
+    /// Oldest and newest slot of the backfill.
+    fn backfill_range(&self, monitoring_depth: u64) -> (u64, u64) {
+        let from = match self.start_from {
+            Some(_) => self.backfill_floor,
+            None => Self::window_start(self.last_tracked_slot, monitoring_depth),
+        };
+        (from, self.backfill_ceiling)
+    }
+
+    /// Backfilled slots still inside the monitoring window that have been
+    /// queued and processed, and the number of such slots in total. A
+    /// forward backfill counts every slot from its anchor instead.
+    fn backfill_progress(&self, coverage: &SyncCoverage, monitoring_depth: u64) -> (u64, u64) {
+        if self.start_from.is_some() {
+            let total = (self.backfill_ceiling + 1).saturating_sub(self.backfill_floor);
+            let queued = self.forward_cursor.saturating_sub(self.backfill_floor);
+            let pending = match queued {
+                0 => 0,
+                _ => coverage.pending_slots(self.backfill_floor, self.forward_cursor - 1),
+            };
+            return (queued.saturating_sub(pending), total);
+        }
+
+        let window_start = Self::window_start(self.last_tracked_slot, monitoring_depth);
+        let total = (self.backfill_ceiling + 1).saturating_sub(window_start);
+        let from = std::cmp::max(self.backfill_floor, window_start);
+        let queued = (self.backfill_ceiling + 1).saturating_sub(from);
+        let pending = coverage.pending_slots(from, self.backfill_ceiling);
+        (queued.saturating_sub(pending), total)
+    }
+}
+
+/// Hysteresis on the interval queue depth: backfill is deferred once the
+/// queue holds `high_water` intervals and resumes once it has drained to
+/// `low_water`. A `high_water` of 0 never defers.
+#[derive(Debug, Clone, Copy)]
+struct Backpressure {
+    high_water: usize,
+    low_water: usize,
+    deferring: bool,
+}
+
+impl Backpressure {
+    fn new(high_water: usize, low_water: usize) -> Self {
+        Self {
+            high_water,
+            low_water,
+            deferring: false,
+        }
+    }
+
+    /// Updates the state for the current queue `depth`, returning whether
+    /// it changed.
+    fn observe(&mut self, depth: usize) -> bool {
+        let deferring = if self.deferring {
+            depth > self.low_water
+        } else {
+            self.high_water > 0 && depth >= self.high_water
+        };
+        let changed = deferring != self.deferring;
+        self.deferring = deferring;
+        changed
+    }
+}
+
+struct QueuedInterval {
+    interval: SlotInterval,
+    enqueued_at: Instant,
+}
+
+/// Read-only view of a queued interval, as reported by `/admin/queue`.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedIntervalInfo {
+    pub start: u64,
+    pub end: u64,
+    pub size: u64,
+    pub attempts: u32,
+    pub age_ms: u64,
+}
+
+/// Intervals waiting for a worker.
+///
+/// The slot updater pushes to it and the history workers pop from it, so
+/// an implementation decides the order intervals are worked off in and
+/// whether it holds them all. It lives in `AppState`, set with
+/// `AppState::with_interval_queue`, so the HTTP layer can inspect it.
+pub trait IntervalQueue: Send + Sync {
+    fn push(&self, interval: SlotInterval);
+
+    fn pop(&self) -> Option<SlotInterval>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pops intervals for a worker to query as one range of at most
+    /// `max_slots` slots. By default this is just the next interval.
+    fn pop_batch(&self, _max_slots: u64) -> Vec<SlotInterval> {
+        self.pop().into_iter().collect()
+    }
+
+    /// The first `limit` intervals in the order they would be popped, for
+    /// `/admin/queue`. Empty for queues that cannot list their entries.
+    fn snapshot(&self, _limit: usize) -> Vec<QueuedIntervalInfo> {
+        Vec::new()
+    }
+}
+
+/// FIFO queue of intervals waiting for a worker, the default
+/// `IntervalQueue`.
+///
+/// Every entry is timestamped when it is pushed, and the queue length and
+/// the age of the oldest entry are reported to `Metrics` on every push and
+/// pop, so a backlog shows up before answers go stale.
+pub struct FifoIntervalQueue {
+    entries: Mutex<VecDeque<QueuedInterval>>,
+    metrics: Arc<dyn Metrics + Send + Sync>,
+}
+
+impl IntervalQueue for FifoIntervalQueue {
+    fn push(&self, interval: SlotInterval) {
+        self.push_at(interval, Instant::now());
+    }
+
+    fn pop(&self) -> Option<SlotInterval> {
+        self.pop_at(Instant::now())
+    }
+
+    fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    fn pop_batch(&self, max_slots: u64) -> Vec<SlotInterval> {
+        self.pop_batch_at(max_slots, Instant::now())
+    }
+
+    fn snapshot(&self, limit: usize) -> Vec<QueuedIntervalInfo> {
+        let now = Instant::now();
+        self.entries()
+            .iter()
+            .take(limit)
+            .map(|entry| QueuedIntervalInfo {
+                start: entry.interval.start,
+                end: entry.interval.end,
+                size: entry.interval.size(),
+                attempts: entry.interval.attempts,
+                age_ms: now.saturating_duration_since(entry.enqueued_at).as_millis() as u64,
+            })
+            .collect()
+    }
+}
+
+impl FifoIntervalQueue {
+    pub fn new(metrics: Arc<dyn Metrics + Send + Sync>) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            metrics,
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, VecDeque<QueuedInterval>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn push_at(&self, interval: SlotInterval, now: Instant) {
+        let mut entries = self.entries();
+        entries.push_back(QueuedInterval {
+            interval,
+            enqueued_at: now,
+        });
+        self.report(&entries, now);
+    }
+
+    fn pop_at(&self, now: Instant) -> Option<SlotInterval> {
+        let mut entries = self.entries();
+        let popped = entries.pop_front()?;
+        self.report(&entries, now);
+        Some(popped.interval)
+    }
+
+    /// Pops the front interval together with every queued interval that
+    /// overlaps or adjoins the range merged so far, as long as that range
+    /// stays within `max_slots`. Disjoint intervals stay queued.
+    fn pop_batch_at(&self, max_slots: u64, now: Instant) -> Vec<SlotInterval> {
+        let mut entries = self.entries();
+        let Some(first) = entries.pop_front() else {
+            return Vec::new();
+        };
+
+        let (mut start, mut end) = (first.interval.start, first.interval.end);
+        let mut batch = vec![first.interval];
+        while let Some(position) = entries.iter().position(|entry| {
+            let interval = &entry.interval;
+            interval.start <= end.saturating_add(1)
+                && interval.end.saturating_add(1) >= start
+                && interval.end.max(end) - interval.start.min(start) < max_slots
+        }) {
+            let interval = entries.remove(position).unwrap().interval;
+            start = start.min(interval.start);
+            end = end.max(interval.end);
+            batch.push(interval);
+        }
+
+        self.report(&entries, now);
+        batch
+    }
+
+    fn report(&self, entries: &VecDeque<QueuedInterval>, now: Instant) {
+        let oldest_age = entries
+            .front()
+            .map(|oldest| now.saturating_duration_since(oldest.enqueued_at))
+            .unwrap_or_default();
+        self.metrics
+            .set_gauge(names::QUEUE_DEPTH, &[], entries.len() as f64);
+        self.metrics
+            .set_gauge(names::INTERVAL_AGE_SECONDS, &[], oldest_age.as_secs_f64());
+    }
+}
+
+/// How every history worker processes the intervals it pops.
+#[derive(Debug, Clone, Copy)]
+struct WorkerSettings {
+    batch_intervals: bool,
+    interval_size: u64,
+    max_attempts: u32,
+}
+
+/// Tracks intervals queued by the slot updater until their first successful
+/// pass, to derive the highest slot up to which coverage is contiguous.
+///
+/// Gap sub-intervals retried after that pass are not tracked: they mostly
+/// cover skipped slots that will never be confirmed.
+#[derive(Debug, Default)]
+struct SyncCoverage {
+    pending: Mutex<BTreeMap<u64, u64>>,
+    highest_queued: AtomicU64,
+}
+
+impl SyncCoverage {
+    fn pending(&self) -> MutexGuard<'_, BTreeMap<u64, u64>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn track(&self, interval: &SlotInterval) {
+        self.pending().insert(interval.start, interval.end);
+        self.highest_queued
+            .fetch_max(interval.end, Ordering::Relaxed);
+    }
+
+    fn complete(&self, interval: &SlotInterval) {
+        self.pending().remove(&interval.start);
+    }
+
+    /// Slots between `from` and `to` (inclusive) in tracked intervals that
+    /// have not been processed yet.
+    fn pending_slots(&self, from: u64, to: u64) -> u64 {
+        self.pending()
+            .iter()
+            .map(|(start, end)| {
+                let (start, end) = (std::cmp::max(*start, from), std::cmp::min(*end, to));
+                (end + 1).saturating_sub(start)
+            })
+            .sum()
+    }
+
+    /// Every tracked interval ending at or below this slot has been processed.
+    fn covered_slot(&self) -> u64 {
+        match self.pending().first_key_value() {
+            Some((start, _)) => start.saturating_sub(1),
+            None => self.highest_queued.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// The Synchronizer is designed to efficiently monitor Solana blockchain
 /// blocks while minimizing RPC traffic.
 ///
@@ -45,122 +539,443 @@ impl SlotInterval {
 ///    - Uses a concurrent queue to manage slot intervals
 ///    - Enables parallel processing of different slot ranges
 ///    - Provides backpressure when processing falls behind
+///    - With batching enabled, a worker merges adjoining queued intervals
+///      into one `get_blocks` call and requeues the remaining gaps
 ///
 /// 3. Dual Task System:
 ///    - Slot Updater: Continuously monitors new slots
 ///    - History Updater: Processes historical slots in parallel
 ///    - Separation allows independent scaling of real-time vs historical processing
 ///
+/// 4. Failure Handling:
+///    - Failed intervals are requeued after an exponential backoff
+///    - After `SYNC_MAX_ATTEMPTS` tries an interval is dead-lettered
+///
 /// Future Optimizations:
 /// 1. Adaptive Interval Sizing:
 ///    - Dynamically adjust interval size based on network conditions
-///    - Implement exponential backoff for failed intervals
-///    - Add interval merging for sparse regions
 ///
 /// 2. Performance Enhancements:
-///    - Add batch processing for multiple intervals
 ///    - Implement priority queue for newer slots
 ///    - Add circuit breaker for RPC rate limiting
 pub struct Synchronizer {
     logic: Arc<SyndicaAppLogic>,
-    monitor_interval_ms: u64,
-    monitoring_depth: usize,
-    interval_queue: Arc<Queue<SlotInterval>>,
+    initial_backfill_depth: usize,
+    slot_confirmation_offset: u64,
+    batch_intervals: bool,
+    interval_size: u64,
+    max_attempts: u32,
+    queue_high_water: usize,
+    queue_low_water: usize,
+    start_from_slot: Option<u64>,
+    history_disabled: bool,
+    slot_feed: Option<watch::Receiver<Option<u64>>>,
+    shutdown: watch::Receiver<bool>,
+    interval_queue: Arc<dyn IntervalQueue>,
+    coverage: Arc<SyncCoverage>,
 }
 
 impl Synchronizer {
-    pub fn new(
-        logic: Arc<SyndicaAppLogic>,
-        monitor_interval_ms: u64,
-        monitoring_depth: usize,
-    ) -> Self {
+    /// The monitoring depth and the monitor interval are read from `logic`
+    /// on every tick, so changes made with `set_monitoring_depth` or
+    /// `set_monitor_interval_ms` apply on the next update.
+    pub fn new(logic: Arc<SyndicaAppLogic>) -> Self {
+        let interval_queue = Arc::clone(logic.state().interval_queue());
+        let initial_backfill_depth = logic.monitoring_depth() as usize;
         Self {
             logic,
-            monitor_interval_ms,
-            monitoring_depth,
-            interval_queue: Arc::new(Queue::<SlotInterval>::default()),
+            initial_backfill_depth,
+            slot_confirmation_offset: 0,
+            batch_intervals: false,
+            interval_size: DEFAULT_SYNC_INTERVAL_SIZE,
+            max_attempts: DEFAULT_SYNC_MAX_ATTEMPTS,
+            queue_high_water: DEFAULT_QUEUE_HIGH_WATER,
+            queue_low_water: DEFAULT_QUEUE_LOW_WATER,
+            start_from_slot: None,
+            history_disabled: false,
+            slot_feed: None,
+            // Without `with_shutdown` the sender is gone and shutdown is
+            // never requested.
+            shutdown: watch::channel(false).1,
+            interval_queue,
+            coverage: Arc::new(SyncCoverage::default()),
         }
     }
 
+    /// Bounds how far back the first interval reaches on startup; the rest
+    /// of the monitoring window is backfilled in steps of this size on the
+    /// following ticks.
+    pub fn with_initial_backfill_depth(mut self, initial_backfill_depth: usize) -> Self {
+        self.initial_backfill_depth = initial_backfill_depth;
+        self
+    }
+
+    /// Keeps the newest `offset` slots below the reported tip out of the
+    /// queued intervals. Endpoints often report a tip whose latest blocks
+    /// `get_blocks` does not return yet, and querying them only produces
+    /// gaps that are queried again.
+    pub fn with_slot_confirmation_offset(mut self, offset: u64) -> Self {
+        self.slot_confirmation_offset = offset;
+        self
+    }
+
+    /// Lets workers merge adjoining queued intervals into a single range
+    /// of at most `BATCH_MAX_SLOTS` slots before querying it.
+    pub fn with_batch_intervals(mut self, batch_intervals: bool) -> Self {
+        self.batch_intervals = batch_intervals;
+        self
+    }
+
+    /// Size, in slots, a gap found in a processed interval is extended to
+    /// when it is queued again, covering the slots after it as well.
+    /// Larger sizes mean fewer, wider `getBlocks` calls.
+    pub fn with_interval_size(mut self, interval_size: u64) -> Self {
+        self.interval_size = interval_size;
+        self
+    }
+
+    /// Times an interval is tried before it is moved to the dead letters in
+    /// `AppState` instead of being requeued, so a range the endpoint can
+    /// never serve does not cycle forever. 0 retries without limit.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Stops queueing history backfill steps while `high_water` or more
+    /// intervals are waiting for a worker, until no more than `low_water`
+    /// are left, so an overloaded synchronizer does not keep growing its
+    /// backlog. The newest slots are still queued every tick. A
+    /// `high_water` of 0 disables this.
+    pub fn with_backpressure(mut self, high_water: usize, low_water: usize) -> Self {
+        self.queue_high_water = high_water;
+        self.queue_low_water = low_water;
+        self
+    }
+
+    /// Backfills forward from `slot` to the tip seen on the first tick,
+    /// in steps of the initial backfill depth, instead of backwards through
+    /// the monitoring window. `SyndicaAppLogic::resolve_start_slot` checks
+    /// `slot` against the endpoint first.
+    pub fn with_start_from_slot(mut self, slot: u64) -> Self {
+        self.start_from_slot = Some(slot);
+        self
+    }
+
+    /// Runs no history workers: the slot updater follows the tip as in
+    /// tip-only mode and confirms the newly produced slots itself instead
+    /// of queueing them. Older slots are only answered on demand.
+    pub fn with_history_disabled(mut self, history_disabled: bool) -> Self {
+        self.history_disabled = history_disabled;
+        self
+    }
+
+    /// Takes new tips from `feed`, such as `SyndicaClient::subscribe_slots`,
+    /// instead of polling `getSlot` every monitor interval. `getSlot` is
+    /// still called on startup, while the feed holds `None` and whenever
+    /// no slot arrives within a monitor interval.
+    pub fn with_slot_feed(mut self, feed: watch::Receiver<Option<u64>>) -> Self {
+        self.slot_feed = Some(feed);
+        self
+    }
+
+    /// Stops the synchronizer once `shutdown` holds `true`: the slot
+    /// updater finishes its current tick, every worker finishes the
+    /// interval it is processing and pulls no new one, and `run` returns
+    /// when all of them have stopped. Intervals still queued are dropped.
+    pub fn with_shutdown(mut self, shutdown: watch::Receiver<bool>) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     pub async fn run(&mut self) {
         info!("Starting block synchronizer");
-        let slot_updater_handle = self.spawn_slot_updater().await;
-        let history_updater_handle = self.spawn_history_updater().await;
+        let mut shutdown = self.shutdown.clone();
+        let mut slot_updater_handle = self.spawn_slot_updater().await;
+        if self.history_disabled {
+            info!("History updater disabled, only the newest slots are confirmed");
+            tokio::select! {
+                biased;
+                _ = Self::shutdown_requested(&mut shutdown) => {}
+                _ = &mut slot_updater_handle => {
+                    error!("Slot updater task ended unexpectedly");
+                    return;
+                }
+            }
+            info!("Stopping block synchronizer");
+            let _ = slot_updater_handle.await;
+            info!("Block synchronizer stopped");
+            return;
+        }
+        let mut history_updater_handle = self.spawn_history_updater().await;
 
+        // Shutdown goes first, so tasks that stopped because of it are not
+        // reported as ending unexpectedly.
         tokio::select! {
-            _ = slot_updater_handle => {
+            biased;
+            _ = Self::shutdown_requested(&mut shutdown) => {}
+            _ = &mut slot_updater_handle => {
                 error!("Slot updater task ended unexpectedly");
+                return;
             }
-            _ = history_updater_handle => {
+            _ = &mut history_updater_handle => {
                 error!("History updater task ended unexpectedly");
+                return;
             }
         }
+        info!("Stopping block synchronizer");
+        let _ = tokio::join!(slot_updater_handle, history_updater_handle);
+        info!("Block synchronizer stopped");
+    }
+
+    /// Resolves once `shutdown` holds `true`. Never resolves if the sender
+    /// is dropped without requesting shutdown.
+    async fn shutdown_requested(shutdown: &mut watch::Receiver<bool>) {
+        if shutdown.wait_for(|stop| *stop).await.is_err() {
+            std::future::pending::<()>().await;
+        }
     }
 
     async fn spawn_slot_updater(&mut self) -> JoinHandle<()> {
         let logic = Arc::clone(&self.logic);
-        let monitor_interval_ms = self.monitor_interval_ms;
         let interval_queue = Arc::clone(&self.interval_queue);
-        let monitoring_depth = self.monitoring_depth;
+        let backfill_depth = self.initial_backfill_depth as u64;
+        let confirmation_offset = self.slot_confirmation_offset;
+        let coverage = Arc::clone(&self.coverage);
+        let history_disabled = self.history_disabled;
+        // Without workers there is nobody to backfill history.
+        let sync_mode = if history_disabled {
+            SyncMode::TipOnly
+        } else {
+            self.logic.sync_mode()
+        };
+        let mut slot_feed = self.slot_feed.take();
+        let mut shutdown = self.shutdown.clone();
+        let mut backpressure = Backpressure::new(self.queue_high_water, self.queue_low_water);
+        let start_from_slot = self.start_from_slot;
 
         tokio::spawn(async move {
-            let mut interval_timer = interval(Duration::from_millis(monitor_interval_ms));
+            let mut monitor_interval_ms = logic.monitor_interval_ms();
+            // The loop polls once before waiting, so the first tick is a
+            // whole period away.
+            let period = Duration::from_millis(monitor_interval_ms);
+            let mut interval_timer = interval_at(tokio::time::Instant::now() + period, period);
             info!(
+                sync_mode = sync_mode.as_str(),
+                slot_feed = slot_feed.is_some(),
+                history_disabled,
                 "Slot updater started - updating every {}ms",
                 monitor_interval_ms
             );
-            let mut last_tracked_slot: u64 = 0;
+            let mut window = SlotWindow::new(sync_mode);
+            window.start_from = start_from_slot.filter(|_| sync_mode == SyncMode::Full);
+            if let Some(start_from_slot) = window.start_from {
+                info!(start_from_slot, "Backfilling forward from START_FROM_SLOT");
+            }
+            // A tip-only window has no history to backfill.
+            let tip_only = sync_mode == SyncMode::TipOnly;
+            let mut backfill_done = tip_only;
+            let mut last_backfill_report: Option<Instant> = None;
+            let mut last_depth = logic.monitoring_depth();
+            let mut pushed_slot = None;
+            // The first tick queues history, not newly produced slots.
+            let mut first_tick = true;
 
             loop {
-                match logic.update_latest_slot().await {
-                    Ok(start_slot) => {
-                        info!(start_slot, "Updated latest slot");
-                        let begin_slot = std::cmp::max(
-                            last_tracked_slot + 1,
-                            start_slot - monitoring_depth as u64,
-                        );
-                        if begin_slot <= start_slot {
-                            let interval = SlotInterval::new(begin_slot, start_slot);
+                let monitoring_depth = logic.monitoring_depth();
+                if monitoring_depth != last_depth {
+                    info!(
+                        previous = last_depth,
+                        monitoring_depth, "Monitoring depth changed"
+                    );
+                    // A deeper window has new history to backfill.
+                    backfill_done = tip_only;
+                    last_depth = monitoring_depth;
+                }
+                if logic.monitor_interval_ms() != monitor_interval_ms {
+                    monitor_interval_ms = logic.monitor_interval_ms();
+                    let period = Duration::from_millis(monitor_interval_ms);
+                    interval_timer = interval_at(tokio::time::Instant::now() + period, period);
+                    info!(monitor_interval_ms, "Slot updater interval changed");
+                }
+                let latest_slot = match pushed_slot {
+                    Some(slot) => Ok(logic.update_latest_slot_from(slot)),
+                    None => logic.update_latest_slot().await,
+                };
+                match latest_slot {
+                    Ok(latest_slot) => {
+                        let start_slot = latest_slot.saturating_sub(confirmation_offset);
+                        info!(latest_slot, start_slot, "Updated latest slot");
+                        let depth = interval_queue.len();
+                        if backpressure.observe(depth) {
+                            if backpressure.deferring {
+                                warn!(
+                                    queue_depth = depth,
+                                    high_water = backpressure.high_water,
+                                    "Interval queue is backed up, deferring history backfill"
+                                );
+                            } else {
+                                info!(
+                                    queue_depth = depth,
+                                    low_water = backpressure.low_water,
+                                    "Interval queue drained, resuming history backfill"
+                                );
+                            }
+                        }
+                        window.defer_backfill = backpressure.deferring;
+                        for interval in window.advance(start_slot, monitoring_depth, backfill_depth)
+                        {
+                            if !first_tick && interval.end == start_slot {
+                                logic.record_slots_queued(interval.start, interval.end);
+                            }
+                            let interval = interval.tracked();
+                            if history_disabled {
+                                Self::confirm_newest(&logic, &coverage, &interval).await;
+                                continue;
+                            }
                             info!(
                                 start = interval.start,
                                 end = interval.end,
                                 size = interval.size(),
                                 "Added interval to queue"
                             );
+                            coverage.track(&interval);
                             interval_queue.push(interval);
                         }
-                        last_tracked_slot = start_slot;
+                        first_tick = false;
+                        logic.record_sync_lag(coverage.covered_slot());
+
+                        if !backfill_done {
+                            let (completed, total) =
+                                window.backfill_progress(&coverage, monitoring_depth);
+                            let (from_slot, to_slot) = window.backfill_range(monitoring_depth);
+                            logic.state().set_backfill_progress(BackfillProgress {
+                                from_slot,
+                                to_slot,
+                                completed_slots: completed,
+                                total_slots: total,
+                            });
+                            backfill_done = completed >= total;
+                            if backfill_done
+                                || last_backfill_report
+                                    .is_none_or(|at| at.elapsed() >= BACKFILL_PROGRESS_PERIOD)
+                            {
+                                logic
+                                    .state()
+                                    .metrics()
+                                    .record_backfill_progress(completed, total);
+                                last_backfill_report = Some(Instant::now());
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("Failed to update starting slot: {}", e);
                     }
                 }
-                interval_timer.tick().await;
+                tokio::select! {
+                    pushed = Self::next_pushed_slot(slot_feed.as_mut(), &mut interval_timer) => {
+                        pushed_slot = pushed;
+                    }
+                    _ = Self::shutdown_requested(&mut shutdown) => break,
+                }
             }
+            info!("Slot updater stopped");
         })
     }
 
+    /// Confirms the newest slots on the slot updater itself, for when there
+    /// are no history workers. A failed range is not retried; its slots are
+    /// answered on demand instead.
+    async fn confirm_newest(
+        logic: &Arc<SyndicaAppLogic>,
+        coverage: &SyncCoverage,
+        interval: &SlotInterval,
+    ) {
+        match logic.query_slot_range(interval.start, interval.end).await {
+            Ok(confirmed) => {
+                coverage.track(interval);
+                coverage.complete(interval);
+                debug!(
+                    start = interval.start,
+                    end = interval.end,
+                    confirmed,
+                    "Confirmed newest slots"
+                );
+            }
+            Err(e) => warn!(
+                start = interval.start,
+                end = interval.end,
+                error = %e,
+                "Failed to confirm newest slots"
+            ),
+        }
+    }
+
+    /// Waits for the next slot from `feed`, or for the next tick of `timer`
+    /// if none arrives first, in which case the tip is polled. A pushed slot
+    /// restarts the timer, so polling only resumes once the feed is quiet
+    /// for a whole period, disconnected (`None`) or closed.
+    async fn next_pushed_slot(
+        feed: Option<&mut watch::Receiver<Option<u64>>>,
+        timer: &mut Interval,
+    ) -> Option<u64> {
+        let Some(feed) = feed else {
+            timer.tick().await;
+            return None;
+        };
+        tokio::select! {
+            changed = feed.changed() => {
+                let slot = match changed {
+                    Ok(()) => *feed.borrow_and_update(),
+                    Err(_) => None,
+                };
+                match slot {
+                    Some(_) => timer.reset(),
+                    None => {
+                        timer.tick().await;
+                    }
+                }
+                slot
+            }
+            _ = timer.tick() => None,
+        }
+    }
+
     async fn spawn_history_updater(&mut self) -> JoinHandle<()> {
         let logic = Arc::clone(&self.logic);
-        let monitoring_depth = self.monitoring_depth;
-        let monitor_interval_ms = self.monitor_interval_ms;
         let interval_queue = Arc::clone(&self.interval_queue);
+        let coverage = Arc::clone(&self.coverage);
+        let settings = WorkerSettings {
+            batch_intervals: self.batch_intervals,
+            interval_size: self.interval_size,
+            max_attempts: self.max_attempts,
+        };
+        let shutdown = self.shutdown.clone();
 
         tokio::spawn(async move {
-            info!("History updater started with {} workers", WORKERS_COUNT);
+            info!(
+                batch_intervals = settings.batch_intervals,
+                interval_size = settings.interval_size,
+                max_attempts = settings.max_attempts,
+                "History updater started with {} workers",
+                WORKERS_COUNT
+            );
 
             let mut worker_handles = Vec::new();
             for worker_id in 0..WORKERS_COUNT {
                 let worker_logic = Arc::clone(&logic);
                 let worker_queue = Arc::clone(&interval_queue);
+                let worker_coverage = Arc::clone(&coverage);
+                let worker_shutdown = shutdown.clone();
 
                 let handle = tokio::spawn(async move {
                     Self::interval_worker(
                         worker_id,
                         worker_logic,
                         worker_queue,
-                        monitoring_depth,
-                        monitor_interval_ms,
+                        worker_coverage,
+                        settings,
+                        worker_shutdown,
                     )
                     .await;
                 });
@@ -178,28 +993,73 @@ impl Synchronizer {
     async fn interval_worker(
         worker_id: usize,
         logic: Arc<SyndicaAppLogic>,
-        queue: Arc<Queue<SlotInterval>>,
-        monitoring_depth: usize,
-        monitor_interval_ms: u64,
+        queue: Arc<dyn IntervalQueue>,
+        coverage: Arc<SyncCoverage>,
+        settings: WorkerSettings,
+        mut shutdown: watch::Receiver<bool>,
     ) {
         info!(worker_id, "History worker started");
 
-        loop {
-            if let Some(interval) = queue.pop() {
+        let mut utilization = WorkerUtilization::default();
+        while !*shutdown.borrow() {
+            if let Some(busy_ratio) = utilization.take_ratio(UTILIZATION_REPORT_PERIOD) {
+                logic.state().metrics().set_gauge(
+                    names::WORKER_UTILIZATION,
+                    &[("worker", worker_id.to_string().as_str())],
+                    busy_ratio,
+                );
+            }
+
+            let monitor_interval_ms = logic.monitor_interval_ms();
+            let batch = if settings.batch_intervals {
+                queue.pop_batch(BATCH_MAX_SLOTS)
+            } else {
+                queue.pop().into_iter().collect()
+            };
+
+            if !batch.is_empty() {
+                let interval = SlotInterval::spanning(&batch);
                 info!(
                     worker_id,
                     start = interval.start,
                     end = interval.end,
                     size = interval.size(),
+                    merged = batch.len(),
                     "Worker got interval from queue"
                 );
 
-                match Self::process_interval(&logic, &interval).await {
-                    Ok(sub_intervals) => {
+                let started = Instant::now();
+                let result =
+                    Self::process_interval(&logic, &interval, settings.interval_size).await;
+                let (confirmed_count, sub_intervals_count, outcome) = match &result {
+                    Ok((confirmed_count, sub_intervals)) => (
+                        *confirmed_count,
+                        sub_intervals.len(),
+                        IntervalOutcome::Success,
+                    ),
+                    Err(_) => (0, 0, IntervalOutcome::Failure),
+                };
+                logic.state().metrics().record_interval_processed(
+                    worker_id,
+                    interval.size(),
+                    started.elapsed(),
+                    confirmed_count,
+                    sub_intervals_count,
+                    outcome,
+                );
+
+                match result {
+                    Ok((_, sub_intervals)) => {
+                        for member in batch.iter().filter(|member| member.tracked) {
+                            coverage.complete(member);
+                        }
                         for sub_interval in sub_intervals {
                             let interval_size_ok = sub_interval.size() >= MIN_INTERVAL_SIZE;
                             let interval_end_ok = sub_interval.end
-                                > logic.state().last_processed_slot() - monitoring_depth as u64;
+                                > logic
+                                    .state()
+                                    .last_processed_slot()
+                                    .saturating_sub(logic.monitoring_depth());
                             if interval_size_ok && interval_end_ok {
                                 queue.push(sub_interval.clone());
                                 debug!(
@@ -233,25 +1093,94 @@ impl Synchronizer {
                             worker_id,
                             start = interval.start,
                             end = interval.end,
+                            attempts = interval.attempts + 1,
                             error = %e,
                             "Failed to process interval"
                         );
-                        queue.push(SlotInterval::new(interval.start, interval.end));
+                        for member in &batch {
+                            let retry = member.retry();
+                            if settings.max_attempts > 0 && retry.attempts >= settings.max_attempts
+                            {
+                                Self::dead_letter(&logic, &coverage, &retry, &e.to_string());
+                                continue;
+                            }
+                            let delay = retry.backoff_delay();
+                            Self::requeue_after(Arc::clone(&queue), retry, delay, shutdown.clone());
+                        }
                     }
                 }
+                utilization.add_busy(started.elapsed());
+
                 debug!(worker_id, "No interval to process - sleeping briefly");
-                tokio::time::sleep(Duration::from_millis(monitor_interval_ms / POLL_DIVIDER)).await;
+                let sleep_started = Instant::now();
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(monitor_interval_ms / POLL_DIVIDER)) => {}
+                    _ = Self::shutdown_requested(&mut shutdown) => {}
+                }
+                utilization.add_idle(sleep_started.elapsed());
             } else {
                 info!(worker_id, "No interval to process - sleeping");
-                tokio::time::sleep(Duration::from_millis(monitor_interval_ms)).await;
+                let sleep_started = Instant::now();
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(monitor_interval_ms)) => {}
+                    _ = Self::shutdown_requested(&mut shutdown) => {}
+                }
+                utilization.add_idle(sleep_started.elapsed());
             }
         }
+        info!(worker_id, "History worker stopped");
+    }
+
+    /// Pushes `interval` back onto the queue once `delay` has elapsed,
+    /// without blocking the calling worker. Dropped if shutdown is requested
+    /// first, since nothing would process it.
+    fn requeue_after(
+        queue: Arc<dyn IntervalQueue>,
+        interval: SlotInterval,
+        delay: Duration,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => queue.push(interval),
+                _ = Self::shutdown_requested(&mut shutdown) => {}
+            }
+        });
+    }
+
+    /// Gives up on `interval` after its last failed attempt. A tracked
+    /// interval no longer holds back the sync coverage, so the backfill can
+    /// complete around it; `/status` lists it until it is requeued.
+    fn dead_letter(
+        logic: &SyndicaAppLogic,
+        coverage: &SyncCoverage,
+        interval: &SlotInterval,
+        error: &str,
+    ) {
+        warn!(
+            start = interval.start,
+            end = interval.end,
+            attempts = interval.attempts,
+            error,
+            "Interval failed too often, moved to dead letters"
+        );
+        if interval.tracked {
+            coverage.complete(interval);
+        }
+        logic.state().metrics().record_interval_dead_lettered();
+        logic.state().dead_letter(DeadLetteredInterval {
+            start: interval.start,
+            end: interval.end,
+            attempts: interval.attempts,
+            error: error.to_string(),
+        });
     }
 
     async fn process_interval(
         logic: &Arc<SyndicaAppLogic>,
         interval: &SlotInterval,
-    ) -> Result<Vec<SlotInterval>, Box<dyn std::error::Error + Send + Sync>> {
+        interval_size: u64,
+    ) -> Result<(usize, Vec<SlotInterval>), Box<dyn std::error::Error + Send + Sync>> {
         let confirmed_blocks = logic.get_blocks(interval.start, interval.end).await?;
         logic.query_slot_range(interval.start, interval.end).await?;
         let mut sub_intervals = Vec::new();
@@ -262,7 +1191,7 @@ impl Synchronizer {
                 let gap_start = current_pos;
                 let gap_end = confirmed_slot - 1;
                 let desired_end = std::cmp::min(
-                    std::cmp::max(gap_end, gap_start + INTERVAL_SIZE - 1),
+                    std::cmp::max(gap_end, gap_start + interval_size - 1),
                     interval.end,
                 );
                 sub_intervals.push(SlotInterval::new(gap_start, desired_end));
@@ -284,6 +1213,844 @@ impl Synchronizer {
             "Processed interval"
         );
 
-        Ok(sub_intervals)
+        Ok((confirmed_blocks.len(), sub_intervals))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::BlockCache;
+    use crate::metrics::{NoOpMetrics, PrometheusMetrics};
+    use crate::state::AppState;
+    use crate::testing::{FakeClient, test_logic};
+
+    #[test]
+    fn test_backoff_delay_grows_and_is_capped() {
+        let mut interval = SlotInterval::new(10, 20);
+        let mut delays = Vec::new();
+        for _ in 0..12 {
+            interval = interval.retry();
+            delays.push(interval.backoff_delay());
+        }
+
+        assert_eq!(delays[0], Duration::from_millis(REQUEUE_BASE_DELAY_MS));
+        assert_eq!(delays[1], Duration::from_millis(2 * REQUEUE_BASE_DELAY_MS));
+        assert!(delays.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert!(delays.windows(2).take(5).all(|pair| pair[1] > pair[0]));
+        assert_eq!(
+            *delays.last().unwrap(),
+            Duration::from_millis(REQUEUE_MAX_DELAY_MS)
+        );
+        assert_eq!(
+            (interval.start, interval.end, interval.attempts),
+            (10, 20, 12)
+        );
+    }
+
+    #[test]
+    fn test_worker_utilization_reports_once_per_period() {
+        let period = Duration::from_secs(10);
+        let mut utilization = WorkerUtilization::default();
+        assert_eq!(utilization.take_ratio(period), None);
+
+        utilization.add_busy(Duration::from_secs(3));
+        utilization.add_idle(Duration::from_secs(6));
+        assert_eq!(utilization.take_ratio(period), None);
+
+        utilization.add_idle(Duration::from_secs(3));
+        assert_eq!(utilization.take_ratio(period), Some(0.25));
+        assert_eq!(utilization.take_ratio(period), None);
+
+        utilization.add_busy(Duration::from_secs(10));
+        assert_eq!(utilization.take_ratio(period), Some(1.0));
+    }
+
+    #[test]
+    fn test_coverage_follows_oldest_pending_interval() {
+        let coverage = SyncCoverage::default();
+        let first = SlotInterval::new(100, 199).tracked();
+        let second = SlotInterval::new(200, 205).tracked();
+        coverage.track(&first);
+        coverage.track(&second);
+        assert_eq!(coverage.covered_slot(), 99);
+
+        coverage.complete(&second);
+        assert_eq!(coverage.covered_slot(), 99);
+
+        coverage.complete(&first.retry());
+        assert_eq!(coverage.covered_slot(), 205);
+        assert!(first.retry().tracked);
+        assert!(!SlotInterval::new(1, 2).tracked);
+    }
+
+    fn bounds(intervals: &[SlotInterval]) -> Vec<(u64, u64)> {
+        intervals
+            .iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    #[test]
+    fn test_window_defaults_to_full_depth() {
+        let mut window = SlotWindow::default();
+
+        assert_eq!(
+            bounds(&window.advance(10_000, 1_000, 1_000)),
+            vec![(9_000, 10_000)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_004, 1_000, 1_000)),
+            vec![(10_001, 10_004)]
+        );
+    }
+
+    #[test]
+    fn test_window_ramps_initial_backfill() {
+        let mut window = SlotWindow::default();
+
+        assert_eq!(
+            bounds(&window.advance(10_000, 1_000, 400)),
+            vec![(9_600, 10_000)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_002, 1_000, 400)),
+            vec![(10_001, 10_002), (9_200, 9_599)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_004, 1_000, 400)),
+            vec![(10_003, 10_004), (9_004, 9_199)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_006, 1_000, 400)),
+            vec![(10_005, 10_006)]
+        );
+    }
+
+    #[test]
+    fn test_window_follows_runtime_depth_change() {
+        let logic = test_logic(Arc::new(FakeClient::new(10_000))).with_monitoring_depth(1_000);
+        let mut window = SlotWindow::default();
+
+        assert_eq!(
+            bounds(&window.advance(10_000, logic.monitoring_depth(), 1_000)),
+            vec![(9_000, 10_000)]
+        );
+
+        // Deepening the window backfills the newly exposed history.
+        assert_eq!(logic.set_monitoring_depth(2_000), 2_000);
+        assert_eq!(logic.state().cache().capacity(), 2_000);
+        assert_eq!(
+            bounds(&window.advance(10_002, logic.monitoring_depth(), 1_000)),
+            vec![(10_001, 10_002), (8_002, 8_999)]
+        );
+
+        // Narrowing it only queues new slots.
+        logic.set_monitoring_depth(500);
+        assert_eq!(
+            bounds(&window.advance(10_004, logic.monitoring_depth(), 1_000)),
+            vec![(10_003, 10_004)]
+        );
+        assert_eq!(logic.state().cache().capacity(), 500);
+    }
+
+    #[test]
+    fn test_window_below_monitoring_depth_starts_at_slot_one() {
+        let mut window = SlotWindow::default();
+
+        assert_eq!(bounds(&window.advance(50, 1_000, 1_000)), vec![(1, 50)]);
+        // Nothing is left to backfill below slot 1.
+        assert_eq!(bounds(&window.advance(52, 1_000, 1_000)), vec![(51, 52)]);
+        assert_eq!(window.backfill_range(1_000), (1, 50));
+    }
+
+    #[tokio::test]
+    async fn test_tip_below_monitoring_depth() {
+        let client = Arc::new(FakeClient::new(50).with_confirmed(1..=60));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let (shutdown, receiver) = watch::channel(false);
+        let mut synchronizer = Synchronizer::new(logic.clone()).with_shutdown(receiver);
+        let run = tokio::spawn(async move { synchronizer.run().await });
+
+        wait_until(|| logic.state().cache().len() == 50).await;
+        client.set_tip(60);
+        wait_until(|| logic.state().cache().peek(60)).await;
+        shutdown.send(true).unwrap();
+        run.await.unwrap();
+
+        let ranges = client.requested_ranges();
+        assert!(ranges.iter().all(|(start, _)| *start >= 1), "{:?}", ranges);
+        let backfill = logic.state().backfill_progress().unwrap();
+        assert_eq!((backfill.from_slot, backfill.to_slot), (1, 50));
+        assert_eq!(backfill.completed_slots, backfill.total_slots);
+    }
+
+    #[test]
+    fn test_tip_only_window_never_backfills() {
+        let mut window = SlotWindow::new(SyncMode::TipOnly);
+
+        assert_eq!(
+            bounds(&window.advance(10_000, 1_000, 400)),
+            vec![(10_000, 10_000)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_003, 1_000, 400)),
+            vec![(10_001, 10_003)]
+        );
+        assert!(window.advance(10_003, 2_000, 400).is_empty());
+        assert_eq!(
+            bounds(&window.advance(10_004, 2_000, 400)),
+            vec![(10_004, 10_004)]
+        );
+    }
+
+    #[test]
+    fn test_window_backfills_forward_from_start_slot() {
+        let mut window = SlotWindow {
+            start_from: Some(8_000),
+            ..SlotWindow::default()
+        };
+
+        assert_eq!(
+            bounds(&window.advance(10_000, 1_000, 1_000)),
+            vec![(8_000, 8_999)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_002, 1_000, 1_000)),
+            vec![(10_001, 10_002), (9_000, 9_999)]
+        );
+        window.defer_backfill = true;
+        assert_eq!(
+            bounds(&window.advance(10_003, 1_000, 1_000)),
+            vec![(10_003, 10_003)]
+        );
+        window.defer_backfill = false;
+        assert_eq!(
+            bounds(&window.advance(10_004, 1_000, 1_000)),
+            vec![(10_004, 10_004), (10_000, 10_000)]
+        );
+        assert_eq!(
+            bounds(&window.advance(10_006, 1_000, 1_000)),
+            vec![(10_005, 10_006)]
+        );
+        assert_eq!(window.backfill_range(1_000), (8_000, 10_000));
+    }
+
+    #[test]
+    fn test_forward_backfill_progress_counts_from_start_slot() {
+        let mut window = SlotWindow {
+            start_from: Some(8_000),
+            ..SlotWindow::default()
+        };
+        let coverage = SyncCoverage::default();
+        let mut advance = |tip: u64| {
+            let intervals: Vec<_> = window
+                .advance(tip, 1_000, 1_500)
+                .into_iter()
+                .map(SlotInterval::tracked)
+                .collect();
+            for interval in &intervals {
+                coverage.track(interval);
+            }
+            (intervals, window.backfill_progress(&coverage, 1_000))
+        };
+
+        let (first, progress) = advance(10_000);
+        assert_eq!(bounds(&first), vec![(8_000, 9_499)]);
+        assert_eq!(progress, (0, 2_001));
+        coverage.complete(&first[0]);
+
+        let (second, progress) = advance(10_002);
+        assert_eq!(bounds(&second), vec![(10_001, 10_002), (9_500, 10_000)]);
+        assert_eq!(progress, (1_500, 2_001));
+        coverage.complete(&second[1]);
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (2_001, 2_001));
+    }
+
+    #[tokio::test]
+    async fn test_start_from_slot_backfills_forward_to_the_tip() {
+        let client = Arc::new(FakeClient::new(10_000).with_confirmed(4_000..=10_000));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let state = Arc::clone(logic.state());
+        let mut synchronizer = Synchronizer::new(logic)
+            .with_initial_backfill_depth(1_000)
+            .with_start_from_slot(5_000);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        wait_until(|| {
+            state
+                .backfill_progress()
+                .is_some_and(|progress| progress.completed_slots == progress.total_slots)
+        })
+        .await;
+        handle.abort();
+
+        assert_eq!(
+            state.backfill_progress(),
+            Some(BackfillProgress {
+                from_slot: 5_000,
+                to_slot: 10_000,
+                completed_slots: 5_001,
+                total_slots: 5_001,
+            })
+        );
+        let ranges = client.requested_ranges();
+        assert_eq!(ranges[0], (5_000, 5_999));
+        assert!(ranges.iter().all(|(start, _)| *start >= 5_000));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_history_spawns_no_workers() {
+        let client = Arc::new(FakeClient::new(10_000).with_confirmed([9_000, 10_000, 10_005]));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let state = Arc::clone(logic.state());
+        // Any worker would pop this right away.
+        state.interval_queue().push(SlotInterval::new(9_000, 9_000));
+        let mut synchronizer = Synchronizer::new(logic).with_history_disabled(true);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        wait_until(|| state.cache().contains(10_000)).await;
+        client.set_tip(10_005);
+        wait_until(|| state.cache().contains(10_005)).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.abort();
+
+        assert_eq!(state.interval_queue().len(), 1);
+        assert!(!state.cache().contains(9_000));
+        assert!(
+            client
+                .requested_ranges()
+                .iter()
+                .all(|(start, _)| *start >= 10_000)
+        );
+        assert_eq!(state.sync_lag(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tip_only_mode_requests_no_history() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10)
+                .with_sync_mode(SyncMode::TipOnly),
+        );
+        let mut synchronizer = Synchronizer::new(logic);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        let reached = |slot: u64| {
+            client
+                .requested_ranges()
+                .iter()
+                .any(|(_, end)| *end >= slot)
+        };
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !reached(10_000) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            client.set_tip(10_050);
+            while !reached(10_050) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        handle.abort();
+
+        let ranges = client.requested_ranges();
+        assert!(
+            ranges.iter().all(|(start, _)| *start >= 10_000),
+            "{:?}",
+            ranges
+        );
+    }
+
+    async fn wait_until(done: impl Fn() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !done() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn test_backpressure_has_hysteresis() {
+        let mut backpressure = Backpressure::new(10, 4);
+        let states: Vec<(bool, bool)> = [3, 9, 10, 12, 7, 5, 4, 9, 10]
+            .into_iter()
+            .map(|depth| (backpressure.observe(depth), backpressure.deferring))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (false, false),
+                (false, false),
+                (true, true),
+                (false, true),
+                (false, true),
+                (false, true),
+                (true, false),
+                (false, false),
+                (true, true),
+            ]
+        );
+
+        let mut disabled = Backpressure::new(0, 0);
+        assert!(!disabled.observe(1_000_000));
+        assert!(!disabled.deferring);
+    }
+
+    #[tokio::test]
+    async fn test_deep_queue_defers_backfill_but_not_the_tip() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let queue = Arc::clone(logic.state().interval_queue());
+        // A backlog nobody works off, already at the high-water mark.
+        for start in (0..50).step_by(10) {
+            queue.push(SlotInterval::new(start, start + 9));
+        }
+        let mut synchronizer = Synchronizer::new(logic)
+            .with_initial_backfill_depth(100)
+            .with_backpressure(5, 2);
+        let updater = synchronizer.spawn_slot_updater().await;
+
+        let queued = || -> Vec<(u64, u64)> {
+            queue
+                .snapshot(usize::MAX)
+                .iter()
+                .map(|interval| (interval.start, interval.end))
+                .collect()
+        };
+
+        wait_until(|| client.get_slot_calls() >= 5).await;
+        client.set_tip(10_050);
+        wait_until(|| queued().contains(&(10_001, 10_050))).await;
+        let backlog: Vec<(u64, u64)> = (0..50)
+            .step_by(10)
+            .map(|start| (start, start + 9))
+            .collect();
+        let mut expected = backlog.clone();
+        expected.extend([(9_900, 10_000), (10_001, 10_050)]);
+        assert_eq!(queued(), expected);
+
+        // Once drained to the low-water mark the next backfill step follows.
+        while queue.len() > 2 {
+            queue.pop();
+        }
+        wait_until(|| queued().contains(&(9_800, 9_899))).await;
+        updater.abort();
+    }
+
+    #[tokio::test]
+    async fn test_slot_confirmation_offset_holds_back_the_tip() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let mut synchronizer = Synchronizer::new(logic).with_slot_confirmation_offset(32);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        let reached = |slot: u64| {
+            client
+                .requested_ranges()
+                .iter()
+                .any(|(_, end)| *end >= slot)
+        };
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !reached(9_968) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            client.set_tip(10_050);
+            while !reached(10_018) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        // Give the updater a few more ticks at the new tip.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.abort();
+
+        let ranges = client.requested_ranges();
+        assert!(ranges.iter().all(|(_, end)| *end <= 10_018), "{:?}", ranges);
+    }
+
+    #[tokio::test]
+    async fn test_gap_sub_intervals_follow_interval_size() {
+        let client = Arc::new(FakeClient::new(1_000).with_confirmed((0..10).chain([50])));
+        let logic = Arc::new(test_logic(client));
+        let interval = SlotInterval::new(0, 99);
+
+        let (confirmed, sub_intervals) = Synchronizer::process_interval(&logic, &interval, 20)
+            .await
+            .unwrap();
+        assert_eq!(confirmed, 11);
+        // The gap before slot 50 already spans more than 20 slots, and the
+        // rest of the interval is queued from slot 50 on.
+        assert_eq!(bounds(&sub_intervals), vec![(10, 49), (50, 99)]);
+
+        let (_, sub_intervals) = Synchronizer::process_interval(&logic, &interval, 60)
+            .await
+            .unwrap();
+        // The gap is extended over slot 50 to 60 slots.
+        assert_eq!(bounds(&sub_intervals), vec![(10, 69), (70, 99)]);
+    }
+
+    #[tokio::test]
+    async fn test_slot_feed_replaces_polling() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let logic = Arc::new(
+            test_logic(client.clone())
+                .with_monitoring_depth(100)
+                .with_monitor_interval_ms(60_000),
+        );
+        let (feed, receiver) = watch::channel(None);
+        let mut synchronizer = Synchronizer::new(Arc::clone(&logic)).with_slot_feed(receiver);
+        tokio::spawn(async move { synchronizer.run().await });
+
+        // With a minute-long interval the workers barely poll the queue, so
+        // watch the slot updater itself.
+        let reached = |slot: u64| logic.state().last_processed_slot() >= slot;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !reached(10_000) {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            for slot in [10_010, 10_020] {
+                feed.send(Some(slot)).unwrap();
+                while !reached(slot) {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        // Only the startup poll; the pushed slots needed no getSlot call.
+        assert_eq!(client.get_slot_calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_polling_without_pushed_slots() {
+        let reaches_new_tip = |feed: Option<watch::Receiver<Option<u64>>>| async move {
+            let client = Arc::new(FakeClient::new(10_000));
+            let logic = Arc::new(
+                test_logic(client.clone())
+                    .with_monitoring_depth(100)
+                    .with_monitor_interval_ms(10),
+            );
+            let mut synchronizer = Synchronizer::new(logic);
+            if let Some(feed) = feed {
+                synchronizer = synchronizer.with_slot_feed(feed);
+            }
+            let handle = tokio::spawn(async move { synchronizer.run().await });
+
+            let reached = |slot: u64| {
+                client
+                    .requested_ranges()
+                    .iter()
+                    .any(|(_, end)| *end >= slot)
+            };
+            let result = tokio::time::timeout(Duration::from_secs(5), async {
+                while !reached(10_000) {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                client.set_tip(10_050);
+                while !reached(10_050) {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+            .await;
+            handle.abort();
+            result.is_ok()
+        };
+
+        // No feed configured.
+        assert!(reaches_new_tip(None).await);
+        // A feed that stays quiet, as when the subscription hangs.
+        let (quiet, receiver) = watch::channel(None);
+        assert!(reaches_new_tip(Some(receiver)).await);
+        // A feed whose subscription is disconnected.
+        let (disconnected, receiver) = watch::channel(Some(1));
+        disconnected.send(None).unwrap();
+        assert!(reaches_new_tip(Some(receiver)).await);
+        // A feed whose sender is gone.
+        let (closed, receiver) = watch::channel(None);
+        drop(closed);
+        assert!(reaches_new_tip(Some(receiver)).await);
+        drop(quiet);
+        drop(disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_all_tasks() {
+        for history_disabled in [false, true] {
+            let client = Arc::new(FakeClient::new(10_000).with_confirmed(9_950..=10_000));
+            let logic = Arc::new(
+                test_logic(client.clone())
+                    .with_monitoring_depth(1_000)
+                    .with_monitor_interval_ms(10),
+            );
+            let (shutdown, receiver) = watch::channel(false);
+            let mut synchronizer = Synchronizer::new(logic)
+                .with_history_disabled(history_disabled)
+                .with_shutdown(receiver);
+            let run = tokio::spawn(async move { synchronizer.run().await });
+
+            wait_until(|| client.get_blocks_calls() > 0).await;
+            shutdown.send(true).unwrap();
+            tokio::time::timeout(Duration::from_secs(5), run)
+                .await
+                .expect("synchronizer did not stop")
+                .unwrap();
+
+            // Nothing is left running to make further calls.
+            let calls = (client.get_slot_calls(), client.get_blocks_calls());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!((client.get_slot_calls(), client.get_blocks_calls()), calls);
+        }
+    }
+
+    #[test]
+    fn test_backfill_progress_counts_processed_window_slots() {
+        let mut window = SlotWindow::default();
+        let coverage = SyncCoverage::default();
+        let advance = |window: &mut SlotWindow, tip: u64| {
+            let intervals: Vec<_> = window
+                .advance(tip, 1_000, 400)
+                .into_iter()
+                .map(SlotInterval::tracked)
+                .collect();
+            for interval in &intervals {
+                coverage.track(interval);
+            }
+            intervals
+        };
+
+        let first = advance(&mut window, 10_000);
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (0, 1_001));
+        coverage.complete(&first[0]);
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (401, 1_001));
+
+        let second = advance(&mut window, 10_002);
+        // The window slid by two slots; the new tip interval is not backfill.
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (401, 999));
+        coverage.complete(&second[1]);
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (801, 999));
+
+        let third = advance(&mut window, 10_004);
+        coverage.complete(&third[1]);
+        assert_eq!(window.backfill_progress(&coverage, 1_000), (997, 997));
+    }
+
+    #[test]
+    fn test_queue_reports_depth_and_oldest_age() {
+        let metrics = Arc::new(PrometheusMetrics::new());
+        let queue = FifoIntervalQueue::new(metrics.clone());
+        let start = Instant::now();
+
+        for (offset, end) in [(0, 99), (1, 199), (2, 299)] {
+            queue.push_at(
+                SlotInterval::new(end - 99, end),
+                start + Duration::from_secs(offset),
+            );
+        }
+        let output = metrics.render();
+        assert!(output.contains("solana_block_monitor_interval_queue_depth 3"));
+        assert!(output.contains("solana_block_monitor_oldest_interval_age_seconds 2"));
+
+        let popped = queue.pop_at(start + Duration::from_secs(10)).unwrap();
+        assert_eq!(popped.end, 99);
+        let output = metrics.render();
+        assert!(output.contains("solana_block_monitor_interval_queue_depth 2"));
+        assert!(output.contains("solana_block_monitor_oldest_interval_age_seconds 9"));
+
+        queue.pop_at(start + Duration::from_secs(11));
+        queue.pop_at(start + Duration::from_secs(12));
+        assert!(queue.pop_at(start + Duration::from_secs(13)).is_none());
+        let output = metrics.render();
+        assert!(output.contains("solana_block_monitor_interval_queue_depth 0"));
+        assert!(output.contains("solana_block_monitor_oldest_interval_age_seconds 0"));
+    }
+
+    #[test]
+    fn test_pop_batch_merges_adjacent_and_overlapping() {
+        let queue = FifoIntervalQueue::new(Arc::new(NoOpMetrics));
+        queue.push(SlotInterval::new(100, 199).tracked());
+        queue.push(SlotInterval::new(500, 599));
+        queue.push(SlotInterval::new(200, 249).retry().retry());
+        queue.push(SlotInterval::new(50, 120));
+
+        let batch = queue.pop_batch(BATCH_MAX_SLOTS);
+        assert_eq!(bounds(&batch), vec![(100, 199), (200, 249), (50, 120)]);
+        let merged = SlotInterval::spanning(&batch);
+        assert_eq!((merged.start, merged.end, merged.attempts), (50, 249, 2));
+        assert!(!merged.tracked);
+        assert!(batch[0].tracked);
+
+        assert_eq!(bounds(&queue.pop_batch(BATCH_MAX_SLOTS)), vec![(500, 599)]);
+        assert!(queue.pop_batch(BATCH_MAX_SLOTS).is_empty());
+    }
+
+    #[test]
+    fn test_pop_batch_leaves_disjoint_intervals() {
+        let queue = FifoIntervalQueue::new(Arc::new(NoOpMetrics));
+        queue.push(SlotInterval::new(0, 99));
+        queue.push(SlotInterval::new(101, 199));
+        queue.push(SlotInterval::new(300, 399));
+
+        assert_eq!(bounds(&queue.pop_batch(BATCH_MAX_SLOTS)), vec![(0, 99)]);
+        assert_eq!(queue.len(), 2);
+
+        // Adjoining, but the merged range would exceed the slot limit.
+        queue.push(SlotInterval::new(400, 499));
+        assert_eq!(bounds(&queue.pop_batch(150)), vec![(101, 199)]);
+        assert_eq!(bounds(&queue.pop_batch(150)), vec![(300, 399)]);
+        assert_eq!(bounds(&queue.pop_batch(200)), vec![(400, 499)]);
+    }
+
+    /// Last in, first out, counting pops, to show the workers take their
+    /// intervals from whatever queue the state holds.
+    #[derive(Default)]
+    struct StackQueue {
+        entries: Mutex<Vec<SlotInterval>>,
+        pops: AtomicU64,
+    }
+
+    impl StackQueue {
+        fn entries(&self) -> MutexGuard<'_, Vec<SlotInterval>> {
+            self.entries
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+    }
+
+    impl IntervalQueue for StackQueue {
+        fn push(&self, interval: SlotInterval) {
+            self.entries().push(interval);
+        }
+
+        fn pop(&self) -> Option<SlotInterval> {
+            self.pops.fetch_add(1, Ordering::SeqCst);
+            self.entries().pop()
+        }
+
+        fn len(&self) -> usize {
+            self.entries().len()
+        }
+    }
+
+    #[test]
+    fn test_interval_queue_defaults() {
+        let queue = StackQueue::default();
+        assert!(queue.is_empty());
+        queue.push(SlotInterval::new(0, 99));
+        queue.push(SlotInterval::new(100, 199));
+
+        // Without its own batching only the next interval is popped.
+        assert_eq!(bounds(&queue.pop_batch(BATCH_MAX_SLOTS)), vec![(100, 199)]);
+        assert!(queue.snapshot(10).is_empty());
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_synchronizer_works_off_an_alternate_queue() {
+        let client = Arc::new(FakeClient::new(10_000));
+        let queue = Arc::new(StackQueue::default());
+        let state = AppState::new(
+            Arc::new(BlockCache::new(1_000)),
+            client.clone(),
+            Arc::new(NoOpMetrics),
+        )
+        .with_interval_queue(queue.clone());
+        let logic = Arc::new(
+            SyndicaAppLogic::new(Arc::new(state))
+                .with_monitoring_depth(1_000)
+                .with_monitor_interval_ms(10),
+        );
+        let mut synchronizer = Synchronizer::new(logic);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        wait_until(|| {
+            client
+                .requested_ranges()
+                .iter()
+                .any(|(_, end)| *end >= 10_000)
+        })
+        .await;
+        assert!(queue.pops.load(Ordering::SeqCst) > 0);
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_failing_interval_is_dead_lettered() {
+        let client = Arc::new(FakeClient::new(10_000));
+        client.set_failing(true);
+        let logic = Arc::new(test_logic(client.clone()).with_monitor_interval_ms(10));
+        let state = logic.state().clone();
+        state.interval_queue().push(SlotInterval::new(9_000, 9_099));
+        let mut synchronizer = Synchronizer::new(logic).with_max_attempts(3);
+        let handle = tokio::spawn(async move { synchronizer.run().await });
+
+        wait_until(|| !state.dead_letters().is_empty()).await;
+        let calls = client.get_blocks_calls();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        handle.abort();
+
+        assert_eq!(calls, 3);
+        assert_eq!(client.get_blocks_calls(), calls);
+        assert!(state.interval_queue().is_empty());
+        let dead_letters = state.dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert_eq!((dead_letters[0].start, dead_letters[0].end), (9_000, 9_099));
+        assert_eq!(dead_letters[0].attempts, 3);
+    }
+
+    #[tokio::test]
+    async fn test_requeue_after_waits_for_delay() {
+        let queue: Arc<dyn IntervalQueue> = Arc::new(FifoIntervalQueue::new(Arc::new(NoOpMetrics)));
+        let (shutdown, receiver) = watch::channel(false);
+
+        Synchronizer::requeue_after(
+            Arc::clone(&queue),
+            SlotInterval::new(1, 5).retry(),
+            Duration::from_millis(50),
+            receiver.clone(),
+        );
+
+        assert!(queue.pop().is_none());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let requeued = queue.pop().unwrap();
+        assert_eq!(requeued.attempts, 1);
+
+        // A pending requeue is dropped on shutdown.
+        Synchronizer::requeue_after(
+            Arc::clone(&queue),
+            SlotInterval::new(6, 10).retry(),
+            Duration::from_millis(50),
+            receiver,
+        );
+        shutdown.send(true).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(queue.pop().is_none());
     }
 }
@@ -1,32 +1,639 @@
+use futures_util::StreamExt;
+use serde::Serialize;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_sdk::commitment_config::CommitmentConfig;
+use solana_client::rpc_client::RpcClientConfig;
+use solana_client::rpc_request::RpcError;
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::signature::Signature;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{Semaphore, watch};
+use tracing::{Instrument, info, info_span, warn};
 
-use crate::types::BoxError;
+use crate::failover::EndpointStats;
+use crate::redact::{redacted_url, scrub};
+use crate::types::{BoxError, BoxFuture};
 
+/// JSON-RPC error codes returned when a slot has no block (skipped, pruned
+/// or not yet available) rather than because the request itself failed.
+const BLOCK_NOT_AVAILABLE: i64 = -32004;
+const SLOT_SKIPPED: i64 = -32007;
+const LONG_TERM_STORAGE_SLOT_SKIPPED: i64 = -32009;
+
+pub const DEFAULT_MAX_CONCURRENCY: usize = 10;
+
+/// Widest slot range a single `getBlocks` call may span.
+pub const MAX_GET_BLOCKS_RANGE: u64 = 500_000;
+
+/// JSON-RPC error code some providers use for "too many requests".
+const RATE_LIMITED: i64 = -32429;
+
+/// Delay before reconnecting a slot subscription, doubled after every
+/// attempt that fails to subscribe.
+const SLOT_SUBSCRIPTION_RETRY_DELAY: Duration = Duration::from_secs(1);
+const SLOT_SUBSCRIPTION_MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Cause of a failed RPC call, used to label error metrics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RpcErrorKind {
+    Timeout,
+    RateLimited,
+    Connection,
+    ServerError,
+    RpcError,
+    Other,
+}
+
+impl RpcErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcErrorKind::Timeout => "timeout",
+            RpcErrorKind::RateLimited => "rate_limited",
+            RpcErrorKind::Connection => "connection",
+            RpcErrorKind::ServerError => "server_error",
+            RpcErrorKind::RpcError => "rpc_error",
+            RpcErrorKind::Other => "other",
+        }
+    }
+
+    /// Classifies an error returned by a `SolanaRpc` call. Anything that is
+    /// not a `ClientError` is reported as `Other`.
+    pub fn classify(error: &BoxError) -> Self {
+        let client_error = error.downcast_ref::<ClientError>().or_else(|| {
+            error
+                .downcast_ref::<RpcClientError>()
+                .map(RpcClientError::client_error)
+        });
+        let Some(error) = client_error else {
+            return RpcErrorKind::Other;
+        };
+
+        match error.kind() {
+            ClientErrorKind::Reqwest(e) if e.is_timeout() => RpcErrorKind::Timeout,
+            ClientErrorKind::Reqwest(e) => match e.status() {
+                Some(reqwest::StatusCode::TOO_MANY_REQUESTS) => RpcErrorKind::RateLimited,
+                Some(status) if status.is_server_error() => RpcErrorKind::ServerError,
+                _ if e.is_connect() => RpcErrorKind::Connection,
+                _ => RpcErrorKind::Other,
+            },
+            ClientErrorKind::Io(_) => RpcErrorKind::Connection,
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if *code == RATE_LIMITED =>
+            {
+                RpcErrorKind::RateLimited
+            }
+            ClientErrorKind::RpcError(_) => RpcErrorKind::RpcError,
+            _ => RpcErrorKind::Other,
+        }
+    }
+}
+
+/// A failed RPC call, with the access key and the endpoint path removed
+/// from its message.
+///
+/// reqwest errors name the request URL, which ends with the key, so the
+/// raw `ClientError` would print it wherever it is logged. It is kept for
+/// classification but neither displayed nor exposed as a `source`.
+pub struct RpcClientError {
+    inner: ClientError,
+    message: String,
+}
+
+impl RpcClientError {
+    pub fn client_error(&self) -> &ClientError {
+        &self.inner
+    }
+}
+
+impl std::fmt::Display for RpcClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::fmt::Debug for RpcClientError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcClientError")
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl std::error::Error for RpcClientError {}
+
+/// RPC calls made per operation since startup, failed ones included, as
+/// reported by `/stats` to budget RPC usage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RpcCallCounts {
+    pub get_slot: u64,
+    pub get_blocks: u64,
+    pub get_block_time: u64,
+    pub get_signature_statuses: u64,
+    pub get_first_available_block: u64,
+}
+
+impl std::ops::AddAssign for RpcCallCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.get_slot += other.get_slot;
+        self.get_blocks += other.get_blocks;
+        self.get_block_time += other.get_block_time;
+        self.get_signature_statuses += other.get_signature_statuses;
+        self.get_first_available_block += other.get_first_available_block;
+    }
+}
+
+/// Commitment level every RPC request is made with.
+pub const RPC_COMMITMENT: CommitmentConfig = CommitmentConfig {
+    commitment: CommitmentLevel::Confirmed,
+};
+
+/// Matches the request timeout `HttpSender` uses for its own default client.
+const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// TLS settings for endpoints that are not covered by the system trust store.
+#[derive(Debug, Clone, Default)]
+pub struct TlsOptions {
+    /// PEM file with an additional root CA to trust, e.g. a private CA that
+    /// signed the RPC node's certificate.
+    pub ca_cert_path: Option<PathBuf>,
+    /// Accept any server certificate, including self-signed, expired and
+    /// wrong-host ones.
+    ///
+    /// SECURITY: this disables server authentication entirely. Anyone able
+    /// to intercept traffic to the RPC endpoint can impersonate it, read the
+    /// access key embedded in the URL and feed the monitor fabricated slot
+    /// data. Only use it against local test validators, never in production.
+    pub insecure: bool,
+}
+
+impl TlsOptions {
+    fn is_default(&self) -> bool {
+        self.ca_cert_path.is_none() && !self.insecure
+    }
+}
+
+/// The RPC operations the monitor relies on.
+///
+/// Methods return boxed futures so the trait stays object safe and can be
+/// shared as `Arc<dyn SolanaRpc>`, which lets tests substitute a fake client.
+pub trait SolanaRpc: Send + Sync {
+    fn get_slot(&self) -> BoxFuture<'_, Result<u64, BoxError>>;
+
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> BoxFuture<'_, Result<Vec<u64>, BoxError>>;
+
+    /// Returns the block time of `slot`, or `None` if the slot has no block.
+    fn get_block_time(&self, slot: u64) -> BoxFuture<'_, Result<Option<i64>, BoxError>>;
+
+    /// Returns the slot of the block that includes the transaction with
+    /// `signature`, or `None` if the endpoint does not know it.
+    fn get_slot_for_signature(
+        &self,
+        signature: Signature,
+    ) -> BoxFuture<'_, Result<Option<u64>, BoxError>>;
+
+    /// Returns the oldest slot the endpoint still has a block for; older
+    /// history has been purged from its ledger.
+    fn get_first_available_block(&self) -> BoxFuture<'_, Result<u64, BoxError>>;
+
+    /// Per-endpoint health for clients that spread calls over several
+    /// endpoints, empty for a single endpoint.
+    fn endpoint_stats(&self) -> Vec<EndpointStats> {
+        Vec::new()
+    }
+
+    /// Calls made so far per operation, all zero for clients that do not
+    /// count them.
+    fn call_counts(&self) -> RpcCallCounts {
+        RpcCallCounts::default()
+    }
+}
+
+/// RPC client for the Syndica endpoint.
+///
+/// Every call first acquires a permit from a shared semaphore, giving a hard
+/// ceiling on in-flight requests no matter how many workers and HTTP
+/// handlers are calling at once.
 pub struct SyndicaClient {
     rpc_client: RpcClient,
+    permits: Semaphore,
+    max_concurrency: usize,
+    connection_url: String,
+    key: String,
+    get_slot_calls: AtomicU64,
+    get_blocks_calls: AtomicU64,
+    get_block_time_calls: AtomicU64,
+    get_signature_statuses_calls: AtomicU64,
+    get_first_available_block_calls: AtomicU64,
 }
 
 impl SyndicaClient {
     pub fn new(rpc_url: String, key: String) -> Self {
-        let connection_url = format!("{}/{}", rpc_url, key);
+        let connection_url = Self::connection_url(rpc_url, &key);
+        let rpc_client = RpcClient::new_with_commitment(connection_url.clone(), RPC_COMMITMENT);
+        Self::from_rpc_client(rpc_client, connection_url, key)
+    }
+
+    /// Like `new`, but sends requests through an HTTP client configured with
+    /// `tls`. Fails if the CA certificate cannot be read or parsed.
+    pub fn new_with_tls(rpc_url: String, key: String, tls: &TlsOptions) -> Result<Self, BoxError> {
+        if tls.is_default() {
+            return Ok(Self::new(rpc_url, key));
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(RPC_REQUEST_TIMEOUT)
+            .pool_idle_timeout(RPC_REQUEST_TIMEOUT);
+        if let Some(path) = &tls.ca_cert_path {
+            let pem = std::fs::read(path).map_err(|e| {
+                format!(
+                    "failed to read RPC CA certificate {}: {}",
+                    path.display(),
+                    e
+                )
+            })?;
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+        }
+        if tls.insecure {
+            warn!(
+                "RPC_INSECURE is enabled: TLS certificates of the RPC endpoint are NOT verified. \
+                 Traffic, including the access key, can be intercepted. Never use this in production."
+            );
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+
+        let connection_url = Self::connection_url(rpc_url, &key);
+        let sender = HttpSender::new_with_client(connection_url.clone(), builder.build()?);
         let rpc_client =
-            RpcClient::new_with_commitment(connection_url, CommitmentConfig::confirmed());
-        Self { rpc_client }
+            RpcClient::new_sender(sender, RpcClientConfig::with_commitment(RPC_COMMITMENT));
+        Ok(Self::from_rpc_client(rpc_client, connection_url, key))
+    }
+
+    /// Appends the access key as the last path segment. An empty key leaves
+    /// the URL alone, for URLs that already carry their access token.
+    fn connection_url(rpc_url: String, key: &str) -> String {
+        if key.is_empty() {
+            rpc_url
+        } else {
+            format!("{}/{}", rpc_url, key)
+        }
+    }
+
+    fn from_rpc_client(rpc_client: RpcClient, connection_url: String, key: String) -> Self {
+        Self {
+            rpc_client,
+            permits: Semaphore::new(DEFAULT_MAX_CONCURRENCY),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            connection_url,
+            key,
+            get_slot_calls: AtomicU64::new(0),
+            get_blocks_calls: AtomicU64::new(0),
+            get_block_time_calls: AtomicU64::new(0),
+            get_signature_statuses_calls: AtomicU64::new(0),
+            get_first_available_block_calls: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        let max_concurrency = max_concurrency.max(1);
+        self.permits = Semaphore::new(max_concurrency);
+        self.max_concurrency = max_concurrency;
+        self
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Subscribes to `slotSubscribe` notifications at `ws_url`, with `key`
+    /// appended as for the HTTP endpoint.
+    ///
+    /// The returned receiver holds the latest notified slot, at `processed`
+    /// commitment, and `None` until subscribed or while reconnecting. The
+    /// subscription is reopened whenever it fails or ends, and the task
+    /// ends once every receiver is dropped.
+    pub fn subscribe_slots(ws_url: String, key: &str) -> watch::Receiver<Option<u64>> {
+        let connection_url = Self::connection_url(ws_url, key);
+        let key = key.to_string();
+        let (sender, receiver) = watch::channel(None);
+        tokio::spawn(async move {
+            let url = redacted_url(&connection_url);
+            let mut retry_delay = SLOT_SUBSCRIPTION_RETRY_DELAY;
+            while !sender.is_closed() {
+                match Self::forward_slots(&connection_url, &sender).await {
+                    Ok(()) => {
+                        warn!(url = %url, "Slot subscription ended, reconnecting");
+                        retry_delay = SLOT_SUBSCRIPTION_RETRY_DELAY;
+                    }
+                    Err(e) => {
+                        let error = e.to_string().replace(&connection_url, &url);
+                        warn!(
+                            url = %url,
+                            error = %scrub(&error, &key),
+                            retry_in_ms = retry_delay.as_millis() as u64,
+                            "Slot subscription failed"
+                        );
+                    }
+                }
+                sender.send_replace(None);
+                tokio::time::sleep(retry_delay).await;
+                retry_delay = (retry_delay * 2).min(SLOT_SUBSCRIPTION_MAX_RETRY_DELAY);
+            }
+        });
+        receiver
+    }
+
+    /// Sends every notified slot to `sender` until the subscription ends or
+    /// nobody listens any more.
+    async fn forward_slots(
+        connection_url: &str,
+        sender: &watch::Sender<Option<u64>>,
+    ) -> Result<(), BoxError> {
+        let client = PubsubClient::new(connection_url).await?;
+        let (mut notifications, unsubscribe) = client.slot_subscribe().await?;
+        info!(url = %redacted_url(connection_url), "Subscribed to slot notifications");
+        while let Some(notification) = notifications.next().await {
+            if sender.send(Some(notification.slot)).is_err() {
+                break;
+            }
+        }
+        unsubscribe().await;
+        Ok(())
+    }
+
+    /// Wraps `error` so its message shows the endpoint as `redacted_url`
+    /// and never contains the key.
+    fn scrubbed(&self, error: ClientError) -> BoxError {
+        let message = error
+            .to_string()
+            .replace(&self.connection_url, &redacted_url(&self.connection_url));
+        Box::new(RpcClientError {
+            message: scrub(&message, &self.key),
+            inner: error,
+        })
+    }
+
+    fn is_missing_block(error: &ClientError) -> bool {
+        matches!(
+            error.kind(),
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                if matches!(*code, BLOCK_NOT_AVAILABLE | SLOT_SKIPPED | LONG_TERM_STORAGE_SLOT_SKIPPED)
+        )
     }
 }
 
-impl SyndicaClient {
-    pub async fn get_slot(&self) -> Result<u64, BoxError> {
-        let slot = self.rpc_client.get_slot().await?;
-        Ok(slot)
+impl SolanaRpc for SyndicaClient {
+    fn get_slot(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        let span = info_span!("rpc", rpc.system = "solana", rpc.method = "getSlot");
+        Box::pin(
+            async move {
+                self.get_slot_calls.fetch_add(1, Ordering::Relaxed);
+                let _permit = self.permits.acquire().await?;
+                let slot = self
+                    .rpc_client
+                    .get_slot()
+                    .await
+                    .map_err(|e| self.scrubbed(e))?;
+                Ok(slot)
+            }
+            .instrument(span),
+        )
+    }
+
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> BoxFuture<'_, Result<Vec<u64>, BoxError>> {
+        let span = info_span!(
+            "rpc",
+            rpc.system = "solana",
+            rpc.method = "getBlocks",
+            start_slot,
+            end_slot
+        );
+        Box::pin(
+            async move {
+                self.get_blocks_calls.fetch_add(1, Ordering::Relaxed);
+                let _permit = self.permits.acquire().await?;
+                let blocks = self
+                    .rpc_client
+                    .get_blocks(start_slot, Some(end_slot))
+                    .await
+                    .map_err(|e| self.scrubbed(e))?;
+                Ok(blocks)
+            }
+            .instrument(span),
+        )
+    }
+
+    fn get_block_time(&self, slot: u64) -> BoxFuture<'_, Result<Option<i64>, BoxError>> {
+        let span = info_span!(
+            "rpc",
+            rpc.system = "solana",
+            rpc.method = "getBlockTime",
+            slot
+        );
+        Box::pin(
+            async move {
+                self.get_block_time_calls.fetch_add(1, Ordering::Relaxed);
+                let _permit = self.permits.acquire().await?;
+                match self.rpc_client.get_block_time(slot).await {
+                    Ok(block_time) => Ok(Some(block_time)),
+                    Err(e) if Self::is_missing_block(&e) => Ok(None),
+                    Err(e) => Err(self.scrubbed(e)),
+                }
+            }
+            .instrument(span),
+        )
+    }
+
+    /// Searches the whole transaction history, not just the recent status
+    /// cache, so signatures of older transactions resolve too.
+    fn get_slot_for_signature(
+        &self,
+        signature: Signature,
+    ) -> BoxFuture<'_, Result<Option<u64>, BoxError>> {
+        let span = info_span!(
+            "rpc",
+            rpc.system = "solana",
+            rpc.method = "getSignatureStatuses"
+        );
+        Box::pin(
+            async move {
+                self.get_signature_statuses_calls
+                    .fetch_add(1, Ordering::Relaxed);
+                let _permit = self.permits.acquire().await?;
+                let statuses = self
+                    .rpc_client
+                    .get_signature_statuses_with_history(&[signature])
+                    .await
+                    .map_err(|e| self.scrubbed(e))?;
+                Ok(statuses
+                    .value
+                    .into_iter()
+                    .next()
+                    .flatten()
+                    .map(|status| status.slot))
+            }
+            .instrument(span),
+        )
     }
 
-    pub async fn get_blocks(&self, start_slot: u64, end_slot: u64) -> Result<Vec<u64>, BoxError> {
-        let blocks = self
-            .rpc_client
-            .get_blocks(start_slot, Some(end_slot))
-            .await?;
-        Ok(blocks)
+    fn get_first_available_block(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        let span = info_span!(
+            "rpc",
+            rpc.system = "solana",
+            rpc.method = "getFirstAvailableBlock"
+        );
+        Box::pin(
+            async move {
+                self.get_first_available_block_calls
+                    .fetch_add(1, Ordering::Relaxed);
+                let _permit = self.permits.acquire().await?;
+                let slot = self
+                    .rpc_client
+                    .get_first_available_block()
+                    .await
+                    .map_err(|e| self.scrubbed(e))?;
+                Ok(slot)
+            }
+            .instrument(span),
+        )
+    }
+
+    fn call_counts(&self) -> RpcCallCounts {
+        RpcCallCounts {
+            get_slot: self.get_slot_calls.load(Ordering::Relaxed),
+            get_blocks: self.get_blocks_calls.load(Ordering::Relaxed),
+            get_block_time: self.get_block_time_calls.load(Ordering::Relaxed),
+            get_signature_statuses: self.get_signature_statuses_calls.load(Ordering::Relaxed),
+            get_first_available_block: self.get_first_available_block_calls.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{MockRpcServer, status_client_error, timeout_client_error};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrency_is_bounded() {
+        let server = MockRpcServer::spawn(serde_json::json!(42), Duration::from_millis(50)).await;
+        let client =
+            Arc::new(SyndicaClient::new(server.url(), "key".to_string()).with_max_concurrency(2));
+
+        let calls: Vec<_> = (0..8)
+            .map(|_| {
+                let client = Arc::clone(&client);
+                tokio::spawn(async move { client.get_slot().await.unwrap() })
+            })
+            .collect();
+        for call in calls {
+            assert_eq!(call.await.unwrap(), 42);
+        }
+
+        assert_eq!(server.requests(), 8);
+        assert!(server.max_in_flight() <= 2);
+        assert_eq!(client.max_concurrency(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_call_counts_per_operation() {
+        let server = MockRpcServer::spawn(serde_json::json!(42), Duration::ZERO).await;
+        let client = SyndicaClient::new(server.url(), "key".to_string());
+        assert_eq!(client.call_counts(), RpcCallCounts::default());
+
+        for _ in 0..3 {
+            client.get_slot().await.unwrap();
+        }
+        // A number is not a block list: failed calls are counted too.
+        for _ in 0..2 {
+            assert!(client.get_blocks(1, 10).await.is_err());
+        }
+        assert_eq!(client.get_block_time(7).await.unwrap(), Some(42));
+        assert_eq!(client.get_first_available_block().await.unwrap(), 42);
+
+        assert_eq!(
+            client.call_counts(),
+            RpcCallCounts {
+                get_slot: 3,
+                get_blocks: 2,
+                get_block_time: 1,
+                get_signature_statuses: 0,
+                get_first_available_block: 1,
+            }
+        );
+        assert_eq!(server.requests(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_classify_errors() {
+        let timeout: BoxError = timeout_client_error().await.into();
+        assert_eq!(RpcErrorKind::classify(&timeout), RpcErrorKind::Timeout);
+
+        let rate_limited: BoxError = status_client_error(429).await.into();
+        assert_eq!(
+            RpcErrorKind::classify(&rate_limited),
+            RpcErrorKind::RateLimited
+        );
+
+        let unavailable: BoxError = status_client_error(503).await.into();
+        assert_eq!(
+            RpcErrorKind::classify(&unavailable),
+            RpcErrorKind::ServerError
+        );
+
+        let other: BoxError = "boom".into();
+        assert_eq!(RpcErrorKind::classify(&other), RpcErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn test_errors_never_contain_the_key() {
+        let server = MockRpcServer::spawn_with_status(
+            serde_json::json!(0),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            Duration::ZERO,
+        )
+        .await;
+        let client = SyndicaClient::new(server.url(), "super-secret-key".to_string());
+
+        let error = client.get_slot().await.unwrap_err();
+        assert_eq!(RpcErrorKind::classify(&error), RpcErrorKind::ServerError);
+        for formatted in [error.to_string(), format!("{:?}", error)] {
+            assert!(!formatted.contains("super-secret-key"), "{}", formatted);
+        }
+        assert!(error.to_string().contains("503"), "{}", error);
+        assert!(error.to_string().contains("<redacted>"), "{}", error);
+
+        let error = client.get_blocks(1, 10).await.unwrap_err();
+        assert!(!format!("{:?}", error).contains("super-secret-key"));
+    }
+
+    #[tokio::test]
+    async fn test_tls_options() {
+        let missing = TlsOptions {
+            ca_cert_path: Some(PathBuf::from("does-not-exist.pem")),
+            insecure: false,
+        };
+        assert!(SyndicaClient::new_with_tls("https://rpc".into(), "key".into(), &missing).is_err());
+
+        let server = MockRpcServer::spawn(serde_json::json!(7), Duration::ZERO).await;
+        let insecure = TlsOptions {
+            ca_cert_path: None,
+            insecure: true,
+        };
+        let client = SyndicaClient::new_with_tls(server.url(), "key".into(), &insecure).unwrap();
+        assert_eq!(client.get_slot().await.unwrap(), 7);
     }
 }
@@ -0,0 +1,424 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde_json::{Value, json};
+use solana_client::client_error::ClientError;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::cache::BlockCache;
+use crate::logic::SyndicaAppLogic;
+use crate::metrics::{Metrics, NoOpMetrics};
+use crate::state::AppState;
+use crate::syndica_client::{RpcCallCounts, SolanaRpc};
+use crate::types::{BoxError, BoxFuture};
+
+/// Unix timestamp reported by `FakeClient` for slot 0.
+pub const FAKE_GENESIS_TIME: i64 = 1_700_000_000;
+
+/// An in-memory stand-in for the RPC endpoint used by unit tests.
+///
+/// The chain tip and the set of confirmed slots are set up front and can be
+/// changed while a test runs. Every call is counted, and the client can be
+/// switched into a failing mode to simulate an unavailable endpoint.
+#[derive(Default)]
+pub struct FakeClient {
+    tip: AtomicU64,
+    confirmed: Mutex<BTreeSet<u64>>,
+    failing: AtomicBool,
+    next_error: Mutex<Option<BoxError>>,
+    failures_remaining: AtomicU64,
+    slots: Mutex<VecDeque<u64>>,
+    get_slot_calls: AtomicU64,
+    get_blocks_calls: AtomicU64,
+    get_block_time_calls: AtomicU64,
+    get_signature_statuses_calls: AtomicU64,
+    get_first_available_block_calls: AtomicU64,
+    signatures: Mutex<HashMap<Signature, u64>>,
+    requested_ranges: Mutex<Vec<(u64, u64)>>,
+    latency_ms: AtomicU64,
+    first_available_block: AtomicU64,
+}
+
+impl FakeClient {
+    pub fn new(tip: u64) -> Self {
+        let client = Self::default();
+        client.set_tip(tip);
+        client
+    }
+
+    pub fn with_confirmed(self, slots: impl IntoIterator<Item = u64>) -> Self {
+        self.confirmed.lock().unwrap().extend(slots);
+        self
+    }
+
+    /// Slots below `slot` read as purged from the ledger.
+    pub fn set_first_available_block(&self, slot: u64) {
+        self.first_available_block.store(slot, Ordering::SeqCst);
+    }
+
+    /// Makes `signature` resolve to a transaction included in `slot`.
+    pub fn with_signature(self, signature: Signature, slot: u64) -> Self {
+        self.signatures.lock().unwrap().insert(signature, slot);
+        self
+    }
+
+    pub fn set_tip(&self, tip: u64) {
+        self.tip.store(tip, Ordering::SeqCst);
+    }
+
+    /// Makes the next `get_slot` calls return `slots`, in order, before
+    /// going back to the tip.
+    pub fn push_slots(&self, slots: impl IntoIterator<Item = u64>) {
+        self.slots.lock().unwrap().extend(slots);
+    }
+
+    pub fn confirm(&self, slot: u64) {
+        self.confirmed.lock().unwrap().insert(slot);
+    }
+
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    /// Makes every call take `latency` before it answers.
+    pub fn set_latency(&self, latency: Duration) {
+        self.latency_ms
+            .store(latency.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// Makes the next call, whichever method it is, fail with `error`.
+    pub fn fail_next(&self, error: BoxError) {
+        *self.next_error.lock().unwrap() = Some(error);
+    }
+
+    /// Makes the next `count` calls fail, then recovers on its own.
+    pub fn fail_next_calls(&self, count: u64) {
+        self.failures_remaining.store(count, Ordering::SeqCst);
+    }
+
+    pub fn block_time(slot: u64) -> i64 {
+        FAKE_GENESIS_TIME + (slot * 400 / 1000) as i64
+    }
+
+    pub fn get_slot_calls(&self) -> u64 {
+        self.get_slot_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn get_blocks_calls(&self) -> u64 {
+        self.get_blocks_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn get_block_time_calls(&self) -> u64 {
+        self.get_block_time_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn get_signature_statuses_calls(&self) -> u64 {
+        self.get_signature_statuses_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn get_first_available_block_calls(&self) -> u64 {
+        self.get_first_available_block_calls.load(Ordering::SeqCst)
+    }
+
+    pub fn requested_ranges(&self) -> Vec<(u64, u64)> {
+        self.requested_ranges.lock().unwrap().clone()
+    }
+
+    async fn respond_after_latency(&self) {
+        let latency_ms = self.latency_ms.load(Ordering::SeqCst);
+        if latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+        }
+    }
+
+    fn check_failing(&self) -> Result<(), BoxError> {
+        if let Some(error) = self.next_error.lock().unwrap().take() {
+            return Err(error);
+        }
+        let counted_failure = self
+            .failures_remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |remaining| {
+                remaining.checked_sub(1)
+            })
+            .is_ok();
+        if counted_failure || self.failing.load(Ordering::SeqCst) {
+            Err("fake rpc failure".into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SolanaRpc for FakeClient {
+    fn get_slot(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        Box::pin(async move {
+            self.get_slot_calls.fetch_add(1, Ordering::SeqCst);
+            self.respond_after_latency().await;
+            self.check_failing()?;
+            let queued = self.slots.lock().unwrap().pop_front();
+            Ok(queued.unwrap_or_else(|| self.tip.load(Ordering::SeqCst)))
+        })
+    }
+
+    fn get_blocks(
+        &self,
+        start_slot: u64,
+        end_slot: u64,
+    ) -> BoxFuture<'_, Result<Vec<u64>, BoxError>> {
+        Box::pin(async move {
+            self.get_blocks_calls.fetch_add(1, Ordering::SeqCst);
+            self.requested_ranges
+                .lock()
+                .unwrap()
+                .push((start_slot, end_slot));
+            self.respond_after_latency().await;
+            self.check_failing()?;
+            let confirmed = self.confirmed.lock().unwrap();
+            Ok(confirmed.range(start_slot..=end_slot).copied().collect())
+        })
+    }
+
+    fn get_block_time(&self, slot: u64) -> BoxFuture<'_, Result<Option<i64>, BoxError>> {
+        Box::pin(async move {
+            self.get_block_time_calls.fetch_add(1, Ordering::SeqCst);
+            self.respond_after_latency().await;
+            self.check_failing()?;
+            let confirmed = self.confirmed.lock().unwrap().contains(&slot);
+            Ok(confirmed.then(|| Self::block_time(slot)))
+        })
+    }
+
+    fn get_slot_for_signature(
+        &self,
+        signature: Signature,
+    ) -> BoxFuture<'_, Result<Option<u64>, BoxError>> {
+        Box::pin(async move {
+            self.get_signature_statuses_calls
+                .fetch_add(1, Ordering::SeqCst);
+            self.respond_after_latency().await;
+            self.check_failing()?;
+            Ok(self.signatures.lock().unwrap().get(&signature).copied())
+        })
+    }
+
+    fn get_first_available_block(&self) -> BoxFuture<'_, Result<u64, BoxError>> {
+        Box::pin(async move {
+            self.get_first_available_block_calls
+                .fetch_add(1, Ordering::SeqCst);
+            self.respond_after_latency().await;
+            self.check_failing()?;
+            Ok(self.first_available_block.load(Ordering::SeqCst))
+        })
+    }
+
+    fn call_counts(&self) -> RpcCallCounts {
+        RpcCallCounts {
+            get_slot: self.get_slot_calls(),
+            get_blocks: self.get_blocks_calls(),
+            get_block_time: self.get_block_time_calls(),
+            get_signature_statuses: self.get_signature_statuses_calls(),
+            get_first_available_block: self.get_first_available_block_calls(),
+        }
+    }
+}
+
+pub fn test_state(client: Arc<FakeClient>, cache_capacity: usize) -> Arc<AppState> {
+    test_state_with_metrics(client, cache_capacity, Arc::new(NoOpMetrics))
+}
+
+pub fn test_state_with_metrics(
+    client: Arc<FakeClient>,
+    cache_capacity: usize,
+    metrics: Arc<dyn Metrics + Send + Sync>,
+) -> Arc<AppState> {
+    Arc::new(AppState::new(
+        Arc::new(BlockCache::new(cache_capacity)),
+        client,
+        metrics,
+    ))
+}
+
+/// Issues a plain HTTP/1.1 GET and returns the status code and body.
+pub async fn http_get(addr: std::net::SocketAddr, path: &str) -> (u16, String) {
+    http_get_with_headers(addr, path, &[]).await
+}
+
+pub async fn http_get_with_headers(
+    addr: std::net::SocketAddr,
+    path: &str,
+    headers: &[(&str, &str)],
+) -> (u16, String) {
+    http_request(addr, "GET", path, headers, "").await
+}
+
+/// POSTs a JSON `body`.
+pub async fn http_post_with_headers(
+    addr: std::net::SocketAddr,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> (u16, String) {
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/json"));
+    http_request(addr, "POST", path, &headers, body).await
+}
+
+async fn http_request(
+    addr: std::net::SocketAddr,
+    method: &str,
+    path: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> (u16, String) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\n", method, path, addr);
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    ));
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    let status = response
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .unwrap_or_default();
+    let body = response
+        .split_once("\r\n\r\n")
+        .map(|(_, body)| body.to_string())
+        .unwrap_or_default();
+    (status, body)
+}
+
+pub fn test_logic(client: Arc<FakeClient>) -> SyndicaAppLogic {
+    SyndicaAppLogic::new(test_state(client, 1000))
+}
+
+/// A minimal JSON-RPC server answering every request with a fixed result
+/// after a delay, tracking how many requests were in flight at once.
+pub struct MockRpcServer {
+    addr: std::net::SocketAddr,
+    result: Value,
+    status: StatusCode,
+    delay: Duration,
+    requests: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+impl MockRpcServer {
+    pub async fn spawn(result: Value, delay: Duration) -> Arc<Self> {
+        Self::spawn_with_status(result, StatusCode::OK, delay).await
+    }
+
+    /// Like `spawn`, but answers every request with `status`; non-200
+    /// responses have an empty body.
+    pub async fn spawn_with_status(
+        result: Value,
+        status: StatusCode,
+        delay: Duration,
+    ) -> Arc<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = Arc::new(Self {
+            addr: listener.local_addr().unwrap(),
+            result,
+            status,
+            delay,
+            requests: AtomicUsize::new(0),
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+        });
+
+        let app = Router::new()
+            .fallback(Self::handle)
+            .with_state(Arc::clone(&server));
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        server
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    pub fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+
+    async fn handle(State(server): State<Arc<Self>>, Json(request): Json<Value>) -> Response {
+        server.requests.fetch_add(1, Ordering::SeqCst);
+        let in_flight = server.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        server.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+        tokio::time::sleep(server.delay).await;
+
+        server.in_flight.fetch_sub(1, Ordering::SeqCst);
+        if server.status != StatusCode::OK {
+            return server.status.into_response();
+        }
+        Json(json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": server.result,
+        }))
+        .into_response()
+    }
+}
+
+async fn post_json_rpc(
+    server: &MockRpcServer,
+    timeout: Duration,
+) -> reqwest::Result<reqwest::Response> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .unwrap()
+        .post(server.url())
+        .header("content-type", "application/json")
+        .body(r#"{"jsonrpc":"2.0","id":1,"method":"getSlot"}"#)
+        .send()
+        .await
+}
+
+/// A real `ClientError` for a request that exceeded its timeout.
+pub async fn timeout_client_error() -> ClientError {
+    let server = MockRpcServer::spawn(json!(0), Duration::from_secs(5)).await;
+    post_json_rpc(&server, Duration::from_millis(20))
+        .await
+        .unwrap_err()
+        .into()
+}
+
+/// A real `ClientError` for an HTTP response with `status`.
+pub async fn status_client_error(status: u16) -> ClientError {
+    let status = StatusCode::from_u16(status).unwrap();
+    let server = MockRpcServer::spawn_with_status(json!(0), status, Duration::ZERO).await;
+    post_json_rpc(&server, Duration::from_secs(5))
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap_err()
+        .into()
+}
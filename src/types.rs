@@ -1 +1,5 @@
+use std::future::Future;
+use std::pin::Pin;
+
 pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -0,0 +1,265 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::types::BoxError;
+
+/// Confirmations waiting for delivery before new ones are dead-lettered.
+pub const DEFAULT_WEBHOOK_BUFFER: usize = 1024;
+/// Deliveries tried per confirmation, the first one included.
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Payload POSTed for every newly confirmed slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SlotConfirmed {
+    pub slot: u64,
+    /// Unix seconds, estimated from the latest known block time rather than
+    /// fetched for this slot, so it drifts when slots run slower than
+    /// nominal. `None` until a block time has been seen.
+    pub block_time: Option<i64>,
+}
+
+/// Queues confirmations for the delivery task without ever waiting on it.
+#[derive(Debug, Clone)]
+pub struct WebhookSender {
+    queue: mpsc::Sender<SlotConfirmed>,
+}
+
+impl WebhookSender {
+    /// A sender and the receiving end to hand to `WebhookDispatcher::new`.
+    /// At most `buffer` confirmations wait for delivery.
+    pub fn channel(buffer: usize) -> (Self, mpsc::Receiver<SlotConfirmed>) {
+        let (queue, receiver) = mpsc::channel(buffer.max(1));
+        (Self { queue }, receiver)
+    }
+
+    /// Queues `event`, dead-lettering it when the queue is full so a slow
+    /// endpoint cannot hold up synchronization.
+    pub fn notify(&self, event: SlotConfirmed) {
+        if let Err(e) = self.queue.try_send(event) {
+            error!(
+                target: "webhook::dead_letter",
+                slot = event.slot,
+                block_time = event.block_time,
+                reason = %e,
+                "Dropped webhook event"
+            );
+        }
+    }
+}
+
+/// Delivers queued confirmations to `WEBHOOK_URL` one at a time, retrying
+/// failures with exponential backoff. Events still failing after the last
+/// attempt are logged on the `webhook::dead_letter` target and dropped.
+pub struct WebhookDispatcher {
+    url: String,
+    client: reqwest::Client,
+    receiver: mpsc::Receiver<SlotConfirmed>,
+    max_attempts: u32,
+    retry_base_delay: Duration,
+}
+
+impl WebhookDispatcher {
+    pub fn new(url: String, receiver: mpsc::Receiver<SlotConfirmed>) -> Result<Self, BoxError> {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()?;
+        Ok(Self {
+            url,
+            client,
+            receiver,
+            max_attempts: DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+            retry_base_delay: RETRY_BASE_DELAY,
+        })
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry; it doubles after every further failure.
+    pub fn with_retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    /// Delivers until every `WebhookSender` is dropped.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(event) = self.receiver.recv().await {
+                self.deliver(event).await;
+            }
+        })
+    }
+
+    async fn deliver(&self, event: SlotConfirmed) {
+        let mut delay = self.retry_base_delay;
+        for attempt in 1..=self.max_attempts {
+            match self.post(&event).await {
+                Ok(()) => {
+                    debug!(slot = event.slot, attempt, "Delivered webhook event");
+                    return;
+                }
+                Err(e) if attempt < self.max_attempts => {
+                    warn!(slot = event.slot, attempt, error = %e, "Webhook delivery failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                }
+                Err(e) => error!(
+                    target: "webhook::dead_letter",
+                    slot = event.slot,
+                    block_time = event.block_time,
+                    attempts = attempt,
+                    error = %e,
+                    "Webhook delivery failed, dropping event"
+                ),
+            }
+        }
+    }
+
+    async fn post(&self, event: &SlotConfirmed) -> Result<(), BoxError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .body(serde_json::to_string(event)?)
+            .send()
+            .await
+            // reqwest errors name the URL, which may carry a token.
+            .map_err(|e| e.without_url())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("webhook returned status {}", response.status()).into())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, extract::State, http::StatusCode, routing::post};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<Value>>>;
+
+    /// Answers `500` to the first `failures` requests and `200` afterwards,
+    /// recording every body it receives.
+    async fn spawn_endpoint(failures: usize) -> (String, Received) {
+        async fn receive(
+            State((failures, received)): State<(usize, Received)>,
+            body: String,
+        ) -> StatusCode {
+            let mut received = received.lock().unwrap();
+            received.push(serde_json::from_str(&body).unwrap());
+            if received.len() <= failures {
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::OK
+            }
+        }
+
+        let received = Received::default();
+        let app = Router::new().route(
+            "/hook",
+            post(receive).with_state((failures, received.clone())),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        (format!("http://{}/hook", addr), received)
+    }
+
+    async fn wait_for(received: &Received, count: usize) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while received.lock().unwrap().len() < count {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_delivers_payload_and_retries_failures() {
+        let (url, received) = spawn_endpoint(1).await;
+        let (sender, receiver) = WebhookSender::channel(16);
+        WebhookDispatcher::new(url, receiver)
+            .unwrap()
+            .with_retry_base_delay(Duration::from_millis(1))
+            .spawn();
+
+        sender.notify(SlotConfirmed {
+            slot: 42,
+            block_time: Some(1_700_000_000),
+        });
+        sender.notify(SlotConfirmed {
+            slot: 43,
+            block_time: None,
+        });
+        wait_for(&received, 3).await;
+
+        let expected = serde_json::json!({"slot": 42, "block_time": 1_700_000_000});
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![
+                expected.clone(),
+                expected,
+                serde_json::json!({"slot": 43, "block_time": null}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (url, received) = spawn_endpoint(usize::MAX).await;
+        let (sender, receiver) = WebhookSender::channel(16);
+        WebhookDispatcher::new(url, receiver)
+            .unwrap()
+            .with_max_attempts(2)
+            .with_retry_base_delay(Duration::from_millis(1))
+            .spawn();
+
+        sender.notify(SlotConfirmed {
+            slot: 1,
+            block_time: None,
+        });
+        sender.notify(SlotConfirmed {
+            slot: 2,
+            block_time: None,
+        });
+        wait_for(&received, 4).await;
+
+        let slots: Vec<_> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|body| body["slot"].as_u64().unwrap())
+            .collect();
+        assert_eq!(slots, vec![1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_full_queue_drops_instead_of_blocking() {
+        let (sender, mut receiver) = WebhookSender::channel(1);
+        for slot in 0..3 {
+            sender.notify(SlotConfirmed {
+                slot,
+                block_time: None,
+            });
+        }
+
+        assert_eq!(receiver.try_recv().unwrap().slot, 0);
+        assert!(receiver.try_recv().is_err());
+    }
+}